@@ -1,35 +1,152 @@
-use crate::{config::Config, error::AstudiosError, model::AndroidStudioReleasesList};
-use reqwest::blocking::Client;
-use std::time::Duration;
+use crate::{
+    config::{Config, NetworkSettings},
+    error::AstudiosError,
+    model::AndroidStudioReleasesList,
+};
+use flate2::read::MultiGzDecoder;
+use reqwest::{blocking::Client, header::CONTENT_TYPE};
+use std::{io::Read, sync::OnceLock};
+
+/// Leading bytes of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Process-wide feed format override (set from the `--feed-format` flag)
+static FEED_FORMAT_OVERRIDE: OnceLock<FeedFormat> = OnceLock::new();
+
+/// Encoding of the releases feed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    /// JSON when the server says so or the URL ends in `.json`, XML otherwise
+    #[default]
+    Auto,
+    /// The JetBrains XML feed
+    Xml,
+    /// The same releases as JSON, e.g. `{"@version": "1.0", "item": [...]}`
+    Json,
+}
+
+impl FeedFormat {
+    /// Override the feed format for every client created by this process
+    pub fn set_override(format: FeedFormat) {
+        let _ = FEED_FORMAT_OVERRIDE.set(format);
+    }
+
+    /// The feed format in effect for this process
+    pub fn current() -> FeedFormat {
+        FEED_FORMAT_OVERRIDE.get().copied().unwrap_or_default()
+    }
+
+    /// Settle `Auto` from what is known about a response: its content type
+    /// and the URL it came from
+    pub fn resolve(self, content_type: Option<&str>, url: &str) -> FeedFormat {
+        if self != FeedFormat::Auto {
+            return self;
+        }
+        let json_type = content_type.is_some_and(|content_type| {
+            let mime = content_type.split(';').next().unwrap_or_default().trim();
+            mime.eq_ignore_ascii_case("application/json") || mime.ends_with("+json")
+        });
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        if json_type || path.to_ascii_lowercase().ends_with(".json") {
+            FeedFormat::Json
+        } else if content_type.is_some_and(|content_type| content_type.contains("xml")) {
+            FeedFormat::Xml
+        } else {
+            FeedFormat::Auto
+        }
+    }
+}
 
 /// HTTP client for interacting with JetBrains API
 pub struct ApiClient {
     client: Client,
+    feed_url: String,
+    feed_format: FeedFormat,
 }
 
 impl ApiClient {
     /// Create a new API client with default configuration
     pub fn new() -> Result<Self, AstudiosError> {
-        Self::with_timeout(Config::NETWORK_TIMEOUT_SECS)
+        Self::with_timeout(
+            NetworkSettings::current().request_timeout_secs(Config::NETWORK_TIMEOUT_SECS),
+        )
     }
 
     /// Create a new API client with custom timeout
     pub fn with_timeout(seconds: u64) -> Result<Self, AstudiosError> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(seconds))
-            .user_agent(Config::user_agent())
-            .build()?;
+        let client = Config::http_client_builder(seconds).build()?;
+
+        Ok(Self {
+            client,
+            feed_url: Config::releases_feed_url(),
+            feed_format: FeedFormat::current(),
+        })
+    }
+
+    /// Parse the feed as `format` instead of the process-wide setting
+    pub fn with_feed_format(mut self, format: FeedFormat) -> Self {
+        self.feed_format = format;
+        self
+    }
 
-        Ok(Self { client })
+    /// Fetch the releases list from `url` instead of the JetBrains feed
+    pub fn with_feed_url(mut self, url: impl Into<String>) -> Self {
+        self.feed_url = url.into();
+        self
     }
 
     /// Fetch Android Studio releases from JetBrains API
     pub fn fetch_releases(&self) -> Result<AndroidStudioReleasesList, AstudiosError> {
-        let response = self.client.get(Config::RELEASES_FEED_URL).send()?;
+        let response = self.client.get(&self.feed_url).send()?.error_for_status()?;
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let format = self
+            .feed_format
+            .resolve(content_type.as_deref(), &self.feed_url);
         let bytes = response.bytes()?;
 
-        let text = std::str::from_utf8(&bytes)?;
-        let content: AndroidStudioReleasesList = quick_xml::de::from_str(text)?;
+        Self::parse_releases_as(&bytes, format)
+    }
+
+    /// Parse a releases feed body, telling XML and JSON apart by its first character
+    pub fn parse_releases(bytes: &[u8]) -> Result<AndroidStudioReleasesList, AstudiosError> {
+        Self::parse_releases_as(bytes, FeedFormat::Auto)
+    }
+
+    /// Parse a releases feed body in the given format; with `Auto`, a body
+    /// starting with `{` is JSON and anything else XML.
+    ///
+    /// The blocking client does not decompress responses, so a body a server or
+    /// mirror sent gzip-encoded is recognized by its magic bytes and inflated here.
+    pub fn parse_releases_as(
+        bytes: &[u8],
+        format: FeedFormat,
+    ) -> Result<AndroidStudioReleasesList, AstudiosError> {
+        let mut inflated = Vec::new();
+        let bytes = if bytes.starts_with(&GZIP_MAGIC) {
+            MultiGzDecoder::new(bytes)
+                .read_to_end(&mut inflated)
+                .map_err(|e| {
+                    AstudiosError::Parse(format!("Failed to decompress the releases feed: {e}"))
+                })?;
+            &inflated
+        } else {
+            bytes
+        };
+
+        let text = std::str::from_utf8(bytes)?;
+        let format = match format {
+            FeedFormat::Auto if text.trim_start().starts_with('{') => FeedFormat::Json,
+            FeedFormat::Auto => FeedFormat::Xml,
+            format => format,
+        };
+        let content: AndroidStudioReleasesList = match format {
+            FeedFormat::Json => serde_json::from_str(text)?,
+            _ => quick_xml::de::from_str(text)?,
+        };
 
         Ok(content)
     }
@@ -0,0 +1,281 @@
+use crate::{
+    config::Config,
+    downloader::{ARIA2_EXIT_DISK_FULL, Downloader},
+    error::AstudiosError,
+    events::{self, InstallEvent},
+    progress,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use reqwest::blocking::Client;
+use serde_json::{Value, json};
+use std::{
+    fs,
+    net::TcpListener,
+    path::Path,
+    process::{Child, Command, Stdio},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Daemon that aria2 downloads go through while a batch operation runs. The
+/// lock is only held to clone the handle, so parallel downloads never wait on
+/// each other; the daemon shuts down once the guard and every download drop it.
+static SHARED_DAEMON: Mutex<Option<Arc<Aria2Daemon>>> = Mutex::new(None);
+
+/// An `aria2c --enable-rpc` process driven over JSON-RPC, so several downloads
+/// share one process and its connection pool instead of spawning aria2 each time
+pub struct Aria2Daemon {
+    /// The daemon process, if this client started it
+    child: Option<Child>,
+    endpoint: String,
+    secret: String,
+    client: Client,
+}
+
+/// Keeps a daemon registered for [`Aria2Daemon::download_shared`] and shuts it
+/// down when dropped
+pub struct SharedAria2Daemon(());
+
+impl Drop for SharedAria2Daemon {
+    fn drop(&mut self) {
+        if let Ok(mut shared) = SHARED_DAEMON.lock() {
+            shared.take();
+        }
+    }
+}
+
+impl Aria2Daemon {
+    /// Start an RPC daemon listening on a free local port
+    pub fn start(aria2_path: &Path) -> Result<Self, AstudiosError> {
+        let port = TcpListener::bind(("127.0.0.1", 0))?.local_addr()?.port();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |age| age.subsec_nanos());
+        let secret = format!("{:x}{nanos:x}", std::process::id());
+
+        let mut cmd = Command::new(aria2_path);
+        cmd.arg("--enable-rpc=true")
+            .arg("--rpc-listen-all=false")
+            .arg(format!("--rpc-listen-port={port}"))
+            .arg(format!("--rpc-secret={secret}"))
+            // Exit along with astudios even if it is killed before shutting us down
+            .arg(format!("--stop-with-process={}", std::process::id()))
+            .arg("--console-log-level=error")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        Downloader::apply_aria2_network_settings(&mut cmd);
+        let mut child = cmd.spawn()?;
+
+        let endpoint = format!("http://127.0.0.1:{port}/jsonrpc");
+        let deadline = Instant::now() + Duration::from_secs(Config::ARIA2_RPC_STARTUP_TIMEOUT_SECS);
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Err(AstudiosError::Download(format!(
+                    "aria2 RPC daemon exited during startup ({status})"
+                )));
+            }
+            match Self::connect(&endpoint, &secret) {
+                Ok(mut daemon) => {
+                    daemon.child = Some(child);
+                    return Ok(daemon);
+                }
+                Err(e) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(e);
+                }
+                Err(_) => thread::sleep(Duration::from_millis(100)),
+            }
+        }
+    }
+
+    /// Connect to a daemon that is already running at `endpoint`
+    pub fn connect(endpoint: &str, secret: &str) -> Result<Self, AstudiosError> {
+        let client = Client::builder()
+            .no_proxy()
+            .timeout(Duration::from_secs(Config::NETWORK_TIMEOUT_SECS))
+            .build()?;
+        let daemon = Self {
+            child: None,
+            endpoint: endpoint.to_string(),
+            secret: secret.to_string(),
+            client,
+        };
+        daemon.call("aria2.getVersion", Vec::new())?;
+        Ok(daemon)
+    }
+
+    /// Register a daemon so aria2 downloads in this process go through it until
+    /// the returned guard is dropped
+    pub fn share(self) -> SharedAria2Daemon {
+        if let Ok(mut shared) = SHARED_DAEMON.lock() {
+            *shared = Some(Arc::new(self));
+        }
+        SharedAria2Daemon(())
+    }
+
+    /// Download through the shared daemon, stopping when `cancel` is set;
+    /// `None` when no daemon is shared
+    pub fn download_shared(
+        url: &str,
+        destination: &Path,
+        cancel: &AtomicBool,
+    ) -> Option<Result<(), AstudiosError>> {
+        let daemon = SHARED_DAEMON.lock().ok()?.clone()?;
+        Some(daemon.download_cancellable(url, destination, cancel))
+    }
+
+    /// Download `url` to `destination` and wait until it finishes
+    pub fn download(&self, url: &str, destination: &Path) -> Result<(), AstudiosError> {
+        self.download_cancellable(url, destination, &AtomicBool::new(false))
+    }
+
+    /// Like [`download`](Self::download), but removes the download from the
+    /// daemon and fails with [`AstudiosError::Cancelled`] once `cancel` is set.
+    /// What was downloaded so far stays on disk for aria2 to resume.
+    pub fn download_cancellable(
+        &self,
+        url: &str,
+        destination: &Path,
+        cancel: &AtomicBool,
+    ) -> Result<(), AstudiosError> {
+        let dir = destination.parent().unwrap_or_else(|| Path::new("."));
+        let out = destination
+            .file_name()
+            .ok_or(AstudiosError::Path(
+                "Invalid destination filename".to_string(),
+            ))?
+            .to_string_lossy();
+        let options = json!({
+            "dir": dir.to_string_lossy(),
+            "out": out,
+            "continue": "true",
+            "max-connection-per-server": Config::ARIA2_MAX_CONNECTIONS.to_string(),
+            "split": Config::ARIA2_MAX_CONNECTIONS.to_string(),
+            "min-split-size": Config::ARIA2_MIN_SPLIT_SIZE,
+            "max-tries": Config::MAX_DOWNLOAD_RETRIES.to_string(),
+            "retry-wait": Config::DOWNLOAD_RETRY_WAIT_SECS.to_string(),
+        });
+        let gid = self.call("aria2.addUri", vec![json!([url]), options])?;
+        let gid = gid.as_str().ok_or_else(|| {
+            AstudiosError::Parse(format!("Unexpected aria2.addUri result: {gid}"))
+        })?;
+
+        progress::restore_terminal_on_exit();
+        let progress = ProgressBar::new(0);
+        progress.set_style(
+            ProgressStyle::default_bar()
+                .template(
+                    "      [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta}) @ {bytes_per_sec}",
+                )
+                .unwrap()
+                .progress_chars("█▉▊▋▌▍▎▏ "),
+        );
+        let mut last_pct = None;
+
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                let _ = self.call("aria2.remove", vec![json!(gid)]);
+                progress.abandon();
+                return Err(AstudiosError::Cancelled(format!("download of {url}")));
+            }
+
+            let status = self.call(
+                "aria2.tellStatus",
+                vec![
+                    json!(gid),
+                    json!([
+                        "status",
+                        "totalLength",
+                        "completedLength",
+                        "errorCode",
+                        "errorMessage"
+                    ]),
+                ],
+            )?;
+            let field = |name: &str| status[name].as_str().unwrap_or_default().to_string();
+            let number = |name: &str| field(name).parse::<u64>().unwrap_or(0);
+
+            let (completed, total) = (number("completedLength"), number("totalLength"));
+            progress.set_length(total);
+            progress.set_position(completed);
+            let pct = (completed.min(total) * 100).checked_div(total);
+            // Completion is reported once the download has been verified
+            if let Some(pct) = pct.map(|pct| pct as u8)
+                && pct < 100
+                && last_pct != Some(pct)
+            {
+                last_pct = Some(pct);
+                events::emit(InstallEvent::Download { pct });
+            }
+
+            match field("status").as_str() {
+                "complete" => {
+                    progress.finish_and_clear();
+                    return Ok(());
+                }
+                "error" | "removed" => {
+                    progress.abandon();
+                    if field("errorCode").parse() == Ok(ARIA2_EXIT_DISK_FULL) {
+                        let written = fs::metadata(destination).map(|m| m.len()).unwrap_or(0);
+                        return Err(Downloader::out_of_space(destination, written));
+                    }
+                    return Err(AstudiosError::Download(format!(
+                        "aria2 download failed: {}",
+                        field("errorMessage")
+                    )));
+                }
+                _ => thread::sleep(Duration::from_millis(Config::ARIA2_RPC_POLL_INTERVAL_MS)),
+            }
+        }
+    }
+
+    /// Call a JSON-RPC method, returning its result
+    fn call(&self, method: &str, params: Vec<Value>) -> Result<Value, AstudiosError> {
+        let mut all_params = vec![json!(format!("token:{}", self.secret))];
+        all_params.extend(params);
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": "astudios",
+            "method": method,
+            "params": all_params,
+        });
+
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .body(serde_json::to_vec(&request)?)
+            .send()?;
+        let mut reply: Value = serde_json::from_slice(&response.bytes()?)?;
+        if let Some(error) = reply.get("error") {
+            return Err(AstudiosError::Download(format!(
+                "aria2 RPC {method} failed: {}",
+                error["message"].as_str().unwrap_or("unknown error")
+            )));
+        }
+        Ok(reply["result"].take())
+    }
+}
+
+impl Drop for Aria2Daemon {
+    fn drop(&mut self) {
+        let Some(mut child) = self.child.take() else {
+            return;
+        };
+
+        let _ = self.call("aria2.shutdown", Vec::new());
+        let deadline = Instant::now() + Duration::from_secs(Config::ARIA2_RPC_STARTUP_TIMEOUT_SECS);
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{blobs, error::AstudiosError, model::InstalledAndroidStudio};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Signer of a code-signed bundle, as reported by `codesign -dvv`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct CodeSignature {
+    /// Bundle identifier the signature was made for (e.g. "com.google.android.studio")
+    pub identifier: Option<String>,
+    /// Certificate chain, leaf first (e.g. "Developer ID Application: Google LLC (EQHXZ8M8AV)")
+    pub authority: Vec<String>,
+    /// Apple team identifier of the signer
+    pub team_id: Option<String>,
+}
+
+impl CodeSignature {
+    /// Read the signature of the bundle at `app_path`.
+    ///
+    /// Returns `None` for unsigned bundles and where `codesign` is unavailable.
+    pub fn read(app_path: &Path) -> Option<Self> {
+        let output = Command::new("codesign")
+            .arg("-dvv")
+            .arg(app_path)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        // codesign writes the details to stderr
+        Some(Self::parse(&String::from_utf8_lossy(&output.stderr)))
+    }
+
+    /// Parse the `Key=Value` lines printed by `codesign -dvv`
+    pub fn parse(details: &str) -> Self {
+        let mut signature = Self::default();
+        for (key, value) in details.lines().filter_map(|line| line.split_once('=')) {
+            let value = value.trim().to_string();
+            match key.trim() {
+                "Identifier" => signature.identifier = Some(value),
+                "Authority" => signature.authority.push(value),
+                "TeamIdentifier" if value != "not set" => signature.team_id = Some(value),
+                _ => {}
+            }
+        }
+        signature
+    }
+}
+
+/// Integrity record of one installed bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BundleAudit {
+    pub path: PathBuf,
+    pub version: String,
+    pub build: String,
+    /// Signer of the bundle; `None` if it is unsigned or could not be checked
+    pub signature: Option<CodeSignature>,
+    /// SHA-256 of `Contents/Info.plist`
+    pub info_plist_sha256: String,
+    /// SHA-256 of every regular file, keyed by path relative to the bundle
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<BTreeMap<String, String>>,
+}
+
+impl BundleAudit {
+    /// Audit an installed bundle, hashing every file when `file_manifest` is set
+    pub fn of(
+        installation: &InstalledAndroidStudio,
+        file_manifest: bool,
+    ) -> Result<Self, AstudiosError> {
+        let path = &installation.path;
+        let files = if file_manifest {
+            Some(file_manifest_of(path)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            path: path.clone(),
+            version: installation.extract_detailed_version(),
+            build: installation.identifier(),
+            signature: CodeSignature::read(path),
+            info_plist_sha256: blobs::sha256_hex(&path.join("Contents").join("Info.plist"))?,
+            files,
+        })
+    }
+}
+
+/// Audit of every installed bundle at one point in time, for diffing against
+/// later audits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditReport {
+    /// Seconds since the Unix epoch at which the audit was taken
+    pub generated_at: u64,
+    pub bundles: Vec<BundleAudit>,
+}
+
+impl AuditReport {
+    /// Audit `installations`, hashing every file when `file_manifest` is set
+    pub fn new(
+        installations: &[InstalledAndroidStudio],
+        file_manifest: bool,
+    ) -> Result<Self, AstudiosError> {
+        let generated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |age| age.as_secs());
+        let bundles = installations
+            .iter()
+            .map(|installation| BundleAudit::of(installation, file_manifest))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            generated_at,
+            bundles,
+        })
+    }
+}
+
+/// SHA-256 of every regular file below `bundle`, keyed by relative path.
+/// Symlinks are not followed, so files outside the bundle are never hashed.
+pub fn file_manifest_of(bundle: &Path) -> Result<BTreeMap<String, String>, AstudiosError> {
+    let mut files = Vec::new();
+    collect_files(bundle, &mut files)?;
+    files.sort();
+
+    let digests = blobs::sha256_hex_many(&files)?;
+    Ok(files
+        .iter()
+        .zip(digests)
+        .map(|(file, digest)| {
+            let relative = file.strip_prefix(bundle).unwrap_or(file);
+            (relative.to_string_lossy().into_owned(), digest)
+        })
+        .collect())
+}
+
+/// Recursively collect the regular files below `dir`
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), AstudiosError> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
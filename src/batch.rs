@@ -0,0 +1,42 @@
+use crate::error::AstudiosError;
+use colored::Colorize;
+
+/// Run `action` for each item, stopping at the first failure unless
+/// `keep_going` is set, in which case all failures are reported at the end
+/// and the returned error names every item that failed
+pub fn run_batch(
+    items: &[String],
+    keep_going: bool,
+    mut action: impl FnMut(&str) -> Result<(), AstudiosError>,
+) -> Result<(), AstudiosError> {
+    let mut failures = Vec::new();
+    for item in items {
+        match action(item) {
+            Ok(()) => {}
+            Err(e) if keep_going => failures.push((item, e)),
+            Err(e) => return Err(e),
+        }
+    }
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!();
+    eprintln!(
+        "{} {} of {} failed:",
+        "❌".red(),
+        failures.len(),
+        items.len()
+    );
+    for (item, e) in &failures {
+        eprintln!("   {} {e}", format!("{item}:").bold());
+    }
+    let failed: Vec<&str> = failures.iter().map(|(item, _)| item.as_str()).collect();
+    Err(AstudiosError::General(format!(
+        "{} of {} operations failed: {}",
+        failures.len(),
+        items.len(),
+        failed.join(", ")
+    )))
+}
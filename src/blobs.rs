@@ -0,0 +1,221 @@
+use crate::{config::Config, error::AstudiosError};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fs, io,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+};
+
+/// Content-addressed storage for downloaded archives.
+///
+/// Archives live under `cache/blobs/<sha256>` and are hardlinked (or, across
+/// filesystems, symlinked) into each per-version directory, so the same
+/// artifact is only downloaded and stored once.
+pub struct BlobStore {
+    dir: PathBuf,
+}
+
+impl BlobStore {
+    /// Create a blob store in the default cache location
+    pub fn new() -> Result<Self, AstudiosError> {
+        Self::with_dir(Config::blobs_dir()?)
+    }
+
+    /// Create a blob store in a custom directory
+    pub fn with_dir(dir: PathBuf) -> Result<Self, AstudiosError> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Path of the blob with the given SHA-256 digest
+    pub fn blob_path(&self, sha256: &str) -> PathBuf {
+        self.dir.join(sha256.to_ascii_lowercase())
+    }
+
+    /// Link an already stored blob to `destination`.
+    ///
+    /// Returns `false` if no blob with this digest exists.
+    pub fn link_existing(&self, sha256: &str, destination: &Path) -> Result<bool, AstudiosError> {
+        let blob = self.blob_path(sha256);
+        if !blob.is_file() {
+            return Ok(false);
+        }
+
+        Self::link(&blob, destination)?;
+        Ok(true)
+    }
+
+    /// Move a downloaded file into the store and link it back in place.
+    ///
+    /// Returns the SHA-256 digest the file is stored under.
+    pub fn adopt(&self, file: &Path) -> Result<String, AstudiosError> {
+        let sha256 = sha256_hex(file)?;
+        let blob = self.blob_path(&sha256);
+
+        if blob.is_file() {
+            // Identical content is already stored; drop the duplicate copy
+            fs::remove_file(file)?;
+        } else if fs::rename(file, &blob).is_err() {
+            // Different filesystem: copy instead of moving
+            fs::copy(file, &blob)?;
+            fs::remove_file(file)?;
+        }
+
+        Self::link(&blob, file)?;
+        Ok(sha256)
+    }
+
+    /// Delete blobs that are no longer linked from anywhere.
+    ///
+    /// A blob is referenced if it has another hardlink, or if a symlink under
+    /// one of `roots` points at it. Returns the number of blobs removed.
+    pub fn prune(&self, roots: &[&Path]) -> Result<usize, AstudiosError> {
+        let unreferenced = self.unreferenced(roots, &[])?;
+        for blob in &unreferenced {
+            fs::remove_file(blob)?;
+        }
+
+        Ok(unreferenced.len())
+    }
+
+    /// Blobs that [`prune`](Self::prune) would delete once the directories in
+    /// `removed` are gone, without deleting anything
+    pub fn unreferenced(
+        &self,
+        roots: &[&Path],
+        removed: &[&Path],
+    ) -> Result<Vec<PathBuf>, AstudiosError> {
+        let mut symlinked = Vec::new();
+        for root in roots {
+            collect_symlink_targets(root, removed, &mut symlinked);
+        }
+        // Hardlinks that disappear along with the removed directories
+        let mut removed_links = HashMap::new();
+        for dir in removed {
+            count_hardlinks(dir, &mut removed_links);
+        }
+
+        let mut unreferenced = Vec::new();
+        for entry in fs::read_dir(&self.dir)?.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let Ok(metadata) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            let gone = removed_links
+                .get(&(metadata.dev(), metadata.ino()))
+                .copied()
+                .unwrap_or(0);
+            if !metadata.is_file() || metadata.nlink().saturating_sub(gone) > 1 {
+                continue;
+            }
+
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if symlinked.contains(&canonical) {
+                continue;
+            }
+
+            unreferenced.push(path);
+        }
+
+        unreferenced.sort();
+        Ok(unreferenced)
+    }
+
+    /// Hardlink `blob` to `destination`, falling back to a symlink
+    fn link(blob: &Path, destination: &Path) -> Result<(), AstudiosError> {
+        if destination.exists() || destination.is_symlink() {
+            fs::remove_file(destination)?;
+        }
+
+        if fs::hard_link(blob, destination).is_err() {
+            std::os::unix::fs::symlink(blob, destination)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Check whether a string is a hex-encoded SHA-256 digest
+pub fn is_sha256(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Parse a checksum given on the command line as `sha256:<hex>` or bare hex,
+/// returning the lowercase hex digest
+pub fn parse_checksum(value: &str) -> Result<String, AstudiosError> {
+    let (algorithm, digest) = value
+        .trim()
+        .split_once(':')
+        .unwrap_or(("sha256", value.trim()));
+    if !algorithm.eq_ignore_ascii_case("sha256") {
+        return Err(AstudiosError::Config(format!(
+            "Unsupported checksum algorithm '{algorithm}'; only sha256 is supported"
+        )));
+    }
+    if !is_sha256(digest) {
+        return Err(AstudiosError::Config(format!(
+            "Invalid SHA-256 checksum '{digest}'; expected 64 hexadecimal characters"
+        )));
+    }
+    Ok(digest.to_ascii_lowercase())
+}
+
+/// Compute the SHA-256 digest of a file, reading it in chunks
+pub fn sha256_hex(path: &Path) -> Result<String, AstudiosError> {
+    let checksum_error = |e: io::Error| {
+        AstudiosError::General(format!("Failed to checksum {}: {e}", path.display()))
+    };
+
+    let mut file = fs::File::open(path).map_err(checksum_error)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher).map_err(checksum_error)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Compute the SHA-256 digests of several files, in the order the files are given
+pub fn sha256_hex_many(paths: &[impl AsRef<Path>]) -> Result<Vec<String>, AstudiosError> {
+    paths.iter().map(|path| sha256_hex(path.as_ref())).collect()
+}
+
+/// Recursively collect the resolved targets of symlinks below `dir`, skipping
+/// the directories in `removed`
+fn collect_symlink_targets(dir: &Path, removed: &[&Path], targets: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_symlink() {
+            if let Ok(target) = path.canonicalize() {
+                targets.push(target);
+            }
+        } else if file_type.is_dir() && !removed.contains(&path.as_path()) {
+            collect_symlink_targets(&path, removed, targets);
+        }
+    }
+}
+
+/// Recursively count the hardlinked files below `dir` by device and inode
+fn count_hardlinks(dir: &Path, links: &mut HashMap<(u64, u64), u64>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+            continue;
+        };
+
+        if metadata.is_dir() {
+            count_hardlinks(&entry.path(), links);
+        } else if metadata.is_file() && metadata.nlink() > 1 {
+            *links.entry((metadata.dev(), metadata.ino())).or_default() += 1;
+        }
+    }
+}
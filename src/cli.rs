@@ -1,4 +1,12 @@
-use clap::{Parser, Subcommand};
+use astudios::{
+    api::FeedFormat,
+    blobs,
+    config::Config,
+    list::CacheMaxAge,
+    model::{BundleNameTemplate, ReleaseChannel},
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::{ffi::OsString, path::PathBuf};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -12,6 +20,48 @@ pub struct Cli {
     pub command: Commands,
 }
 
+/// Network and feed options of the commands that fetch the releases feed or
+/// download archives
+#[derive(Args, Debug, Clone, Default)]
+#[command(next_help_heading = "Network Options")]
+pub struct NetworkArgs {
+    /// Treat the cached version list as fresh for this long (e.g. "30m", "2h", "1d", "0" to always fetch, "never" to never refetch). Defaults to 1d for list and info, 1h for install and download; update always fetches
+    #[arg(long, value_name = "DURATION")]
+    pub max_age: Option<CacheMaxAge>,
+
+    /// Only connect over IPv4, for networks with broken IPv6 routing [env: ASTUDIOS_PREFER_IPV4]
+    #[arg(long)]
+    pub prefer_ipv4: bool,
+
+    /// Retry failed downloads from the mirrors listed in ASTUDIOS_MIRRORS
+    #[arg(long)]
+    pub prefer_mirror_on_failure: bool,
+
+    /// Also trust the certificates in this PEM bundle [env: ASTUDIOS_CA_BUNDLE]
+    #[arg(long, value_name = "PATH")]
+    pub ca_bundle: Option<PathBuf>,
+
+    /// Do not verify TLS certificates (dangerous; prefer --ca-bundle)
+    #[arg(long)]
+    pub insecure: bool,
+
+    /// Seconds allowed to connect to a server before giving up (default: 10)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout_connect: Option<u64>,
+
+    /// Seconds allowed for the release list, or for a built-in download to stall (default: 30 for the release list, 60 for downloads)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout: Option<u64>,
+
+    /// Wait up to this many seconds for the JetBrains API to become reachable
+    #[arg(long, value_name = "SECS")]
+    pub wait_for_network: Option<u64>,
+
+    /// Format of the releases feed; auto picks JSON for a JSON content type or a .json URL
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub feed_format: Option<FeedFormatArg>,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// List all versions of Android Studio that are available to install
@@ -28,6 +78,26 @@ pub enum Commands {
         #[arg(long)]
         canary: bool,
 
+        /// Show only versions from the given release channel
+        #[arg(long, value_enum)]
+        channel: Option<ChannelArg>,
+
+        /// Show only versions built on this IntelliJ platform branch (e.g., 243)
+        #[arg(long, value_name = "BRANCH")]
+        platform_major: Option<u32>,
+
+        /// Show only versions built on exactly this IntelliJ platform build (e.g., 243.22562.218)
+        #[arg(long, value_name = "BUILD")]
+        platform_build: Option<String>,
+
+        /// Show only versions of this major track (e.g., 2024.2)
+        #[arg(long, value_name = "TRACK")]
+        major: Option<String>,
+
+        /// Show only versions that were not in the list before its last refresh
+        #[arg(long)]
+        new: bool,
+
         /// Limit the number of results
         #[arg(short, long)]
         limit: Option<usize>,
@@ -35,6 +105,9 @@ pub enum Commands {
         /// Show download information for all platforms instead of just the current platform
         #[arg(long)]
         all_platforms: bool,
+
+        #[command(flatten)]
+        network: NetworkArgs,
     },
 
     /// Download a specific version of Android Studio
@@ -50,49 +123,279 @@ pub enum Commands {
         #[arg(long)]
         latest_prerelease: bool,
 
+        /// Download this archive URL directly instead of a release from the feed
+        #[arg(long, value_name = "URL", conflicts_with_all = ["version", "latest", "latest_prerelease", "all_platforms", "probe"])]
+        url: Option<String>,
+
         /// The directory to download the archive to. Defaults to ~/.astudios/versions/{version}
         #[arg(long)]
         directory: Option<String>,
+
+        /// Downloader to use; an explicit choice disables the aria2 fallback
+        #[arg(long, value_enum, default_value_t = DownloaderArg::Auto)]
+        downloader: DownloaderArg,
+
+        /// Store the archive once by checksum and link it into the version directory
+        #[arg(long)]
+        dedupe_downloads: bool,
+
+        /// Ask which macOS architecture to use when a release offers several
+        #[arg(long)]
+        select_arch_on_ambiguity: bool,
+
+        /// Download the archives for every platform, e.g. to seed a mirror
+        #[arg(long, conflicts_with = "select_arch_on_ambiguity")]
+        all_platforms: bool,
+
+        /// Verify the archive against this digest instead of the feed's (sha256:<hex>)
+        #[arg(long, value_name = "ALGO:HEX", value_parser = blobs::parse_checksum, conflicts_with = "all_platforms")]
+        checksum: Option<String>,
+
+        /// Only print the download URL, size and checksum without downloading
+        #[arg(long)]
+        probe: bool,
+
+        /// Print the probed download as JSON
+        #[arg(long, requires = "probe")]
+        json: bool,
+
+        #[command(flatten)]
+        network: NetworkArgs,
     },
 
     /// Download and install a specific version of Android Studio
     Install {
-        /// Version to install (e.g., "2024.3.2.14", "Android Studio Meerkat Feature Drop", "2023.3.1 Canary 8")
-        version: Option<String>,
+        /// Versions to install (e.g., "2024.3.2.14", "Android Studio Meerkat Feature Drop", "2023.3.1 Canary 8")
+        #[arg(value_name = "VERSION")]
+        versions: Vec<String>,
 
-        /// Install the latest available version
-        #[arg(long)]
+        /// Install the latest stable release; refuses anything not labeled stable
+        #[arg(long, conflicts_with = "versions")]
         latest: bool,
 
-        /// Custom installation directory (default: /Applications)
+        /// Install the latest pre-release version available (Canary or Beta)
+        #[arg(long, conflicts_with_all = ["versions", "latest"])]
+        latest_prerelease: bool,
+
+        /// Install the archive at this URL, bypassing the feed; pair it with --checksum
+        #[arg(long, value_name = "URL", conflicts_with_all = ["versions", "latest", "latest_prerelease", "only_if_missing"])]
+        url: Option<String>,
+
+        /// Custom installation directory (default: the applications directory)
         #[arg(long, short)]
         directory: Option<String>,
 
+        /// Directory for the "Android Studio.app" symlink (default: /Applications)
+        #[arg(long, value_name = "DIR")]
+        applications_dir: Option<String>,
+
         /// Skip prerequisite checks (not recommended)
         #[arg(long)]
         skip_checks: bool,
+
+        /// Treat prerequisite warnings as errors and refuse to install
+        #[arg(long, conflicts_with = "skip_checks")]
+        strict: bool,
+
+        /// Check the app's code signature inside the disk image before copying it
+        #[arg(long)]
+        verify_signature: bool,
+
+        /// Skip only the network check, for offline installs of a downloaded archive
+        #[arg(long)]
+        no_prereq_network_check: bool,
+
+        /// Do not create or update the "Android Studio.app" symlink
+        #[arg(long)]
+        no_symlink: bool,
+
+        /// Downloader to use; an explicit choice disables the aria2 fallback
+        #[arg(long, value_enum, default_value_t = DownloaderArg::Auto)]
+        downloader: DownloaderArg,
+
+        /// Keep the archive, stored once by checksum, for later reinstalls
+        #[arg(long)]
+        dedupe_downloads: bool,
+
+        /// Verify the archive against this digest instead of the feed's (sha256:<hex>)
+        #[arg(long, value_name = "ALGO:HEX", value_parser = blobs::parse_checksum)]
+        checksum: Option<String>,
+
+        /// Ask which macOS architecture to use when a release offers several
+        #[arg(long)]
+        select_arch_on_ambiguity: bool,
+
+        /// Refuse to install a build that would not run natively, e.g. under Rosetta
+        #[arg(long, conflicts_with = "url")]
+        strict_arch: bool,
+
+        /// Name for the installed bundle, with {version}, {build}, {channel} and {codename} placeholders
+        #[arg(long, value_name = "TEMPLATE")]
+        name_template: Option<BundleNameTemplate>,
+
+        /// Do nothing and succeed if the requested build is already installed
+        #[arg(long)]
+        only_if_missing: bool,
+
+        /// After installing, keep only the N most recently used downloaded archives per major track
+        #[arg(long, value_name = "N")]
+        keep_n_archives: Option<usize>,
+
+        /// Maximum disk images mounted at once (above 1 is at your own risk on macOS)
+        #[arg(long, value_name = "N", default_value_t = Config::DEFAULT_CONCURRENT_EXTRACTIONS as u32, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrent_extractions: u32,
+
+        /// Versions downloaded at once when installing several (default: CPU count, at most 4)
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        parallel: Option<u32>,
+
+        /// Continue with the remaining versions when one fails
+        #[arg(long)]
+        keep_going: bool,
+
+        /// Download several versions through one aria2 RPC daemon instead of one aria2 process each
+        #[arg(long)]
+        aria2_rpc: bool,
+
+        /// Stream progress as JSON records, one per line, on stdout
+        #[arg(long)]
+        json_lines: bool,
+
+        /// Wait for another running astudios operation instead of failing
+        #[arg(long)]
+        wait: bool,
+
+        #[command(flatten)]
+        network: NetworkArgs,
     },
 
     /// Uninstall a version of Android Studio
     Uninstall {
-        /// Version to uninstall
-        version: String,
+        /// Versions to uninstall
+        #[arg(value_name = "VERSION", required = true)]
+        versions: Vec<String>,
+
+        /// Keep the downloaded archive so a later install can reuse it
+        #[arg(long)]
+        keep_files: bool,
+
+        /// Continue with the remaining versions when one fails
+        #[arg(long)]
+        keep_going: bool,
+
+        /// Show what would be removed and how much space it frees, without removing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Wait for another running astudios operation instead of failing
+        #[arg(long)]
+        wait: bool,
+
+        /// Uninstall without asking for confirmation [env: ASTUDIOS_ASSUME_YES]
+        #[arg(short, long)]
+        yes: bool,
     },
 
     /// Change the selected Android Studio
     Use {
         /// Version to switch to
+        #[arg(required_unless_present = "path")]
+        version: Option<String>,
+
+        /// Android Studio bundle installed outside astudios to switch to
+        #[arg(long, value_name = "APP", conflicts_with = "version")]
+        path: Option<PathBuf>,
+
+        /// Directory for the "Android Studio.app" symlink (default: /Applications)
+        #[arg(long, value_name = "DIR")]
+        applications_dir: Option<String>,
+
+        /// Switch the launcher symlink of this channel (e.g. "Android Studio Canary.app"); "per_channel_symlinks": true in preferences.json does so for every bundle
+        #[arg(long, value_enum)]
+        channel: Option<ChannelArg>,
+
+        /// Wait for another running astudios operation instead of failing
+        #[arg(long)]
+        wait: bool,
+    },
+
+    /// Set the channel shown for an installed version when detection gets it wrong
+    SetChannel {
+        /// Installed version to label
         version: String,
+
+        /// Channel to show for the version
+        #[arg(value_enum, required_unless_present = "clear")]
+        channel: Option<ChannelArg>,
+
+        /// Remove the label and go back to detecting the channel
+        #[arg(long, conflicts_with = "channel")]
+        clear: bool,
+
+        /// Wait for another running astudios operation instead of failing
+        #[arg(long)]
+        wait: bool,
     },
 
     /// List the versions of Android Studio that are installed
-    Installed,
+    Installed {
+        /// Show the symlink and the bundles it can point to as a tree
+        #[arg(long)]
+        tree: bool,
+
+        /// Order of the listing
+        #[arg(long, value_enum, default_value_t = InstalledSort::Version)]
+        sort: InstalledSort,
+
+        /// Reverse the order of the listing
+        #[arg(long)]
+        reverse: bool,
+
+        /// Print the installations and their sizes as JSON
+        #[arg(long, conflicts_with = "tree")]
+        json: bool,
+
+        /// Check each bundle's structure and code signature and mark it healthy or not
+        #[arg(long, conflicts_with = "tree")]
+        verify: bool,
+
+        /// Show only versions of this major track (e.g., 2024.2)
+        #[arg(long, value_name = "TRACK")]
+        major: Option<String>,
+
+        /// Group the listing by major track, newest track first
+        #[arg(long, conflicts_with_all = ["tree", "json"])]
+        group: bool,
+
+        /// Also list the versions installed by JetBrains Toolbox
+        #[arg(long)]
+        include_toolbox: bool,
+    },
 
     /// Show which version is currently selected
-    Which,
+    Which {
+        /// Directory for the "Android Studio.app" symlink (default: /Applications)
+        #[arg(long, value_name = "DIR")]
+        applications_dir: Option<String>,
+
+        /// Show the launcher symlink of this channel instead of the stable one
+        #[arg(long, value_enum)]
+        channel: Option<ChannelArg>,
+
+        /// Print the active installation and its launcher symlink as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Without a symlink, also consider the versions installed by JetBrains Toolbox
+        #[arg(long)]
+        include_toolbox: bool,
+    },
 
     /// Update the list of available versions of Android Studio
-    Update,
+    Update {
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
 
     /// Open a project with the currently selected Android Studio
     Open {
@@ -100,4 +403,297 @@ pub enum Commands {
         #[arg(default_value = ".")]
         path: String,
     },
+
+    /// Reveal an astudios directory in the system file manager
+    OpenDir {
+        /// Which directory to open
+        #[arg(value_enum)]
+        target: DirTarget,
+    },
+
+    /// Show the details and release notes of an available version
+    Info {
+        /// Version to describe (e.g., "2024.2.1.11", "Ladybug")
+        version: String,
+
+        /// Open the release notes in the browser
+        #[arg(long)]
+        open: bool,
+
+        /// Print the details as JSON
+        #[arg(long, conflicts_with = "open")]
+        json: bool,
+
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
+
+    /// Check whether a newer stable release than the installed ones is available
+    Outdated {
+        /// Open the release notes of the latest release in the browser
+        #[arg(long)]
+        open: bool,
+
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
+
+    /// Show the active version, installed versions, cache state and available updates
+    Status {
+        /// Print the status as JSON
+        #[arg(long)]
+        json: bool,
+
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
+
+    /// Check whether this system meets the requirements for installing Android Studio
+    Doctor {
+        /// Print the result of every check as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Fail when any check reports a warning, not only on failures
+        #[arg(long)]
+        strict: bool,
+
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
+
+    /// Measure download throughput with each available downloader
+    Benchmark {
+        /// URL to download from (default: the latest stable release's archive)
+        #[arg(long)]
+        url: Option<String>,
+
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
+
+    /// Record each installed bundle's signer and hashes as JSON, for security audits
+    Audit {
+        /// Also record the SHA-256 of every file in each bundle
+        #[arg(long)]
+        sha_manifest: bool,
+
+        /// Write the audit to this file instead of printing it
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Check that installed bundles are complete and validly signed
+    Verify {
+        /// Reinstall the bundles that fail from the releases feed, keeping their paths
+        #[arg(long)]
+        repair: bool,
+
+        /// Wait for another running astudios operation instead of failing
+        #[arg(long, requires = "repair")]
+        wait: bool,
+
+        /// Reinstall with --repair without asking for confirmation [env: ASTUDIOS_ASSUME_YES]
+        #[arg(short, long)]
+        yes: bool,
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
+
+    /// Show the recent install, uninstall, use and set-channel operations
+    History {
+        /// Number of entries to show
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+
+        /// Print the entries as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Inspect the astudios configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Manage a `studio` command that launches the selected Android Studio
+    Shim {
+        /// Create the `studio` command
+        #[arg(long, conflicts_with = "uninstall")]
+        install: bool,
+
+        /// Remove the `studio` command
+        #[arg(long)]
+        uninstall: bool,
+
+        /// Directory for the `studio` command (default: ~/.local/bin)
+        #[arg(long, value_name = "DIR")]
+        bin_dir: Option<PathBuf>,
+
+        // Run the selected launcher; this is what the installed shim calls
+        #[arg(long, hide = true, conflicts_with_all = ["install", "uninstall"])]
+        exec: bool,
+
+        // Arguments forwarded to the launcher
+        #[arg(last = true, hide = true)]
+        args: Vec<OsString>,
+    },
+}
+
+impl Commands {
+    /// For commands that modify installations, whether to wait for the
+    /// operation lock; `None` for read-only commands that need no lock
+    pub fn lock_wait(&self) -> Option<bool> {
+        match self {
+            Commands::Install { wait, .. }
+            | Commands::Uninstall {
+                wait,
+                dry_run: false,
+                ..
+            }
+            | Commands::Use { wait, .. }
+            | Commands::SetChannel { wait, .. }
+            | Commands::Verify {
+                wait, repair: true, ..
+            } => Some(*wait),
+            _ => None,
+        }
+    }
+
+    /// The downloader chosen with `--downloader`, for commands that download
+    pub fn downloader(&self) -> Option<DownloaderArg> {
+        match self {
+            Commands::Download { downloader, .. } | Commands::Install { downloader, .. } => {
+                Some(*downloader)
+            }
+            _ => None,
+        }
+    }
+
+    /// The network options of commands that fetch the feed or download archives
+    pub fn network(&self) -> Option<&NetworkArgs> {
+        match self {
+            Commands::List { network, .. }
+            | Commands::Download { network, .. }
+            | Commands::Install { network, .. }
+            | Commands::Update { network }
+            | Commands::Info { network, .. }
+            | Commands::Outdated { network, .. }
+            | Commands::Status { network, .. }
+            | Commands::Doctor { network, .. }
+            | Commands::Benchmark { network, .. }
+            | Commands::Verify { network, .. }
+            | Commands::Config {
+                action: ConfigAction::Check { network },
+            } => Some(network),
+            _ => None,
+        }
+    }
+
+    /// Whether `-y`/`--yes` was given to a command that asks for confirmation
+    pub fn assume_yes(&self) -> bool {
+        match self {
+            Commands::Uninstall { yes, .. } | Commands::Verify { yes, .. } => *yes,
+            _ => false,
+        }
+    }
+
+    /// Whether the command talks to the JetBrains API or download servers
+    pub fn uses_network(&self) -> bool {
+        matches!(
+            self,
+            Commands::List { .. }
+                | Commands::Download { .. }
+                | Commands::Install { .. }
+                | Commands::Update { .. }
+                | Commands::Info { .. }
+                | Commands::Outdated { .. }
+                | Commands::Benchmark { .. }
+                | Commands::Verify { repair: true, .. }
+        )
+    }
+}
+
+/// Actions of the `config` command
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Check the configured directories, URLs and settings for problems
+    Check {
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
+}
+
+/// Orders accepted by `installed --sort`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstalledSort {
+    /// Newest version first
+    Version,
+    /// Most recently installed first
+    Date,
+    /// Alphabetical by bundle path
+    Path,
+}
+
+/// Release channel names accepted on the command line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelArg {
+    Release,
+    Beta,
+    Canary,
+    Rc,
+    Patch,
+    FeatureDrop,
+}
+
+impl From<ChannelArg> for ReleaseChannel {
+    fn from(channel: ChannelArg) -> Self {
+        match channel {
+            ChannelArg::Release => ReleaseChannel::Release,
+            ChannelArg::Beta => ReleaseChannel::Beta,
+            ChannelArg::Canary => ReleaseChannel::Canary,
+            ChannelArg::Rc => ReleaseChannel::ReleaseCandidate,
+            ChannelArg::Patch => ReleaseChannel::Patch,
+            ChannelArg::FeatureDrop => ReleaseChannel::FeatureDrop,
+        }
+    }
+}
+
+/// Releases feed formats accepted on the command line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormatArg {
+    Auto,
+    Xml,
+    Json,
+}
+
+impl From<FeedFormatArg> for FeedFormat {
+    fn from(format: FeedFormatArg) -> Self {
+        match format {
+            FeedFormatArg::Auto => FeedFormat::Auto,
+            FeedFormatArg::Xml => FeedFormat::Xml,
+            FeedFormatArg::Json => FeedFormat::Json,
+        }
+    }
+}
+
+/// Downloader selection accepted on the command line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloaderArg {
+    /// aria2 when available, falling back to the built-in client if it fails
+    Auto,
+    /// aria2 only
+    Aria2,
+    /// The built-in HTTP client only
+    Reqwest,
+}
+
+/// Directories that `open-dir` can reveal
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirTarget {
+    Versions,
+    Cache,
+    Config,
+    Apps,
 }
@@ -1,15 +1,250 @@
-use crate::cli::{Cli, Commands};
+use crate::cli::{Cli, Commands, ConfigAction, DirTarget, DownloaderArg, InstalledSort};
 use astudios::{
-    config::Config,
+    api::FeedFormat,
+    aria2_rpc::{Aria2Daemon, SharedAria2Daemon},
+    audit::AuditReport,
+    batch,
+    blobs::{self, BlobStore},
+    config::{Config, NetworkSettings},
+    confirm,
+    detector::{CheckStatus, SystemDetector},
     downloader::Downloader,
     error::AstudiosError,
-    installer::Installer,
-    list::AndroidStudioLister,
-    model::{AndroidStudio, InstalledAndroidStudio, ReleaseChannel},
+    events::{self, InstallEvent},
+    history::{self, History, HistoryAction, HistoryEntry, HistoryResult},
+    installer::{ExtractionSemaphore, Installer},
+    json_file::JsonFile,
+    list::{self, AndroidStudioLister, CacheMaxAge},
+    lock::OperationLock,
+    model::{
+        self, AndroidStudio, BundleNameTemplate, Download, InstalledAndroidStudio, MacArch,
+        ReleaseChannel, major_track, major_track_key, needs_rosetta,
+    },
+    output::HumanOutput,
+    preferences::Preferences,
     progress::ProgressReporter,
+    say, sayln,
+    self_update::SelfUpdateCheck,
+    shim::Shim,
+    sizes::SizeCache,
+    state::{InstallCheckpoint, State},
 };
 use colored::Colorize;
-use std::{fs, path::Path, path::PathBuf, process::Command};
+use indicatif::HumanBytes;
+use serde::Serialize;
+use std::{
+    ffi::{OsStr, OsString},
+    fs,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// Shown next to releases older than [`Config::outdated_after_months`]
+const OUTDATED_NOTE: &str = "(outdated — consider upgrading)";
+
+/// Machine-readable summary printed by `status --json`
+#[derive(Serialize)]
+struct StatusReport {
+    active: Option<StatusVersion>,
+    installed_count: usize,
+    installed_size_bytes: u64,
+    cache: CacheStatus,
+    latest_release: Option<StatusVersion>,
+    update_available: Option<bool>,
+    /// Installations whose archive had no checksum to verify against
+    unverified_installs: usize,
+    /// Newer astudios release, when the opt-in self-update check found one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    astudios_update: Option<String>,
+    /// Installations of releases older than the outdated threshold
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    outdated_installs: Vec<StatusVersion>,
+}
+
+#[derive(Serialize)]
+struct StatusVersion {
+    version: String,
+    build: String,
+    path: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct CacheStatus {
+    path: PathBuf,
+    age_secs: Option<u64>,
+    fresh: bool,
+}
+
+/// Machine-readable listing printed by `installed --json`
+#[derive(Serialize)]
+struct InstalledReport {
+    installations: Vec<InstalledEntry>,
+    total_size_bytes: u64,
+}
+
+#[derive(Serialize)]
+struct InstalledEntry {
+    version: String,
+    build: String,
+    path: PathBuf,
+    active: bool,
+    size_bytes: u64,
+    /// CPU architectures of the launcher, e.g. ["x86_64", "arm64"]; absent when
+    /// they could not be determined
+    #[serde(skip_serializing_if = "Option::is_none")]
+    architectures: Option<Vec<String>>,
+    /// Whether JetBrains Toolbox manages the bundle; absent without `--include-toolbox`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    toolbox: Option<bool>,
+    /// Whether the bundle passed `--verify`; absent without the flag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    healthy: Option<bool>,
+    /// Why the bundle failed `--verify`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    problem: Option<String>,
+}
+
+/// Machine-readable result printed by `which --json`
+#[derive(Serialize)]
+struct WhichReport {
+    active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    /// Where the launcher symlink points, as stored in the link; set even when
+    /// the target no longer exists
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symlink_target: Option<PathBuf>,
+    /// Whether the launcher symlink selects the installation, rather than it
+    /// being the fallback used when there is no symlink
+    #[serde(skip_serializing_if = "Option::is_none")]
+    managed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    short_version: Option<String>,
+    /// Whether JetBrains Toolbox manages the installation; absent without `--include-toolbox`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    toolbox: Option<bool>,
+}
+
+/// Machine-readable result printed by `download --probe --json`
+#[derive(Serialize)]
+struct ProbeReport<'a> {
+    name: &'a str,
+    version: &'a str,
+    build: &'a str,
+    platform_build: &'a str,
+    platform_version: &'a str,
+    downloads: Vec<ProbeDownload<'a>>,
+}
+
+#[derive(Serialize)]
+struct ProbeDownload<'a> {
+    platform: &'static str,
+    url: &'a str,
+    size: &'a str,
+    checksum: &'a str,
+}
+
+/// Machine-readable details printed by `info --json`
+#[derive(Serialize)]
+struct InfoReport<'a> {
+    name: &'a str,
+    version: &'a str,
+    build: &'a str,
+    channel: &'a str,
+    /// Whether no release in the same channel is newer
+    latest_in_channel: bool,
+    platform_build: &'a str,
+    platform_version: &'a str,
+    date: &'a str,
+    outdated: bool,
+    /// The macOS download for this Mac; absent when the release has none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    download: Option<ProbeDownload<'a>>,
+}
+
+/// Download behavior shared by the download and install commands
+#[derive(Clone)]
+struct DownloadOptions {
+    /// Downloader forced on the command line; `None` auto-detects
+    downloader: Option<Downloader>,
+    dedupe_downloads: bool,
+    select_arch_on_ambiguity: bool,
+    /// Digest from `--checksum`, verified instead of the feed's
+    checksum: Option<String>,
+    /// Check the bundle's signature in the mounted image before copying it
+    verify_signature: bool,
+    /// Bundle naming scheme from `--name-template`, overriding the configured one
+    name_template: Option<BundleNameTemplate>,
+    /// Succeed without installing when the release's build is already installed
+    only_if_missing: bool,
+    /// Archives to keep per major track after installing, from `--keep-n-archives`
+    keep_archives: Option<usize>,
+    /// Refuse builds that are not native to this Mac, from `--strict-arch`
+    strict_arch: bool,
+}
+
+/// Which available releases `list` shows
+struct ListFilters {
+    release: bool,
+    beta: bool,
+    canary: bool,
+    channel: Option<ReleaseChannel>,
+    /// IntelliJ platform branch, e.g. 243
+    platform_major: Option<u32>,
+    /// Exact IntelliJ platform build, e.g. "243.22562.218"
+    platform_build: Option<String>,
+    /// `YYYY.N` major track, e.g. "2024.2"
+    major: Option<String>,
+    /// Only releases that appeared with the last refresh of the list
+    new_only: bool,
+}
+
+/// Which installations `installed` shows
+struct InstalledFilters {
+    /// `YYYY.N` major track, e.g. "2024.2"
+    major: Option<String>,
+    /// Also show the bundles JetBrains Toolbox installed
+    include_toolbox: bool,
+}
+
+/// Which prerequisite checks `install` runs
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PrerequisiteChecks {
+    All,
+    /// Everything but network connectivity, for installs that need no network
+    SkipNetwork,
+    None,
+}
+
+/// Release that `install` was asked for
+#[derive(Clone, Copy)]
+enum InstallTarget<'a> {
+    /// A version query, as accepted by `list`
+    Version(&'a str),
+    /// The newest release labeled stable
+    Latest,
+    /// The newest Beta or Canary
+    LatestPrerelease,
+    /// An archive URL outside the feed
+    Url(&'a str),
+}
+
+impl InstallTarget<'_> {
+    /// How the target is written in the history log
+    fn label(&self) -> &str {
+        match self {
+            InstallTarget::Version(version) => version,
+            InstallTarget::Latest => "latest",
+            InstallTarget::LatestPrerelease => "latest-prerelease",
+            InstallTarget::Url(url) => url,
+        }
+    }
+}
 
 /// Handles all CLI commands with proper error handling and user feedback
 pub struct CommandHandler;
@@ -17,68 +252,731 @@ pub struct CommandHandler;
 impl CommandHandler {
     /// Main entry point for handling CLI commands
     pub fn handle(cli: Cli) -> Result<(), AstudiosError> {
+        let args = cli.command.network().cloned().unwrap_or_default();
+        if let Some(max_age) = args.max_age {
+            CacheMaxAge::set_override(max_age);
+        }
+        if let Some(format) = args.feed_format {
+            FeedFormat::set_override(format.into());
+        }
+        confirm::set_assume_yes(cli.command.assume_yes());
+
+        let mut network = NetworkSettings::from_env();
+        network.prefer_ipv4 |= args.prefer_ipv4;
+        network.prefer_mirror_on_failure |= args.prefer_mirror_on_failure;
+        if let Some(ca_bundle) = args.ca_bundle {
+            network.ca_bundle = Some(ca_bundle);
+        }
+        network.insecure = args.insecure;
+        network.connect_timeout_secs = args.timeout_connect;
+        network.timeout_secs = args.timeout;
+        if network.insecure {
+            eprintln!(
+                "{} {}",
+                "⚠️".yellow(),
+                "WARNING: TLS certificate verification is disabled (--insecure). Anyone on the network path can tamper with downloads; only the SHA-256 checksum check still protects them."
+                    .red()
+                    .bold()
+            );
+        }
+        NetworkSettings::set_override(network);
+
+        // Fail early on misconfiguration, such as an unusable CA bundle, rather
+        // than partway through the command; `config check` reports it itself.
+        // Read-only commands work without the directories installs write to.
+        if !matches!(cli.command, Commands::Config { .. }) {
+            let mut problems = Config::problems(cli.command.lock_wait().is_some());
+            if cli.command.downloader() == Some(DownloaderArg::Aria2)
+                && Downloader::find_aria2().is_err()
+            {
+                problems.push(
+                    "--downloader aria2 was given, but aria2 is not installed (e.g. `brew install aria2`)"
+                        .to_string(),
+                );
+            }
+            Config::report_problems(problems)?;
+        }
+
+        if let Some(secs) = args.wait_for_network
+            && cli.command.uses_network()
+            && !SystemDetector::wait_for_network(Duration::from_secs(secs))?
+        {
+            println!(
+                "{} JetBrains API still unreachable after {secs}s, continuing anyway",
+                "⚠️".yellow()
+            );
+        }
+
+        // Held until this function returns, so the lock is released on every exit path
+        let lock = cli
+            .command
+            .lock_wait()
+            .map(OperationLock::acquire)
+            .transpose()?;
+        if lock.is_some() {
+            Self::clean_stale_staging();
+        }
+
         match cli.command {
             Commands::List {
                 release,
                 beta,
                 canary,
+                channel,
+                platform_major,
+                platform_build,
+                major,
+                new,
+                limit,
+                all_platforms,
+                ..
+            } => Self::handle_list(
+                ListFilters {
+                    release,
+                    beta,
+                    canary,
+                    channel: channel.map(ReleaseChannel::from),
+                    platform_major,
+                    platform_build,
+                    major,
+                    new_only: new,
+                },
                 limit,
                 all_platforms,
-            } => Self::handle_list(release, beta, canary, limit, all_platforms),
+            ),
             Commands::Download {
                 version,
                 latest,
                 latest_prerelease,
-                directory,
-            } => Self::handle_download(
+                select_arch_on_ambiguity,
+                all_platforms,
+                probe: true,
+                json,
+                ..
+            } => Self::handle_download_probe(
                 version.as_deref(),
                 latest,
                 latest_prerelease,
-                directory.as_deref(),
+                all_platforms,
+                select_arch_on_ambiguity,
+                json,
             ),
-            Commands::Install {
+            Commands::Download {
                 version,
                 latest,
+                latest_prerelease,
                 directory,
-                skip_checks,
-            } => Self::handle_install(
-                version.as_deref(),
+                downloader,
+                dedupe_downloads,
+                select_arch_on_ambiguity,
+                all_platforms,
+                checksum,
+                url,
+                ..
+            } => {
+                let options = DownloadOptions {
+                    downloader: Self::select_downloader(downloader)?,
+                    dedupe_downloads,
+                    select_arch_on_ambiguity,
+                    checksum,
+                    verify_signature: false,
+                    name_template: None,
+                    only_if_missing: false,
+                    keep_archives: None,
+                    strict_arch: false,
+                };
+                match url {
+                    Some(url) => Self::handle_download_url(&url, directory.as_deref(), options),
+                    None => Self::handle_download(
+                        version.as_deref(),
+                        latest,
+                        latest_prerelease,
+                        directory.as_deref(),
+                        all_platforms,
+                        options,
+                    ),
+                }
+            }
+            Commands::Install {
+                versions,
                 latest,
-                directory.as_deref(),
+                latest_prerelease,
+                directory,
+                applications_dir,
                 skip_checks,
+                strict,
+                verify_signature,
+                no_prereq_network_check,
+                no_symlink,
+                downloader,
+                dedupe_downloads,
+                checksum,
+                name_template,
+                only_if_missing,
+                url,
+                keep_n_archives,
+                select_arch_on_ambiguity,
+                strict_arch,
+                concurrent_extractions,
+                parallel,
+                keep_going,
+                aria2_rpc,
+                json_lines,
+                ..
+            } => {
+                if checksum.is_some() && versions.len() > 1 {
+                    return Err(AstudiosError::General(
+                        "--checksum pins the digest of a single archive; install one version at a time"
+                            .to_string(),
+                    ));
+                }
+                if json_lines {
+                    // Messages go to stderr so they cannot interleave with the records
+                    HumanOutput::set_override(HumanOutput::Stderr);
+                    events::set_handler(events::json_lines_handler(std::io::stdout()));
+                }
+                ExtractionSemaphore::global().set_limit(concurrent_extractions as usize);
+                let options = DownloadOptions {
+                    downloader: Self::select_downloader(downloader)?,
+                    dedupe_downloads,
+                    select_arch_on_ambiguity,
+                    checksum,
+                    verify_signature,
+                    name_template,
+                    only_if_missing,
+                    keep_archives: keep_n_archives,
+                    strict_arch,
+                };
+                let checks = if skip_checks {
+                    PrerequisiteChecks::None
+                } else if no_prereq_network_check {
+                    PrerequisiteChecks::SkipNetwork
+                } else {
+                    PrerequisiteChecks::All
+                };
+                let install = |target: InstallTarget| {
+                    let result = Self::handle_install(
+                        target,
+                        directory.as_deref(),
+                        checks,
+                        strict,
+                        no_symlink,
+                        applications_dir.as_deref(),
+                        options.clone(),
+                    )
+                    .inspect_err(|e| {
+                        events::emit(InstallEvent::Failed {
+                            error: e.to_string(),
+                        })
+                    });
+                    Self::record_history(HistoryAction::Install, target.label(), result)
+                };
+                if let Some(url) = &url {
+                    install(InstallTarget::Url(url))
+                } else if latest {
+                    install(InstallTarget::Latest)
+                } else if latest_prerelease {
+                    install(InstallTarget::LatestPrerelease)
+                } else if versions.is_empty() {
+                    Err(AstudiosError::General(
+                        "Please specify a version or use --latest or --latest-prerelease"
+                            .to_string(),
+                    ))
+                } else if versions.len() == 1 {
+                    install(InstallTarget::Version(&versions[0]))
+                } else {
+                    // Shuts the daemon down when the batch is done
+                    let _daemon = if aria2_rpc {
+                        Self::share_aria2_daemon(options.downloader.as_ref())
+                    } else {
+                        None
+                    };
+                    let parallel =
+                        parallel.map_or_else(Self::default_parallel_downloads, |n| n as usize);
+                    Self::install_batch(
+                        &versions,
+                        keep_going,
+                        parallel,
+                        |version| {
+                            Self::is_version_installed(
+                                version,
+                                directory.as_deref(),
+                                applications_dir.as_deref(),
+                            )
+                        },
+                        |version| {
+                            Self::prefetch_download(version, applications_dir.as_deref(), &options)
+                        },
+                        |version| install(InstallTarget::Version(version)),
+                    )
+                }
+            }
+            Commands::Uninstall {
+                versions,
+                keep_files,
+                keep_going,
+                dry_run: true,
+                ..
+            } => batch::run_batch(&versions, keep_going, |version| {
+                Self::handle_uninstall_dry_run(version, keep_files)
+            }),
+            Commands::Uninstall {
+                versions,
+                keep_files,
+                keep_going,
+                ..
+            } => batch::run_batch(&versions, keep_going, |version| {
+                Self::record_history(
+                    HistoryAction::Uninstall,
+                    version,
+                    Self::handle_uninstall(version, keep_files),
+                )
+            }),
+            Commands::Use {
+                version,
+                path,
+                applications_dir,
+                channel,
+                ..
+            } => {
+                let target = match (&version, &path) {
+                    (Some(version), _) => version.clone(),
+                    (None, Some(path)) => path.display().to_string(),
+                    (None, None) => String::new(),
+                };
+                let result = Self::handle_use(
+                    version.as_deref(),
+                    path.as_deref(),
+                    applications_dir.as_deref(),
+                    channel.map(ReleaseChannel::from),
+                );
+                Self::record_history(HistoryAction::Use, &target, result)
+            }
+            Commands::SetChannel {
+                version, channel, ..
+            } => {
+                let result = Self::handle_set_channel(&version, channel.map(ReleaseChannel::from));
+                Self::record_history(HistoryAction::SetChannel, &version, result)
+            }
+            Commands::Installed {
+                tree,
+                sort,
+                reverse,
+                json,
+                verify,
+                major,
+                group,
+                include_toolbox,
+            } => Self::handle_installed(
+                tree,
+                sort,
+                reverse,
+                json,
+                verify,
+                InstalledFilters {
+                    major,
+                    include_toolbox,
+                },
+                group,
+            ),
+            Commands::Which {
+                applications_dir,
+                channel,
+                json,
+                include_toolbox,
+            } => Self::handle_which(
+                applications_dir.as_deref(),
+                channel.map(ReleaseChannel::from),
+                json,
+                include_toolbox,
             ),
-            Commands::Uninstall { version } => Self::handle_uninstall(&version),
-            Commands::Use { version } => Self::handle_use(&version),
-            Commands::Installed => Self::handle_installed(),
-            Commands::Which => Self::handle_which(),
-            Commands::Update => Self::handle_update(),
+            Commands::Update { .. } => Self::handle_update(),
             Commands::Open { path } => Self::handle_open(&path),
+            Commands::OpenDir { target } => Self::handle_open_dir(target),
+            Commands::Info {
+                version,
+                open,
+                json,
+                ..
+            } => Self::handle_info(&version, open, json),
+            Commands::Outdated { open, .. } => Self::handle_outdated(open),
+            Commands::Status { json, .. } => Self::handle_status(json),
+            Commands::Doctor { json, strict, .. } => Self::handle_doctor(json, strict),
+            Commands::History { limit, json } => Self::handle_history(limit, json),
+            Commands::Audit {
+                sha_manifest,
+                output,
+            } => Self::handle_audit(sha_manifest, output.as_deref()),
+            Commands::Verify { repair, .. } => Self::handle_verify(repair),
+            Commands::Benchmark { url, .. } => Self::handle_benchmark(url.as_deref()),
+            Commands::Config {
+                action: ConfigAction::Check { .. },
+            } => Self::handle_config_check(),
+            Commands::Shim {
+                install,
+                uninstall,
+                bin_dir,
+                exec,
+                args,
+            } => {
+                if exec {
+                    Self::exec_active_launcher(&args)
+                } else {
+                    Self::handle_shim(install, uninstall, bin_dir)
+                }
+            }
+        }
+    }
+
+    /// Append the outcome of a mutating operation to the history log and pass it on.
+    ///
+    /// A log that cannot be written only produces a warning; it never fails the
+    /// operation itself.
+    fn record_history(
+        action: HistoryAction,
+        version: &str,
+        result: Result<(), AstudiosError>,
+    ) -> Result<(), AstudiosError> {
+        let entry = HistoryEntry::new(action, version, &result);
+        if let Err(e) = History::open().and_then(|history| history.append(&entry)) {
+            eprintln!("{} Could not write the history log: {e}", "⚠️".yellow());
+        }
+        result
+    }
+
+    /// Create an installer, optionally placing the symlink in a custom applications directory
+    fn installer_for(applications_dir: Option<&str>) -> Result<Installer, AstudiosError> {
+        let installer = Installer::new()?;
+        Ok(match applications_dir {
+            Some(dir) => installer.with_applications_dir(PathBuf::from(dir)),
+            None => installer,
+        })
+    }
+
+    /// Pick the macOS architecture to download for a release.
+    ///
+    /// With `prompt` set, an interactive terminal and several architectures on
+    /// offer, the user is asked and the answer is remembered. Otherwise the
+    /// remembered choice or the host architecture is used.
+    fn select_mac_arch(item: &AndroidStudio, prompt: bool) -> Result<MacArch, AstudiosError> {
+        let mut preferences = Preferences::load()?;
+        let default = preferences.mac_arch.unwrap_or_else(MacArch::host);
+        let arches = item.macos_arches();
+
+        if !prompt || arches.len() < 2 || !std::io::stdin().is_terminal() {
+            return Ok(default);
+        }
+
+        sayln!(
+            "{} {} is offered for several architectures:",
+            "❓".blue(),
+            item.name
+        );
+        for (index, arch) in arches.iter().enumerate() {
+            let marker = if *arch == default { " (default)" } else { "" };
+            sayln!("  {}) {arch}{marker}", index + 1);
+        }
+        say!("Select an architecture [1-{}]: ", arches.len());
+        HumanOutput::flush()?;
+
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        let arch = match answer.trim() {
+            "" => default,
+            choice => choice
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|n| arches.get(n).copied())
+                .ok_or_else(|| AstudiosError::General(format!("Invalid selection: {choice}")))?,
+        };
+
+        preferences.mac_arch = Some(arch);
+        preferences.save()?;
+        sayln!(
+            "{} Using {arch}; this choice is remembered for future installs",
+            "✅".green()
+        );
+
+        Ok(arch)
+    }
+
+    /// Installation used in place of a missing symlink: the configured default or
+    /// the most recently installed version
+    fn default_studio(
+        installer: &Installer,
+    ) -> Result<Option<InstalledAndroidStudio>, AstudiosError> {
+        let preferences = Preferences::load()?;
+        installer.default_studio(preferences.default_studio.as_deref())
+    }
+
+    /// Resolve the downloader requested on the command line; `None` means auto-detect
+    fn select_downloader(choice: DownloaderArg) -> Result<Option<Downloader>, AstudiosError> {
+        Ok(match choice {
+            DownloaderArg::Auto => None,
+            DownloaderArg::Aria2 => Some(Downloader::Aria2(Downloader::find_aria2()?)),
+            DownloaderArg::Reqwest => Some(Downloader::Reqwest),
+        })
+    }
+
+    /// Install several versions, skipping those an interrupted earlier run of
+    /// the same command already installed.
+    ///
+    /// With `parallel` above 1 the archives are first fetched with `download`,
+    /// up to `parallel` at a time; installing them, and so mounting and
+    /// extracting, then proceeds one version at a time.
+    fn install_batch(
+        versions: &[String],
+        keep_going: bool,
+        parallel: usize,
+        is_installed: impl Fn(&str) -> bool,
+        download: impl Fn(&str) -> Result<(), AstudiosError> + Sync,
+        install: impl Fn(&str) -> Result<(), AstudiosError>,
+    ) -> Result<(), AstudiosError> {
+        let path = InstallCheckpoint::path()?;
+        let mut checkpoint = InstallCheckpoint::resume(&path, versions);
+        // The checkpoint may be older than an uninstall of a version it lists
+        checkpoint.retain_installed(is_installed);
+        let remaining = checkpoint.remaining();
+        if remaining.len() < versions.len() {
+            sayln!(
+                "{} Resuming install: {}/{} already installed",
+                "↻".blue(),
+                versions.len() - remaining.len(),
+                versions.len()
+            );
+        }
+
+        if parallel > 1 && remaining.len() > 1 {
+            Self::download_in_parallel(&remaining, parallel, download);
+        }
+
+        batch::run_batch(&remaining, keep_going, |version| {
+            install(version)?;
+            checkpoint.mark_completed(version);
+            checkpoint.save_to(&path)
+        })?;
+        InstallCheckpoint::clear(&path)
+    }
+
+    /// Concurrent batch downloads when `--parallel` is not given: one per CPU,
+    /// capped so the network and the mounts that follow are not overwhelmed
+    fn default_parallel_downloads() -> usize {
+        std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(Config::MAX_DEFAULT_PARALLEL_DOWNLOADS)
+    }
+
+    /// Fetch the archive of each version, `parallel` at a time. Failures are
+    /// only reported: the install that follows retries the download and fails
+    /// properly.
+    fn download_in_parallel(
+        versions: &[String],
+        parallel: usize,
+        download: impl Fn(&str) -> Result<(), AstudiosError> + Sync,
+    ) {
+        let workers = parallel.min(versions.len());
+        sayln!(
+            "{} Downloading {} versions, up to {workers} at a time",
+            "⚡".blue(),
+            versions.len()
+        );
+
+        let next = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    while let Some(version) = versions.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        if let Err(e) = download(version) {
+                            eprintln!(
+                                "{} Could not download {version} ahead of installing it: {e}",
+                                "⚠️".yellow()
+                            );
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Whether the release `version` names is installed; `false` when that
+    /// cannot be told, so the version is installed again rather than skipped
+    fn is_version_installed(
+        version: &str,
+        directory: Option<&str>,
+        applications_dir: Option<&str>,
+    ) -> bool {
+        let installed = || -> Result<bool, AstudiosError> {
+            let release = Self::resolve_install_target(
+                &Self::install_lister()?,
+                InstallTarget::Version(version),
+            )?;
+            let installer = Self::installer_for(applications_dir)?;
+            Ok(Self::find_installed_build(&installer, directory, &release.build)?.is_some())
+        };
+        installed().unwrap_or(false)
+    }
+
+    /// Download the archive a batch install of `version` will use, unless
+    /// `--only-if-missing` would skip that version anyway
+    fn prefetch_download(
+        version: &str,
+        applications_dir: Option<&str>,
+        options: &DownloadOptions,
+    ) -> Result<(), AstudiosError> {
+        let installer = Self::download_installer(applications_dir, options)?;
+        let release = Self::resolve_install_target(
+            &Self::install_lister()?,
+            InstallTarget::Version(version),
+        )?;
+        if options.only_if_missing
+            && Self::find_installed_build(&installer, None, &release.build)?.is_some()
+        {
+            return Ok(());
+        }
+        // The architecture is not known until the install asks for it
+        if options.select_arch_on_ambiguity && release.macos_arches().len() > 1 {
+            return Ok(());
+        }
+
+        installer
+            .with_mac_arch(Self::select_mac_arch(&release, false)?)
+            .download_release(&release.version, &release.name)
+            .map(|_| ())
+    }
+
+    /// Installer configured with the download and install options shared by
+    /// `install` and the downloads a batch install starts ahead of it
+    fn download_installer(
+        applications_dir: Option<&str>,
+        options: &DownloadOptions,
+    ) -> Result<Installer, AstudiosError> {
+        let mut installer = Self::installer_for(applications_dir)?
+            .with_dedupe_downloads(options.dedupe_downloads)
+            .with_checksum(options.checksum.clone())
+            .with_source_signature_check(options.verify_signature);
+        if let Some(downloader) = &options.downloader {
+            installer = installer.with_downloader(downloader.clone());
+        }
+        if options.strict_arch {
+            installer = installer.with_strict_arch(true);
+        }
+        if let Some(template) = &options.name_template {
+            installer = installer.with_bundle_name_template(template.clone());
+        }
+        Ok(installer)
+    }
+
+    /// Start an aria2 RPC daemon for a batch of downloads, or return `None` so each
+    /// download spawns aria2 itself when that is not possible
+    fn share_aria2_daemon(forced: Option<&Downloader>) -> Option<SharedAria2Daemon> {
+        let aria2_path = match forced {
+            Some(Downloader::Aria2(path)) => path.clone(),
+            Some(Downloader::Reqwest) => {
+                sayln!(
+                    "{} --aria2-rpc has no effect with the built-in downloader",
+                    "⚠️".yellow()
+                );
+                return None;
+            }
+            None => match Downloader::find_aria2() {
+                Ok(path) => path,
+                Err(_) => {
+                    sayln!(
+                        "{} --aria2-rpc needs aria2, which is not installed",
+                        "⚠️".yellow()
+                    );
+                    return None;
+                }
+            },
+        };
+
+        match Aria2Daemon::start(&aria2_path) {
+            Ok(daemon) => {
+                sayln!("{} Downloading through an aria2 RPC daemon", "🚀".blue());
+                Some(daemon.share())
+            }
+            Err(e) => {
+                sayln!(
+                    "{} Could not start the aria2 RPC daemon ({e}); running aria2 per download",
+                    "⚠️".yellow()
+                );
+                None
+            }
+        }
+    }
+
+    /// Remove the leftovers of failed installs before a command that modifies
+    /// installations, mentioning it only when something was removed
+    fn clean_stale_staging() {
+        let Ok(installer) = Installer::new() else {
+            return;
+        };
+        let removed = installer.clean_stale_staging();
+        if !removed.is_empty() {
+            // stderr, so `install --json-lines` output stays machine-readable
+            eprintln!(
+                "{} Removed {} stale staging artifact(s) left by earlier runs",
+                "🧹".blue(),
+                removed.len()
+            );
         }
     }
 
     /// Handle the list command to display available Android Studio versions
     fn handle_list(
-        release: bool,
-        beta: bool,
-        canary: bool,
+        filters: ListFilters,
         limit: Option<usize>,
         all_platforms: bool,
     ) -> Result<(), AstudiosError> {
-        let lister = AndroidStudioLister::new()?;
+        let lister =
+            AndroidStudioLister::new()?.with_max_age(CacheMaxAge::current_or(CacheMaxAge::LIST));
         let releases = lister.get_releases()?;
+        let latest_builds = lister.latest_in_each_channel(&releases.items);
+        let previous_builds = lister.previous_builds();
+        let is_new = |item: &AndroidStudio| {
+            previous_builds
+                .as_ref()
+                .is_some_and(|previous| !previous.contains(&item.build))
+        };
+
+        let mut items =
+            lister.filter_by_channel(releases, filters.release, filters.beta, filters.canary);
+
+        if let Some(channel) = filters.channel {
+            items = lister.filter_by_release_channel(items, channel);
+        }
+
+        if let Some(major) = filters.platform_major {
+            items = lister.filter_by_platform_major(items, major);
+        }
 
-        let mut items = lister.filter_by_channel(releases, release, beta, canary);
+        if let Some(platform_build) = &filters.platform_build {
+            items = lister.filter_by_platform_build(items, platform_build);
+        }
+
+        if let Some(track) = &filters.major {
+            items = lister.filter_by_major_track(items, track);
+        }
 
         // Filter by current platform unless all_platforms flag is set
         if !all_platforms {
             items = lister.filter_by_current_platform(items);
         }
 
-        let display_items: Vec<_> = if let Some(limit) = limit {
-            items.into_iter().take(limit).collect()
-        } else {
-            items
-        };
+        if filters.new_only {
+            if previous_builds.is_none() {
+                println!(
+                    "{} The releases list has not been refreshed since it was first fetched, so there is nothing to compare with yet",
+                    "ℹ️".blue()
+                );
+                return Ok(());
+            }
+            items.retain(|item| is_new(item));
+        }
+
+        // Order before limiting so --limit keeps the newest releases
+        let display_items = lister.newest_first(items, limit);
 
         // Get installed versions and active version for status display
         let installer = Installer::new()?;
@@ -122,8 +1020,18 @@ impl CommandHandler {
             return Ok(());
         }
 
+        let (outdated_after, today) = (Config::outdated_after_months(), model::today());
+
+        // Print oldest first so the newest release ends up closest to the prompt
         for item in display_items.iter().rev() {
-            Self::print_version_info(item, &installed_studios, &active_studio);
+            Self::print_version_info(
+                item,
+                &installed_studios,
+                &active_studio,
+                latest_builds.contains(&item.build),
+                is_new(item),
+                item.is_outdated(outdated_after, &today),
+            );
         }
 
         Ok(())
@@ -133,6 +1041,9 @@ impl CommandHandler {
         item: &AndroidStudio,
         installed_studios: &[InstalledAndroidStudio],
         active_studio: &Option<InstalledAndroidStudio>,
+        latest_in_channel: bool,
+        is_new: bool,
+        is_outdated: bool,
     ) {
         let channel_color = match item.channel_type() {
             ReleaseChannel::Release => "Release".green(),
@@ -140,6 +1051,7 @@ impl CommandHandler {
             ReleaseChannel::Canary => "Canary".red(),
             ReleaseChannel::ReleaseCandidate => "RC".blue(),
             ReleaseChannel::Patch => "Patch".cyan(),
+            ReleaseChannel::FeatureDrop => "Feature Drop".magenta(),
         };
 
         // Check if this version is installed
@@ -167,16 +1079,47 @@ impl CommandHandler {
             (false, false) => "".normal(),
         };
 
+        let latest = if latest_in_channel {
+            " (latest in channel)".cyan()
+        } else {
+            "".normal()
+        };
+
+        let new = if is_new {
+            " 🆕".normal()
+        } else {
+            "".normal()
+        };
+
         println!(
-            "{} {} ({}){}",
+            "{} {} ({}){}{}{}",
             ">".dimmed(),
             item.version.bold(),
             channel_color,
-            status
+            latest,
+            status,
+            new
         );
         println!("  {} {}", "Name:".dimmed(), item.name);
         println!("  {} {}", "Build:".dimmed(), item.build);
-        println!("  {} {}", "Date:".dimmed(), item.date);
+        if !item.platform_build.is_empty() {
+            println!(
+                "  {} {} ({})",
+                "Platform:".dimmed(),
+                item.platform_version,
+                item.platform_build
+            );
+        }
+        if is_outdated {
+            println!(
+                "  {} {} {}",
+                "Date:".dimmed(),
+                item.date,
+                OUTDATED_NOTE.yellow()
+            );
+        } else {
+            println!("  {} {}", "Date:".dimmed(), item.date);
+        }
 
         // Show download information for macOS
         if let Some(download) = item.get_platform_download() {
@@ -195,120 +1138,360 @@ impl CommandHandler {
 
     /// Handle the install command to install Android Studio versions
     fn handle_install(
-        version: Option<&str>,
-        latest: bool,
+        target: InstallTarget,
         directory: Option<&str>,
-        skip_checks: bool,
+        checks: PrerequisiteChecks,
+        strict: bool,
+        no_symlink: bool,
+        applications_dir: Option<&str>,
+        options: DownloadOptions,
     ) -> Result<(), AstudiosError> {
-        let lister = AndroidStudioLister::new()?;
-
-        // Find the target version
-        let target_item = if latest {
-            lister.get_latest_release()?
-        } else if let Some(version_query) = version {
-            lister.find_version_by_query(version_query)?
-        } else {
-            return Err(AstudiosError::General(
-                "Please specify a version or use --latest".to_string(),
-            ));
-        };
+        let checksum_pinned = options.checksum.is_some();
+        let installer = Self::download_installer(applications_dir, &options)?
+            .with_symlink(!no_symlink)
+            .with_network_check(checks == PrerequisiteChecks::All)
+            .with_strict_checks(strict);
+        let symlink_path = installer.applications_dir().join("Android Studio.app");
+        let symlink_path = symlink_path.display().to_string();
+        let default_install_dir = installer.applications_dir().display().to_string();
+        let install_dir = directory.unwrap_or(&default_install_dir);
+        let run_checks = checks != PrerequisiteChecks::None;
+
+        let (app_path, version_str) = match target {
+            InstallTarget::Url(url) => {
+                if !checksum_pinned {
+                    Self::warn_unverified_url();
+                }
+                Self::print_install_header("from URL", url, install_dir);
 
-        let version_str = &target_item.version;
-        let full_name = &target_item.name;
-        let install_dir = directory.unwrap_or("/Applications");
+                let app_path = installer.install_from_url(url, directory, run_checks)?;
+                let version = InstalledAndroidStudio::new(app_path.clone())?
+                    .map(|installed| installed.extract_detailed_version())
+                    .unwrap_or_default();
+                (app_path, version)
+            }
+            target => {
+                let lister = Self::install_lister()?;
+                let target_item = match target {
+                    InstallTarget::Version(query) => Self::resolve_version_query(&lister, query)?,
+                    target => Self::resolve_install_target(&lister, target)?,
+                };
+                let version_str = &target_item.version;
+                let full_name = &target_item.name;
+
+                if options.only_if_missing
+                    && let Some(existing) =
+                        Self::find_installed_build(&installer, directory, &target_item.build)?
+                {
+                    sayln!(
+                        "{} Android Studio {} is already installed at {}",
+                        "✅".green(),
+                        version_str.cyan(),
+                        existing.path.display()
+                    );
+                    return Ok(());
+                }
 
-        // Display installation header with clear formatting
-        println!();
-        println!("{}", "━".repeat(80).dimmed());
-        println!(
-            "{} {} {}",
-            "🚀".blue(),
-            "Installing Android Studio".bold(),
-            version_str.cyan()
-        );
-        println!("   {}", full_name.green());
-        println!(
-            "   {} {}",
-            "Target directory:".dimmed(),
-            install_dir.yellow()
-        );
-        println!("{}", "━".repeat(80).dimmed());
-        println!();
+                Self::print_install_header(version_str, full_name, install_dir);
 
-        let installer = Installer::new()?;
-        installer.install_version_with_checks(version_str, full_name, directory, !skip_checks)?;
+                let mac_arch =
+                    Self::select_mac_arch(&target_item, options.select_arch_on_ambiguity)?;
+                let app_path = installer
+                    .with_mac_arch(mac_arch)
+                    .install_version_with_checks(version_str, full_name, directory, run_checks)?;
+                (app_path, version_str.clone())
+            }
+        };
 
         // Display success summary
-        println!();
-        println!("{}", "━".repeat(80).dimmed());
-        println!(
+        sayln!();
+        sayln!("{}", "━".repeat(80).dimmed());
+        sayln!(
             "{} {} {}",
             "✅".green(),
             "Installation Complete".bold().green(),
             "🎉".green()
         );
-        println!();
-        println!("   {} {}", "Version:".dimmed(), version_str.cyan().bold());
-        println!("   {} {}", "Location:".dimmed(), install_dir.yellow());
+        sayln!();
+        sayln!("   {} {}", "Version:".dimmed(), version_str.cyan().bold());
+        sayln!("   {} {}", "Location:".dimmed(), install_dir.yellow());
 
         // Show different information based on installation directory
-        if directory.is_none() || directory == Some("/Applications") {
-            println!(
-                "   {} {}",
-                "Symlink:".dimmed(),
-                "/Applications/Android Studio.app".blue()
-            );
-            println!();
-            println!(
+        if !no_symlink
+            && (applications_dir.is_some()
+                || directory.is_none_or(|dir| dir == default_install_dir))
+        {
+            sayln!("   {} {}", "Symlink:".dimmed(), symlink_path.blue());
+            sayln!();
+            sayln!(
                 "   {} Launch Android Studio from Applications or run:",
                 "💡".blue()
             );
-            println!("   {}", "open \"/Applications/Android Studio.app\"".cyan());
+            sayln!("   {}", format!("open \"{symlink_path}\"").cyan());
         } else {
-            println!(
+            let app_path = app_path.display();
+            sayln!(
                 "   {} {}",
                 "App Bundle:".dimmed(),
-                format!("{}/Android Studio {}.app", install_dir, version_str).blue()
-            );
-            println!();
-            println!("   {} Launch Android Studio by running:", "💡".blue());
-            println!(
-                "   {}",
-                format!(
-                    "open \"{}/Android Studio {}.app\"",
-                    install_dir, version_str
-                )
-                .cyan()
+                app_path.to_string().blue()
             );
+            sayln!();
+            sayln!("   {} Launch Android Studio by running:", "💡".blue());
+            sayln!("   {}", format!("open \"{app_path}\"").cyan());
         }
 
-        println!("{}", "━".repeat(80).dimmed());
+        sayln!("{}", "━".repeat(80).dimmed());
+
+        // The install already succeeded, so a failed cleanup only warrants a warning
+        let keep_archives = options.keep_archives.or_else(|| {
+            Preferences::load()
+                .ok()
+                .and_then(|preferences| preferences.keep_archives)
+        });
+        if let Some(keep) = keep_archives
+            && let Err(e) = Self::evict_archives(keep)
+        {
+            eprintln!("{} Could not evict old archives: {e}", "⚠️".yellow());
+        }
 
         Ok(())
     }
 
-    /// Handle the download command to download Android Studio versions
-    fn handle_download(
-        version: Option<&str>,
-        latest: bool,
-        latest_prerelease: bool,
-        directory: Option<&str>,
-    ) -> Result<(), AstudiosError> {
-        let lister = AndroidStudioLister::new()?;
+    /// Apply the archive retention policy, reporting what it removed
+    fn evict_archives(keep: usize) -> Result<(), AstudiosError> {
+        let eviction = Installer::new()?.evict_archives(keep, &BlobStore::new()?)?;
+        if eviction.evicted.is_empty() {
+            return Ok(());
+        }
 
-        // Find the target version
-        let target_item = if latest {
-            lister.get_latest_release()?
-        } else if latest_prerelease {
-            lister.get_latest_prerelease()?
-        } else if let Some(version_query) = version {
-            lister.find_version_by_query(version_query)?
+        sayln!();
+        sayln!(
+            "{} Evicted {} archive(s) beyond the {} most recently used per track, reclaiming {}",
+            "🧹".blue(),
+            eviction.evicted.len(),
+            keep,
+            HumanBytes(eviction.reclaimed_bytes).to_string().yellow()
+        );
+        for archive in &eviction.evicted {
+            sayln!("   {}", archive.display().to_string().dimmed());
+        }
+        Ok(())
+    }
+
+    /// Lister for install and download, which want a recent list so a release
+    /// that just shipped is found; `--max-age` still overrides it
+    fn install_lister() -> Result<AndroidStudioLister, AstudiosError> {
+        Ok(AndroidStudioLister::new()?.with_max_age(CacheMaxAge::current_or(CacheMaxAge::INSTALL)))
+    }
+
+    /// Find the release `install` was asked for
+    fn resolve_install_target(
+        lister: &AndroidStudioLister,
+        target: InstallTarget,
+    ) -> Result<AndroidStudio, AstudiosError> {
+        match target {
+            InstallTarget::Version(version_query) => lister.find_version_by_query(version_query),
+            InstallTarget::Latest => lister.get_latest_release(),
+            InstallTarget::LatestPrerelease => lister.get_latest_prerelease(),
+            InstallTarget::Url(url) => Err(AstudiosError::General(format!(
+                "{url} is not a release in the feed"
+            ))),
+        }
+    }
+
+    /// Resolve the version query of an install or download. An exact version or
+    /// build, or a query only one release matches, is used as is. When several
+    /// match, the user picks one on a terminal; otherwise the newest is used.
+    fn resolve_version_query(
+        lister: &AndroidStudioLister,
+        query: &str,
+    ) -> Result<AndroidStudio, AstudiosError> {
+        let mut matches = lister.find_all_matching(query)?;
+        let Some(best) = matches.first() else {
+            return Err(list::version_not_found(query));
+        };
+        let query = query.trim();
+        if matches.len() == 1
+            || best.version.eq_ignore_ascii_case(query)
+            || best.build.eq_ignore_ascii_case(query)
+        {
+            return Ok(matches.swap_remove(0));
+        }
+
+        if !std::io::stdin().is_terminal() {
+            eprintln!(
+                "{} {} releases match '{query}'; using the newest, {}. Give its full version to pick another.",
+                "ℹ️".blue(),
+                matches.len(),
+                best.name
+            );
+            return Ok(matches.swap_remove(0));
+        }
+
+        sayln!(
+            "{} {} releases match '{query}':",
+            "❓".blue(),
+            matches.len()
+        );
+        let listed = matches.len().min(list::MAX_LISTED_CANDIDATES);
+        for (index, item) in matches.iter().take(listed).enumerate() {
+            sayln!(
+                "  {}) {}\n     Version: {} | Build: {} | Channel: {}",
+                index + 1,
+                item.name,
+                item.version,
+                item.build,
+                item.channel
+            );
+        }
+        if matches.len() > listed {
+            sayln!(
+                "  ... and {} more; give the full version to pick one of them",
+                matches.len() - listed
+            );
+        }
+        say!("Select a release [1-{listed}] (default 1): ");
+        HumanOutput::flush()?;
+
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        let index = match answer.trim() {
+            "" => 0,
+            choice => choice
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .filter(|n| *n < listed)
+                .ok_or_else(|| AstudiosError::General(format!("Invalid selection: {choice}")))?,
+        };
+        Ok(matches.swap_remove(index))
+    }
+
+    /// Warn that an archive fetched with `--url` and no `--checksum` is never verified
+    fn warn_unverified_url() {
+        eprintln!(
+            "{} {}",
+            "⚠️".yellow(),
+            "WARNING: no --checksum given. The archive from --url is not in the releases feed, so nothing verifies it was not corrupted or tampered with."
+                .red()
+                .bold()
+        );
+    }
+
+    /// Print the banner shown before an install starts
+    fn print_install_header(version: &str, detail: &str, install_dir: &str) {
+        sayln!();
+        sayln!("{}", "━".repeat(80).dimmed());
+        sayln!(
+            "{} {} {}",
+            "🚀".blue(),
+            "Installing Android Studio".bold(),
+            version.cyan()
+        );
+        sayln!("   {}", detail.green());
+        sayln!(
+            "   {} {}",
+            "Target directory:".dimmed(),
+            install_dir.yellow()
+        );
+        sayln!("{}", "━".repeat(80).dimmed());
+        sayln!();
+    }
+
+    /// Find the release selected by the download command's arguments
+    fn resolve_download_target(
+        lister: &AndroidStudioLister,
+        version: Option<&str>,
+        latest: bool,
+        latest_prerelease: bool,
+    ) -> Result<AndroidStudio, AstudiosError> {
+        if latest {
+            lister.get_latest_release()
+        } else if latest_prerelease {
+            lister.get_latest_prerelease()
+        } else if let Some(version_query) = version {
+            Self::resolve_version_query(lister, version_query)
         } else {
-            return Err(AstudiosError::General(
+            Err(AstudiosError::General(
                 "Please specify a version or use --latest or --latest-prerelease".to_string(),
-            ));
+            ))
+        }
+    }
+
+    /// Handle `download --probe` by printing the download URL instead of downloading
+    fn handle_download_probe(
+        version: Option<&str>,
+        latest: bool,
+        latest_prerelease: bool,
+        all_platforms: bool,
+        select_arch_on_ambiguity: bool,
+        json: bool,
+    ) -> Result<(), AstudiosError> {
+        let target_item = Self::resolve_download_target(
+            &Self::install_lister()?,
+            version,
+            latest,
+            latest_prerelease,
+        )?;
+
+        let downloads: Vec<&Download> = if all_platforms {
+            target_item.downloads.iter().collect()
+        } else {
+            let arch = Self::select_mac_arch(&target_item, select_arch_on_ambiguity)?;
+            vec![target_item.require_platform_download_for(arch)?]
         };
 
+        if json {
+            let report = ProbeReport {
+                name: &target_item.name,
+                version: &target_item.version,
+                build: &target_item.build,
+                platform_build: &target_item.platform_build,
+                platform_version: &target_item.platform_version,
+                downloads: downloads
+                    .iter()
+                    .map(|download| ProbeDownload {
+                        platform: download.platform_id(),
+                        url: &download.link,
+                        size: &download.size,
+                        checksum: &download.checksum,
+                    })
+                    .collect(),
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+
+        // The URL comes first on its own line so `| head -n 1` yields just the link
+        for download in downloads {
+            println!("{}", download.link);
+            if all_platforms {
+                println!("  Platform: {}", download.platform_name());
+            }
+            println!("  Size: {}", download.size);
+            if !download.checksum.is_empty() {
+                println!("  SHA-256: {}", download.checksum);
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle the download command to download Android Studio versions
+    fn handle_download(
+        version: Option<&str>,
+        latest: bool,
+        latest_prerelease: bool,
+        directory: Option<&str>,
+        all_platforms: bool,
+        options: DownloadOptions,
+    ) -> Result<(), AstudiosError> {
+        let target_item = Self::resolve_download_target(
+            &Self::install_lister()?,
+            version,
+            latest,
+            latest_prerelease,
+        )?;
+
         let version_str = &target_item.version;
         let full_name = &target_item.name;
 
@@ -326,72 +1509,314 @@ impl CommandHandler {
             PathBuf::from(dir)
         } else {
             // Create version-specific subdirectory in versions directory
-            Config::default_download_dir().join(version_str)
+            Config::default_download_dir()?.join(version_str)
         };
 
         // Ensure download directory exists
         fs::create_dir_all(&download_dir)?;
 
-        // Get appropriate download for current platform
-        let download = target_item
-            .get_platform_download()
-            .ok_or(AstudiosError::Download(
-                "No download available for current platform".to_string(),
-            ))?;
+        if all_platforms {
+            return Self::download_all_platforms(&target_item, &download_dir, options.downloader);
+        }
+
+        // Prefer the smaller patch artifact when its base build is already
+        // installed; a pinned checksum is for the full build, so it keeps that
+        let base_installed = options.checksum.is_none()
+            && Installer::new()?
+                .installed_patch_base(&target_item)
+                .is_some();
+
+        let download = match target_item.get_patch_download() {
+            Some(patch) if base_installed => {
+                println!(
+                    "{} Base build is installed, downloading the patch artifact ({}) instead of the full build",
+                    "ℹ️".blue(),
+                    patch.size
+                );
+                patch
+            }
+            _ => {
+                let arch = Self::select_mac_arch(&target_item, options.select_arch_on_ambiguity)?;
+                let download = target_item.require_platform_download_for(arch)?;
+                if target_item.is_patch() {
+                    println!(
+                        "{} No patch artifact applicable, downloading the full build ({})",
+                        "ℹ️".blue(),
+                        download.size
+                    );
+                }
+                download
+            }
+        };
+        let default_filename = download.default_filename(version_str);
+        Self::fetch_download(
+            download.clone(),
+            &download_dir,
+            &default_filename,
+            full_name,
+            &options,
+        )
+    }
+
+    /// Handle `download --url`, fetching an archive that is not in the feed
+    fn handle_download_url(
+        url: &str,
+        directory: Option<&str>,
+        options: DownloadOptions,
+    ) -> Result<(), AstudiosError> {
+        if options.checksum.is_none() {
+            Self::warn_unverified_url();
+        }
+        let filename = Downloader::resolve_filename(url, "android-studio.dmg");
+        let label = Path::new(&filename).file_stem().map_or_else(
+            || filename.clone(),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+
+        println!();
+        println!("{} Downloading {}...", "🚀".blue(), url.green().bold());
+        println!();
+
+        let download_dir = match directory {
+            Some(dir) => PathBuf::from(dir),
+            None => Config::default_download_dir()?.join(&label),
+        };
+        fs::create_dir_all(&download_dir)?;
+
+        let download = Download {
+            link: url.to_string(),
+            size: String::new(),
+            checksum: String::new(),
+        };
+        Self::fetch_download(download, &download_dir, &filename, &label, &options)
+    }
+
+    /// Download `download` into `download_dir`, applying the pinned checksum
+    /// and the blob store from `options`
+    fn fetch_download(
+        mut download: Download,
+        download_dir: &Path,
+        default_filename: &str,
+        full_name: &str,
+        options: &DownloadOptions,
+    ) -> Result<(), AstudiosError> {
+        let pinned_checksum = &options.checksum;
+        let forced_downloader = &options.downloader;
+        if let Some(checksum) = pinned_checksum.clone() {
+            download.checksum = checksum;
+        }
 
-        // Create filename from URL
-        let default_filename = format!("android-studio-{version_str}.dmg");
-        let filename = Path::new(&download.link)
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or(&default_filename);
+        // Name the file after the server-provided name, or the URL
+        let filename = Downloader::resolve_filename(&download.link, default_filename);
 
         let download_path = download_dir.join(filename);
 
-        // Skip if file already exists
+        // Skip if file already exists, unless it fails a pinned checksum
         if download_path.exists() {
             let metadata = fs::metadata(&download_path)?;
             if metadata.len() > 0 {
-                println!(
+                sayln!(
                     "{} File already exists: {}",
                     "ℹ️".yellow(),
                     download_path.display()
                 );
-                return Ok(());
+                if pinned_checksum.is_none() {
+                    return Ok(());
+                }
+                match Downloader::verify_checksum(&download_path, &download.checksum) {
+                    Ok(_) => {
+                        sayln!("{} Matches the pinned checksum", "✅".green());
+                        return Ok(());
+                    }
+                    Err(e) => sayln!("{} {e}; downloading it again", "⚠️".yellow()),
+                }
             }
         }
 
-        // Use best available downloader
-        let downloader = Downloader::detect_best();
-        println!(
+        if options.dedupe_downloads
+            && blobs::is_sha256(&download.checksum)
+            && BlobStore::new()?.link_existing(&download.checksum, &download_path)?
+        {
+            sayln!(
+                "{} Reusing stored archive with the same checksum: {}",
+                "ℹ️".yellow(),
+                download_path.display()
+            );
+            return Ok(());
+        }
+
+        // Use the requested downloader, or the best available one
+        let downloader = forced_downloader
+            .clone()
+            .unwrap_or_else(Downloader::detect_best);
+        sayln!(
             "{} Using downloader: {}",
             "📥".blue(),
             downloader.description()
         );
 
-        // Download the file
-        downloader.download(&download.link, &download_path, Some(full_name))?;
+        // Download the file; only an auto-detected aria2 may fall back to reqwest
+        let reused = downloader.download_with_mirrors(
+            &download.link,
+            &download_path,
+            Some(full_name),
+            forced_downloader.is_some(),
+            &download.checksum,
+        )?;
+
+        if options.dedupe_downloads {
+            BlobStore::new()?.adopt(&download_path)?;
+        }
 
-        println!();
-        println!(
+        sayln!();
+        if !blobs::is_sha256(&download.checksum) {
+            sayln!(
+                "{} No SHA-256 checksum is published for this download; it was not verified",
+                "⚠️".yellow()
+            );
+        }
+        sayln!(
             "{} {} downloaded successfully!",
             "✅".green(),
             full_name.green().bold()
         );
-        println!("  Location: {}", download_path.display());
+        sayln!("  Location: {}", download_path.display());
+        if reused > 0 {
+            sayln!("  Reused:   {} from a partial download", HumanBytes(reused));
+        }
+
+        Ok(())
+    }
+
+    /// Download every artifact of a release into per-platform directories,
+    /// verifying checksums and reporting the outcome for each platform
+    fn download_all_platforms(
+        target_item: &AndroidStudio,
+        download_dir: &Path,
+        forced_downloader: Option<Downloader>,
+    ) -> Result<(), AstudiosError> {
+        if target_item.downloads.is_empty() {
+            return Err(AstudiosError::Download(format!(
+                "No downloads are listed for {}",
+                target_item.name
+            )));
+        }
+
+        let downloader = forced_downloader
+            .clone()
+            .unwrap_or_else(Downloader::detect_best);
+        println!(
+            "{} Using downloader: {}",
+            "📥".blue(),
+            downloader.description()
+        );
+
+        let mut results = Vec::new();
+        for download in &target_item.downloads {
+            let platform = download.platform_name();
+            let default_filename = format!(
+                "android-studio-{}-{}",
+                target_item.version,
+                download.platform_id()
+            );
+            let filename = Downloader::resolve_filename(&download.link, &default_filename);
+            let download_path = download_dir.join(download.platform_id()).join(filename);
+
+            println!();
+            println!("{} {} ({})", "📦".blue(), platform.bold(), download.size);
+
+            let outcome = Self::download_verified(
+                &downloader,
+                forced_downloader.is_some(),
+                download,
+                &download_path,
+            );
+
+            results.push((platform, download_path, outcome));
+        }
+
+        println!();
+        println!(
+            "{} Summary for {}:",
+            "📋".green().bold(),
+            target_item.name.bold()
+        );
+        let mut failed = 0;
+        for (platform, path, outcome) in &results {
+            match outcome {
+                Ok(note) => println!(
+                    "   {} {platform}: {note} ({})",
+                    "✅".green(),
+                    path.display().to_string().dimmed()
+                ),
+                Err(e) => {
+                    failed += 1;
+                    println!("   {} {platform}: {}", "❌".red(), e.to_string().red());
+                }
+            }
+        }
 
+        if failed > 0 {
+            return Err(AstudiosError::Download(format!(
+                "{failed} of {} platform downloads failed",
+                results.len()
+            )));
+        }
         Ok(())
     }
 
+    /// Download one artifact unless it is already present, then check its SHA-256 digest
+    fn download_verified(
+        downloader: &Downloader,
+        exact: bool,
+        download: &Download,
+        download_path: &Path,
+    ) -> Result<&'static str, AstudiosError> {
+        let platform = download.platform_name();
+        let verified = if fs::metadata(download_path).is_ok_and(|m| m.len() > 0) {
+            sayln!("      {} File already exists", "ℹ️".yellow());
+            Downloader::verify_checksum(download_path, &download.checksum)?
+        } else {
+            downloader.download_with_mirrors(
+                &download.link,
+                download_path,
+                Some(platform),
+                exact,
+                &download.checksum,
+            )?;
+            blobs::is_sha256(&download.checksum)
+        };
+
+        Ok(if verified {
+            "downloaded and verified"
+        } else {
+            "downloaded, no checksum to verify"
+        })
+    }
+
     /// Handle the uninstall command
-    fn handle_uninstall(version: &str) -> Result<(), AstudiosError> {
+    fn handle_uninstall(version: &str, keep_files: bool) -> Result<(), AstudiosError> {
         let installer = Installer::new()?;
+        confirm::confirm(&format!("uninstall Android Studio {version}"), || {
+            let plan = installer.plan_uninstall(version, keep_files, &BlobStore::new()?)?;
+            let mut summary = vec![format!(
+                "{} {} ({})",
+                "🗑️".red(),
+                plan.installation.enhanced_display_name().green(),
+                plan.installation.path.display()
+            )];
+            if !plan.symlinks.is_empty() {
+                summary.push(format!("   {}", "It is the active version".yellow()));
+            }
+            summary.push(format!("   Frees {}", HumanBytes(plan.reclaimable_bytes())));
+            Ok(summary)
+        })?;
 
         println!();
         println!("{} Uninstalling Android Studio {}...", "🗑️".red(), version);
         println!();
 
-        installer.uninstall_version(version)?;
+        installer.uninstall_version(version, keep_files)?;
 
         println!();
         println!(
@@ -403,18 +1828,260 @@ impl CommandHandler {
         Ok(())
     }
 
-    /// Handle the use command to switch versions
-    fn handle_use(version: &str) -> Result<(), AstudiosError> {
+    /// Handle `uninstall --dry-run`, listing what would be removed
+    fn handle_uninstall_dry_run(version: &str, keep_files: bool) -> Result<(), AstudiosError> {
         let installer = Installer::new()?;
+        let plan = installer.plan_uninstall(version, keep_files, &BlobStore::new()?)?;
+        let installation = &plan.installation;
+
+        println!();
+        println!(
+            "{} Uninstalling {} would remove:",
+            "🔍".blue(),
+            installation.enhanced_display_name().green()
+        );
+        println!();
+        for symlink_path in &plan.symlinks {
+            println!(
+                "  Symlink: {} {}",
+                symlink_path.display(),
+                "(active launcher)".yellow()
+            );
+        }
+        println!(
+            "  Bundle:  {} ({})",
+            installation.path.display(),
+            HumanBytes(plan.bundle_bytes)
+        );
+        if let Some(version_dir) = &plan.version_dir {
+            if plan.keep_files {
+                println!("  Kept:    {} (--keep-files)", version_dir.display());
+            } else {
+                println!(
+                    "  Files:   {} ({})",
+                    version_dir.display(),
+                    HumanBytes(plan.version_dir_bytes)
+                );
+            }
+        }
+        for (blob, size) in &plan.blobs {
+            println!("  Archive: {} ({})", blob.display(), HumanBytes(*size));
+        }
+
+        println!();
+        println!(
+            "Would reclaim {}. Nothing was removed.",
+            HumanBytes(plan.reclaimable_bytes())
+                .to_string()
+                .yellow()
+                .bold()
+        );
+
+        Ok(())
+    }
+
+    /// Handle the set-channel command; `None` clears the label
+    fn handle_set_channel(
+        version: &str,
+        channel: Option<ReleaseChannel>,
+    ) -> Result<(), AstudiosError> {
+        let mut installation = Installer::new()?.find_installed(version)?;
+        installation.channel_override = channel.map(|channel| channel.label().to_string());
+
+        let mut state = State::load()?;
+        state.set_channel_override(
+            installation.path.clone(),
+            installation.channel_override.clone(),
+        );
+        state.save()?;
+
+        match channel {
+            Some(channel) => println!(
+                "{} {} is now shown as {}",
+                "✅".green(),
+                installation.display_name(),
+                channel.label().cyan()
+            ),
+            None => println!(
+                "{} {} is shown as detected again ({})",
+                "✅".green(),
+                installation.display_name(),
+                installation.channel().cyan()
+            ),
+        }
+
+        Ok(())
+    }
+
+    /// Handle the use command to switch versions
+    fn handle_use(
+        version: Option<&str>,
+        path: Option<&Path>,
+        applications_dir: Option<&str>,
+        channel: Option<ReleaseChannel>,
+    ) -> Result<(), AstudiosError> {
+        let mut installer = Self::installer_for(applications_dir)?;
+        if let Some(channel) = channel {
+            installer = installer.with_symlink_channel(channel);
+        }
+
+        if let Some(path) = path {
+            let installed = installer.switch_to_path(path)?;
+            if installed.path.parent() != Some(installer.applications_dir()) {
+                let mut preferences = Preferences::load()?;
+                preferences.add_external_studio(installed.path.clone());
+                preferences.save()?;
+            }
+            println!(
+                "{} Now using {} ({})",
+                "✅".green(),
+                installed.display_name(),
+                installed.path.display()
+            );
+            return Ok(());
+        }
+
+        let version = version.unwrap_or_default();
         installer.switch_to_version(version)?;
         println!("{} Now using Android Studio {}", "✅".green(), version);
         Ok(())
     }
 
+    /// Installed bundles, including those outside the applications directory that
+    /// were selected with `use --path`
+    fn installed_with_external(
+        installer: &Installer,
+    ) -> Result<Vec<InstalledAndroidStudio>, AstudiosError> {
+        let mut installations = installer.list_installed_studios()?;
+
+        for path in Preferences::load()?.external_studios {
+            if installations.iter().any(|i| i.path == path) {
+                continue;
+            }
+            if let Ok(Some(installed)) = installer.installed_at(path) {
+                installations.push(installed);
+            }
+        }
+        Ok(installations)
+    }
+
+    /// Installation of `build`, looking in the applications directory, among
+    /// externally selected bundles and in `directory`
+    fn find_installed_build(
+        installer: &Installer,
+        directory: Option<&str>,
+        build: &str,
+    ) -> Result<Option<InstalledAndroidStudio>, AstudiosError> {
+        let mut installations = Self::installed_with_external(installer)?;
+        if let Some(dir) = directory {
+            installations.extend(
+                Installer::with_directories(Config::versions_dir()?, PathBuf::from(dir))?
+                    .list_installed_studios()?,
+            );
+        }
+
+        Ok(installations
+            .into_iter()
+            .find(|install| install.version.build_version == build))
+    }
+
     /// Handle the installed command to show installed versions
-    fn handle_installed() -> Result<(), AstudiosError> {
+    fn handle_installed(
+        tree: bool,
+        sort: InstalledSort,
+        reverse: bool,
+        json: bool,
+        verify: bool,
+        filters: InstalledFilters,
+        group: bool,
+    ) -> Result<(), AstudiosError> {
         let installer = Installer::new()?;
-        let installations = installer.list_installed_studios()?;
+        let mut installations = Self::installed_with_external(&installer)?;
+        if filters.include_toolbox {
+            for installed in Installer::list_toolbox_studios()? {
+                if !installations.iter().any(|i| i.path == installed.path) {
+                    installations.push(installed);
+                }
+            }
+        }
+        match sort {
+            InstalledSort::Version => installations.sort_by(|a, b| b.cmp(a)),
+            InstalledSort::Date => {
+                installations.sort_by_key(|i| std::cmp::Reverse(i.installed_at()))
+            }
+            InstalledSort::Path => installations.sort_by(|a, b| a.path.cmp(&b.path)),
+        }
+        if reverse {
+            installations.reverse();
+        }
+        if let Some(track) = &filters.major {
+            let track = major_track(track);
+            installations.retain(|i| i.version.major_track() == track);
+        }
+        if group {
+            // Stable, so the chosen order holds within each track
+            installations
+                .sort_by_key(|i| std::cmp::Reverse(major_track_key(&i.version.major_track())));
+        }
+
+        if tree {
+            Self::print_installed_tree(&installer, &installations);
+            return Ok(());
+        }
+
+        let mut sizes = SizeCache::load()?;
+        let bundle_sizes: Vec<u64> = installations
+            .iter()
+            .map(|installation| sizes.size_of(&installation.path))
+            .collect();
+        sizes.retain_existing();
+        // The cache only saves time; listing works without it
+        let _ = sizes.save();
+        let total_size: u64 = bundle_sizes.iter().sum();
+
+        let active = installer.get_active_studio()?;
+        let active_id = active.as_ref().map(|a| a.identifier());
+
+        // Structural and code signature problems per bundle, when asked for
+        let problems: Vec<Option<String>> = installations
+            .iter()
+            .map(|installation| {
+                if !verify {
+                    return None;
+                }
+                installer
+                    .verify_installation(&installation.path)
+                    .and_then(|()| Installer::verify_signature(&installation.path))
+                    .err()
+                    .map(|e| e.to_string())
+            })
+            .collect();
+
+        if json {
+            let report = InstalledReport {
+                installations: installations
+                    .iter()
+                    .zip(&bundle_sizes)
+                    .zip(&problems)
+                    .map(|((installation, &size_bytes), problem)| InstalledEntry {
+                        version: installation.extract_detailed_version(),
+                        build: installation.identifier(),
+                        path: installation.path.clone(),
+                        active: active_id.as_ref() == Some(&installation.identifier()),
+                        size_bytes,
+                        architectures: installation.architectures(),
+                        toolbox: filters
+                            .include_toolbox
+                            .then(|| Installer::is_toolbox_managed(&installation.path)),
+                        healthy: verify.then_some(problem.is_none()),
+                        problem: problem.clone(),
+                    })
+                    .collect(),
+                total_size_bytes: total_size,
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
 
         if installations.is_empty() {
             println!("{} No Android Studio versions installed", "⚠️".yellow());
@@ -424,10 +2091,19 @@ impl CommandHandler {
             println!("{} Installed Android Studio versions:", "📋".green().bold());
             println!();
 
-            let active = installer.get_active_studio()?;
-            let active_id = active.as_ref().map(|a| a.identifier());
+            let count = installations.len();
+            let healthy = problems.iter().filter(|problem| problem.is_none()).count();
+            let mut current_track = None;
+            for ((installation, size), problem) in
+                installations.into_iter().zip(bundle_sizes).zip(problems)
+            {
+                let track = installation.version.major_track();
+                if group && current_track.as_ref() != Some(&track) {
+                    println!("  {} {}", "Track".bold(), track.magenta().bold());
+                    println!();
+                    current_track = Some(track);
+                }
 
-            for installation in installations {
                 let is_active = active_id.as_ref() == Some(&installation.identifier());
 
                 // Enhanced display name with better formatting
@@ -436,17 +2112,24 @@ impl CommandHandler {
 
                 // Status indicator with better visual distinction
                 let status_indicator = if is_active {
-                    "✅ [Selected]".green().bold()
+                    " ✅ [Selected]".green().bold()
+                } else {
+                    "".normal()
+                };
+                // Read-only for astudios: Toolbox updates and removes these itself
+                let toolbox = if Installer::is_toolbox_managed(&installation.path) {
+                    " (Toolbox)".magenta()
                 } else {
                     "".normal()
                 };
 
                 // Main version line with improved formatting and proper spacing
-                if is_active {
-                    println!("   {} {}", enhanced_name.cyan().bold(), status_indicator);
-                } else {
-                    println!("   {}", enhanced_name.cyan().bold());
-                }
+                println!(
+                    "   {}{}{}",
+                    enhanced_name.cyan().bold(),
+                    toolbox,
+                    status_indicator
+                );
 
                 // Version and build info with better alignment and spacing
                 println!(
@@ -458,33 +2141,237 @@ impl CommandHandler {
                 // Path with proper formatting
                 let path_str = installation.path.display().to_string();
                 println!("        Path: {}", path_str.dimmed());
+                println!("        Size: {}", HumanBytes(size).to_string().yellow());
+                if let Some(architectures) = installation.architectures() {
+                    let arch = match architectures.as_slice() {
+                        [single] => single.clone(),
+                        _ => format!("universal ({})", architectures.join(", ")),
+                    };
+                    if needs_rosetta(&architectures) {
+                        println!("        Arch: {} {}", arch, "(runs under Rosetta)".yellow());
+                    } else {
+                        println!("        Arch: {arch}");
+                    }
+                }
+                if verify {
+                    match problem {
+                        None => println!("        Health: {} {}", "✅".green(), "OK".green()),
+                        Some(problem) => {
+                            println!("        Health: {} {}", "⚠️".yellow(), problem.yellow())
+                        }
+                    }
+                }
 
                 println!();
             }
+
+            println!(
+                "   {} {} across {count} installation(s)",
+                "Total:".bold(),
+                HumanBytes(total_size).to_string().yellow().bold()
+            );
+            if verify {
+                let summary = format!("{healthy} of {count} installation(s) healthy");
+                if healthy == count {
+                    println!("   {} {}", "Verified:".bold(), summary.green());
+                } else {
+                    println!("   {} {}", "Verified:".bold(), summary.yellow());
+                }
+            }
         }
 
         Ok(())
     }
 
-    /// Handle the which command to show current version
-    fn handle_which() -> Result<(), AstudiosError> {
-        let installer = Installer::new()?;
-        let active = installer.get_active_studio()?;
+    /// Print the symlinks in the applications directory and the bundles they point to
+    fn print_installed_tree(installer: &Installer, installations: &[InstalledAndroidStudio]) {
+        let applications_dir = installer.applications_dir();
+        let active = fs::canonicalize(applications_dir.join("Android Studio.app")).ok();
+
+        let mut links: Vec<PathBuf> = fs::read_dir(applications_dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|path| {
+                        let name = path.file_name().unwrap_or_default().to_string_lossy();
+                        path.is_symlink()
+                            && name.contains("Android Studio")
+                            && name.ends_with(".app")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        links.sort();
+
+        let (managed, external): (Vec<_>, Vec<_>) = installations
+            .iter()
+            .partition(|installation| installation.path.parent() == Some(applications_dir));
+
+        println!("📂 {}", applications_dir.display().to_string().bold());
+        let count = links.len() + managed.len();
+        let branch = |index: usize| {
+            if index + 1 == count {
+                "└──"
+            } else {
+                "├──"
+            }
+        };
 
-        match active {
-            Some(installation) => {
+        for (index, link) in links.iter().enumerate() {
+            let name = link.file_name().unwrap_or_default().to_string_lossy();
+            let target = fs::read_link(link).unwrap_or_default();
+            let target = target.display().to_string();
+            if link.exists() {
                 println!(
-                    "{} Currently using {} ({})",
-                    "✅".green(),
-                    installation.display_name().green(),
-                    installation.identifier().blue()
+                    "{} {} → {}",
+                    branch(index),
+                    name.cyan(),
+                    target.green().bold()
+                );
+            } else {
+                println!(
+                    "{} {} → {} {}",
+                    branch(index),
+                    name.cyan(),
+                    target.red(),
+                    "(broken link)".red().bold()
+                );
+            }
+        }
+
+        let describe = |installation: &InstalledAndroidStudio, label: String| {
+            let details = format!(
+                "({}, {})",
+                installation.extract_detailed_version(),
+                installation.identifier()
+            );
+            if active.as_deref() == Some(installation.path.as_path()) {
+                format!(
+                    "{} {} {}",
+                    label.green().bold(),
+                    details.dimmed(),
+                    "✅ [Selected]".green()
+                )
+            } else {
+                format!("{} {}", label, details.dimmed())
+            }
+        };
+
+        for (index, installation) in managed.iter().enumerate() {
+            let name = installation.path.file_name().unwrap_or_default();
+            let label = name.to_string_lossy().to_string();
+            println!(
+                "{} {}",
+                branch(links.len() + index),
+                describe(installation, label)
+            );
+        }
+
+        if !external.is_empty() {
+            println!("📂 {}", "External".bold());
+            for (index, installation) in external.iter().enumerate() {
+                let branch = if index + 1 == external.len() {
+                    "└──"
+                } else {
+                    "├──"
+                };
+                let mut label = installation.path.display().to_string();
+                if Installer::is_toolbox_managed(&installation.path) {
+                    label.push_str(" (Toolbox)");
+                }
+                println!("{} {}", branch, describe(installation, label));
+            }
+        }
+    }
+
+    /// Handle the which command to show current version
+    fn handle_which(
+        applications_dir: Option<&str>,
+        channel: Option<ReleaseChannel>,
+        json: bool,
+        include_toolbox: bool,
+    ) -> Result<(), AstudiosError> {
+        let mut installer = Self::installer_for(applications_dir)?;
+        if let Some(channel) = channel {
+            installer = installer.with_symlink_channel(channel);
+        }
+        let active = installer.get_active_studio()?;
+        // Only the stable launcher falls back to a default bundle
+        let fallback = match active {
+            None if channel.is_none() => match Self::default_studio(&installer)? {
+                None if include_toolbox => Installer::list_toolbox_studios()?.into_iter().next(),
+                fallback => fallback,
+            },
+            _ => None,
+        };
+        let toolbox_label = |installation: &InstalledAndroidStudio| {
+            if Installer::is_toolbox_managed(&installation.path) {
+                " (Toolbox)".magenta()
+            } else {
+                "".normal()
+            }
+        };
+
+        if json {
+            let symlink_path =
+                installer.channel_symlink_path(channel.unwrap_or(ReleaseChannel::Release));
+            let managed = active.is_some();
+            let report = match active.or(fallback) {
+                Some(installation) => WhichReport {
+                    active: true,
+                    identifier: Some(installation.identifier()),
+                    toolbox: include_toolbox
+                        .then(|| Installer::is_toolbox_managed(&installation.path)),
+                    short_version: Some(installation.version.short_version),
+                    path: Some(installation.path),
+                    symlink_target: fs::read_link(&symlink_path).ok(),
+                    managed: Some(managed),
+                },
+                None => WhichReport {
+                    active: false,
+                    path: None,
+                    symlink_target: fs::read_link(&symlink_path).ok(),
+                    managed: None,
+                    identifier: None,
+                    short_version: None,
+                    toolbox: None,
+                },
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+
+        match (active, fallback) {
+            (Some(installation), _) => {
+                println!(
+                    "{} Currently using {} ({}){}",
+                    "✅".green(),
+                    installation.display_name().green(),
+                    installation.identifier().blue(),
+                    toolbox_label(&installation)
                 );
                 println!(
                     "   Path: {}",
                     installation.path.display().to_string().dimmed()
                 );
             }
-            None => {
+            (None, Some(installation)) => {
+                println!(
+                    "{} No symlink; treating {} ({}){} as active",
+                    "ℹ️".blue(),
+                    installation.display_name().green(),
+                    installation.identifier().blue(),
+                    toolbox_label(&installation)
+                );
+                println!(
+                    "   Path: {}",
+                    installation.path.display().to_string().dimmed()
+                );
+                println!();
+                println!("Use 'astudios use <version>' to create the symlink");
+            }
+            (None, None) => {
                 println!(
                     "{} Android Studio is not installed or symlink is missing",
                     "⚠️".yellow()
@@ -494,6 +2381,28 @@ impl CommandHandler {
             }
         }
 
+        if channel.is_none() {
+            let others: Vec<_> = installer
+                .active_studios()?
+                .into_iter()
+                .filter(|(channel, _)| {
+                    channel.symlink_name() != ReleaseChannel::Release.symlink_name()
+                })
+                .collect();
+            if !others.is_empty() {
+                println!();
+                println!("Other channels:");
+                for (channel, installation) in others {
+                    println!(
+                        "   {}: {} ({})",
+                        channel.label().cyan(),
+                        installation.display_name().green(),
+                        installation.identifier().blue()
+                    );
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -501,7 +2410,12 @@ impl CommandHandler {
     fn handle_open(path: &str) -> Result<(), AstudiosError> {
         let installer = Installer::new()?;
 
-        let app_path = match installer.get_active_studio()? {
+        let active = match installer.get_active_studio()? {
+            Some(studio) => Some(studio),
+            None => Self::default_studio(&installer)?,
+        };
+
+        let app_path = match active {
             Some(studio) => studio.path,
             None => {
                 // Fall back to the default symlink path
@@ -554,24 +2468,224 @@ impl CommandHandler {
         Ok(())
     }
 
+    /// Handle the open-dir command to reveal an astudios directory in the file manager
+    fn handle_open_dir(target: DirTarget) -> Result<(), AstudiosError> {
+        let dir = match target {
+            DirTarget::Versions => Config::versions_dir()?,
+            DirTarget::Cache => Config::cache_dir()?,
+            DirTarget::Config => Config::config_dir()?,
+            DirTarget::Apps => Config::default_applications_dir(),
+        };
+
+        fs::create_dir_all(&dir)?;
+
+        println!(
+            "{} Opening {}...",
+            "📂".blue(),
+            dir.display().to_string().cyan()
+        );
+
+        Self::open_with_system(dir.as_os_str())
+    }
+
+    /// Open a path or URL with the platform's default handler
+    fn open_with_system(target: &OsStr) -> Result<(), AstudiosError> {
+        let opener = if cfg!(target_os = "macos") {
+            "open"
+        } else if cfg!(target_os = "windows") {
+            "explorer"
+        } else {
+            "xdg-open"
+        };
+
+        let status = Command::new(opener)
+            .arg(target)
+            .status()
+            .map_err(|e| AstudiosError::General(format!("Failed to run {opener}: {e}")))?;
+
+        // explorer.exe reports a non-zero exit code even when it opens the target
+        if !status.success() && !cfg!(target_os = "windows") {
+            return Err(AstudiosError::General(format!(
+                "Failed to open {}",
+                target.to_string_lossy()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Handle the info command to show the details of one release
+    fn handle_info(version: &str, open: bool, json: bool) -> Result<(), AstudiosError> {
+        let lister = AndroidStudioLister::new()?;
+        let item = lister.find_version_by_query(version)?;
+        let latest_in_channel = lister
+            .latest_in_each_channel(&lister.get_releases()?.items)
+            .contains(&item.build);
+        let outdated = item.is_outdated(Config::outdated_after_months(), &model::today());
+
+        if json {
+            let report = InfoReport {
+                name: &item.name,
+                version: &item.version,
+                build: &item.build,
+                channel: &item.channel,
+                latest_in_channel,
+                platform_build: &item.platform_build,
+                platform_version: &item.platform_version,
+                date: &item.date,
+                outdated,
+                download: item.get_platform_download().map(|download| ProbeDownload {
+                    platform: download.platform_id(),
+                    url: &download.link,
+                    size: &download.size,
+                    checksum: &download.checksum,
+                }),
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+
+        println!("{}", item.display_name().green().bold());
+        println!();
+        println!("   {} {}", "Version:".dimmed(), item.version.cyan());
+        println!("   {} {}", "Build:".dimmed(), item.build.blue());
+        if latest_in_channel {
+            println!(
+                "   {} {} {}",
+                "Channel:".dimmed(),
+                item.channel,
+                "(latest in channel)".cyan()
+            );
+        } else {
+            println!("   {} {}", "Channel:".dimmed(), item.channel);
+        }
+        match item.platform_version_parsed() {
+            Some((branch, build, fix)) => println!(
+                "   {} {} ({})",
+                "Platform:".dimmed(),
+                item.platform_version,
+                format!("{branch}.{build}.{fix}").blue()
+            ),
+            None if !item.platform_build.is_empty() => println!(
+                "   {} {} {}",
+                "Platform:".dimmed(),
+                item.platform_build,
+                "(unrecognized platform build)".yellow()
+            ),
+            None => {}
+        }
+        if outdated {
+            println!(
+                "   {} {} {}",
+                "Released:".dimmed(),
+                item.date,
+                OUTDATED_NOTE.yellow()
+            );
+        } else {
+            println!("   {} {}", "Released:".dimmed(), item.date);
+        }
+
+        match item.get_platform_download() {
+            Some(download) => {
+                println!("   {} {}", "Size:".dimmed(), download.size.yellow());
+                println!("   {} {}", "Download:".dimmed(), download.link.dimmed());
+            }
+            None => println!(
+                "   {} {}",
+                "Download:".dimmed(),
+                "not available for macOS".yellow()
+            ),
+        }
+
+        Self::print_release_notes(&item, open)
+    }
+
+    /// Handle the outdated command to compare installed versions with the latest release
+    fn handle_outdated(open: bool) -> Result<(), AstudiosError> {
+        let installer = Installer::new()?;
+        let lister = AndroidStudioLister::new()?;
+
+        let latest = lister.get_latest_release()?;
+        let installations = installer.list_installed_studios()?;
+
+        if installations
+            .iter()
+            .any(|installed| installed.version.build_version == latest.build)
+        {
+            println!(
+                "{} The latest release {} is installed",
+                "✅".green(),
+                latest.version.green().bold()
+            );
+            return Ok(());
+        }
+
+        match installations.first() {
+            Some(newest) => println!(
+                "{} {} is available (newest installed: {})",
+                "🆕".green(),
+                latest.display_name().green().bold(),
+                newest.extract_detailed_version().cyan()
+            ),
+            None => println!(
+                "{} {} is available (nothing installed yet)",
+                "🆕".green(),
+                latest.display_name().green().bold()
+            ),
+        }
+        println!("   Run 'astudios install --latest' to upgrade");
+
+        Self::print_release_notes(&latest, open)
+    }
+
+    /// Print where to read about a release, opening the page if requested
+    fn print_release_notes(item: &AndroidStudio, open: bool) -> Result<(), AstudiosError> {
+        let url = item.release_notes_url();
+        println!();
+        println!("   {} {}", "Release notes:".dimmed(), url.cyan());
+
+        if open {
+            Self::open_with_system(OsStr::new(url))?;
+        }
+
+        Ok(())
+    }
+
     /// Handle the update command to refresh version cache
     fn handle_update() -> Result<(), AstudiosError> {
         let reporter = ProgressReporter::new(true);
 
-        // Force refresh by clearing cache
+        // Fetch regardless of the cache's age or `--max-age`; the old list stays
+        // until this succeeds
         let lister = AndroidStudioLister::new()?;
-        let releases = lister.get_releases()?;
+        let summary = lister.refresh()?;
 
         reporter.finish_with_success("Version list updated");
 
         println!(
             "{} Found {} available versions",
             "✅".green(),
-            releases.items.len()
+            summary.current.items.len()
         );
 
+        if summary.previous.is_some() {
+            let new_releases = summary.new_releases();
+            if new_releases.is_empty() {
+                println!("{} No new versions since the last update", "ℹ️".blue());
+            } else {
+                println!(
+                    "{} {} new version(s) available since the last update:",
+                    "🆕".green(),
+                    new_releases.len()
+                );
+                for item in new_releases {
+                    println!("  {} - {}", item.version.green(), item.name);
+                }
+            }
+        }
+
         // Show the latest few versions
-        let latest_versions: Vec<_> = releases.items.into_iter().take(5).collect();
+        let latest_versions: Vec<_> = summary.current.items.into_iter().take(5).collect();
 
         if !latest_versions.is_empty() {
             println!();
@@ -583,11 +2697,654 @@ impl CommandHandler {
                     ReleaseChannel::Canary => "Canary".red(),
                     ReleaseChannel::ReleaseCandidate => "RC".blue(),
                     ReleaseChannel::Patch => "Patch".cyan(),
+                    ReleaseChannel::FeatureDrop => "Feature Drop".magenta(),
                 };
                 println!("  {} - {} ({}", item.version.green(), item.build, channel);
             }
         }
 
+        if let Some(version) = Self::newer_astudios_version() {
+            Self::print_astudios_update(&version);
+        }
+
         Ok(())
     }
+
+    /// Handle the audit command to record the integrity of installed bundles
+    fn handle_audit(sha_manifest: bool, output: Option<&Path>) -> Result<(), AstudiosError> {
+        let installations = Self::installed_with_external(&Installer::new()?)?;
+        if output.is_some() && sha_manifest {
+            println!(
+                "{} Hashing every file of {} installation(s); this can take a while...",
+                "🔎".blue(),
+                installations.len()
+            );
+        }
+
+        let report = AuditReport::new(&installations, sha_manifest)?;
+        let json = serde_json::to_string_pretty(&report)?;
+
+        match output {
+            Some(path) => {
+                fs::write(path, json + "\n")?;
+                println!(
+                    "{} Audit of {} installation(s) written to {}",
+                    "✅".green(),
+                    report.bundles.len(),
+                    path.display()
+                );
+            }
+            None => println!("{json}"),
+        }
+
+        Ok(())
+    }
+
+    /// Handle the verify command: check each installed bundle's structure and
+    /// signature and, with `repair`, reinstall the failing ones from the feed
+    fn handle_verify(repair: bool) -> Result<(), AstudiosError> {
+        let installer = Installer::new()?;
+        let installations = Self::installed_with_external(&installer)?;
+        if installations.is_empty() {
+            println!("{} No Android Studio installations found", "ℹ️".blue());
+            return Ok(());
+        }
+
+        let mut broken = Vec::new();
+        for installation in installations {
+            let check = installer
+                .verify_installation(&installation.path)
+                .and_then(|()| Installer::verify_signature(&installation.path));
+            match check {
+                Ok(()) => println!(
+                    "{} {} ({})",
+                    "✅".green(),
+                    installation.path.display(),
+                    installation.version.build_version.dimmed()
+                ),
+                Err(e) => {
+                    println!(
+                        "{} {} ({})",
+                        "❌".red(),
+                        installation.path.display(),
+                        installation.version.build_version.dimmed()
+                    );
+                    println!("   {e}");
+                    broken.push(installation);
+                }
+            }
+        }
+
+        if broken.is_empty() {
+            return Ok(());
+        }
+        if !repair {
+            return Err(AstudiosError::General(format!(
+                "{} installation(s) failed verification; run `astudios verify --repair` to reinstall them",
+                broken.len()
+            )));
+        }
+
+        let lister = AndroidStudioLister::new()?;
+        let mut unrepaired = 0;
+        let mut repairs = Vec::new();
+        for installation in &broken {
+            let build = &installation.version.build_version;
+            match lister.find_by_build(build)? {
+                Some(release) => repairs.push((installation, release)),
+                None => {
+                    println!();
+                    println!(
+                        "{} Skipping {}: build {build} is not in the releases feed, so it cannot be downloaded again",
+                        "⚠️".yellow(),
+                        installation.path.display()
+                    );
+                    unrepaired += 1;
+                }
+            }
+        }
+
+        if !repairs.is_empty() {
+            println!();
+            confirm::confirm(
+                &format!("reinstall {} installation(s)", repairs.len()),
+                || {
+                    Ok(repairs
+                        .iter()
+                        .map(|(installation, release)| {
+                            format!(
+                                "{} {} with {}",
+                                "🔧".blue(),
+                                installation.path.display(),
+                                release.name.cyan()
+                            )
+                        })
+                        .collect())
+                },
+            )?;
+        }
+
+        for (installation, release) in &repairs {
+            println!();
+            println!(
+                "{} Repairing {} with {}",
+                "🔧".blue(),
+                installation.path.display(),
+                release.name.cyan()
+            );
+            let result = Self::select_mac_arch(release, false).and_then(|mac_arch| {
+                Installer::new()?
+                    .with_mac_arch(mac_arch)
+                    .reinstall(&installation.path, release)
+                    .map(|_| ())
+            });
+            match Self::record_history(HistoryAction::Install, &release.version, result) {
+                Ok(()) => println!("{} Repaired {}", "✅".green(), installation.path.display()),
+                Err(e) => {
+                    eprintln!(
+                        "{} Could not repair {}: {e}",
+                        "❌".red(),
+                        installation.path.display()
+                    );
+                    unrepaired += 1;
+                }
+            }
+        }
+
+        if unrepaired > 0 {
+            return Err(AstudiosError::General(format!(
+                "{unrepaired} of {} broken installation(s) could not be repaired",
+                broken.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Handle the history command to show recent operations
+    fn handle_history(limit: usize, json: bool) -> Result<(), AstudiosError> {
+        let entries = History::open()?.recent(limit)?;
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+            return Ok(());
+        }
+
+        if entries.is_empty() {
+            println!("{} No operations recorded yet", "ℹ️".blue());
+            return Ok(());
+        }
+
+        for entry in &entries {
+            let result = match entry.result {
+                HistoryResult::Success => "ok".green(),
+                HistoryResult::Failure => "failed".red(),
+            };
+            println!(
+                "{}  {:<11}  {:<24}  {}",
+                history::format_utc(entry.timestamp).dimmed(),
+                entry.action.label(),
+                entry.version,
+                result
+            );
+            if let Some(error) = &entry.error {
+                println!("    {}", error.lines().next().unwrap_or_default().dimmed());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle the status command to summarize the overall astudios state
+    fn handle_status(json: bool) -> Result<(), AstudiosError> {
+        let installer = Installer::new()?;
+        let lister = AndroidStudioLister::new()?;
+
+        let active = installer.get_active_studio()?;
+        let installations = installer.list_installed_studios()?;
+        let installed_size_bytes = installations.iter().map(|i| i.disk_size()).sum();
+
+        let cache = CacheStatus {
+            path: lister.cache_path(),
+            age_secs: lister.cache_age()?.map(|age| age.as_secs()),
+            fresh: lister.is_cache_fresh()?,
+        };
+
+        // The update check is best-effort so status still works offline
+        let latest = lister.get_latest_release().ok();
+        let update_available = latest.as_ref().map(|latest| {
+            !installations
+                .iter()
+                .any(|installed| installed.version.build_version == latest.build)
+        });
+
+        let report = StatusReport {
+            active: active.as_ref().map(|a| StatusVersion {
+                version: a.extract_detailed_version(),
+                build: a.identifier(),
+                path: Some(a.path.clone()),
+            }),
+            installed_count: installations.len(),
+            installed_size_bytes,
+            cache,
+            latest_release: latest.as_ref().map(|l| StatusVersion {
+                version: l.version.clone(),
+                build: l.build.clone(),
+                path: None,
+            }),
+            update_available,
+            unverified_installs: State::load()?.unverified_installs().len(),
+            astudios_update: Self::newer_astudios_version(),
+            outdated_installs: Self::outdated_installs(&lister, &installations)
+                .into_iter()
+                .map(|(installed, _)| StatusVersion {
+                    version: installed.extract_detailed_version(),
+                    build: installed.identifier(),
+                    path: Some(installed.path.clone()),
+                })
+                .collect(),
+        };
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+
+        println!("{} astudios status", "📊".green().bold());
+        println!();
+
+        match &report.active {
+            Some(active) => {
+                println!(
+                    "   {} {} ({})",
+                    "Active:".dimmed(),
+                    active.version.green().bold(),
+                    active.build.blue()
+                );
+                if let Some(path) = &active.path {
+                    println!("           {}", path.display().to_string().dimmed());
+                }
+            }
+            None => println!("   {} {}", "Active:".dimmed(), "none".yellow()),
+        }
+
+        println!(
+            "   {} {} ({})",
+            "Installed:".dimmed(),
+            report.installed_count.to_string().cyan(),
+            HumanBytes(report.installed_size_bytes)
+        );
+
+        let cache_state = match report.cache.age_secs {
+            None => "missing".yellow(),
+            Some(age) if report.cache.fresh => {
+                format!("fresh, updated {} ago", Self::format_age(age)).green()
+            }
+            Some(age) => format!("stale, updated {} ago", Self::format_age(age)).yellow(),
+        };
+        println!("   {} {}", "Cache:".dimmed(), cache_state);
+
+        if report.unverified_installs > 0 {
+            println!(
+                "   {} {} install(s) without checksum verification",
+                "Unverified:".dimmed(),
+                report.unverified_installs.to_string().yellow()
+            );
+        }
+
+        if !report.outdated_installs.is_empty() {
+            let versions: Vec<_> = report
+                .outdated_installs
+                .iter()
+                .map(|outdated| outdated.version.as_str())
+                .collect();
+            println!(
+                "   {} {} {}",
+                "Outdated:".dimmed(),
+                versions.join(", ").yellow(),
+                OUTDATED_NOTE.yellow()
+            );
+        }
+
+        match (&report.latest_release, report.update_available) {
+            (Some(latest), Some(true)) => println!(
+                "   {} {} is available (run 'astudios install --latest')",
+                "Update:".dimmed(),
+                latest.version.green().bold()
+            ),
+            (Some(latest), _) => println!(
+                "   {} latest release {} is installed",
+                "Update:".dimmed(),
+                latest.version.green()
+            ),
+            (None, _) => println!(
+                "   {} {}",
+                "Update:".dimmed(),
+                "unknown (could not load the version list)".yellow()
+            ),
+        }
+
+        if let Some(version) = &report.astudios_update {
+            Self::print_astudios_update(version);
+        }
+
+        Ok(())
+    }
+
+    /// Installations whose release is older than [`Config::outdated_after_months`],
+    /// matched to the releases list by build; empty when the list cannot be loaded
+    fn outdated_installs<'a>(
+        lister: &AndroidStudioLister,
+        installations: &'a [InstalledAndroidStudio],
+    ) -> Vec<(&'a InstalledAndroidStudio, AndroidStudio)> {
+        if installations.is_empty() {
+            return Vec::new();
+        }
+        let Ok(releases) = lister.get_releases() else {
+            return Vec::new();
+        };
+        let (months, today) = (Config::outdated_after_months(), model::today());
+
+        installations
+            .iter()
+            .filter_map(|installed| {
+                releases
+                    .items
+                    .iter()
+                    .find(|item| item.build == installed.version.build_version)
+                    .filter(|item| item.is_outdated(months, &today))
+                    .map(|item| (installed, item.clone()))
+            })
+            .collect()
+    }
+
+    /// Newer astudios release found by the opt-in self-update check
+    fn newer_astudios_version() -> Option<String> {
+        SelfUpdateCheck::from_env()?.newer_version(env!("CARGO_PKG_VERSION"))
+    }
+
+    /// One-line notice that a newer astudios is available
+    fn print_astudios_update(version: &str) {
+        println!();
+        println!(
+            "{} astudios {} is available (you have {}); run 'cargo install astudios' to update",
+            "ℹ️".blue(),
+            version.green().bold(),
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+
+    /// Format an age in seconds as a short human-readable string
+    fn format_age(secs: u64) -> String {
+        match secs {
+            s if s < 60 => format!("{s}s"),
+            s if s < 60 * 60 => format!("{}m", s / 60),
+            s if s < 60 * 60 * 24 => format!("{}h", s / (60 * 60)),
+            s => format!("{}d", s / (60 * 60 * 24)),
+        }
+    }
+
+    /// Handle the benchmark command to compare the throughput of the downloaders
+    fn handle_benchmark(url: Option<&str>) -> Result<(), AstudiosError> {
+        let url = match url {
+            Some(url) => url.to_string(),
+            None => {
+                let latest = AndroidStudioLister::new()?.get_latest_release()?;
+                latest
+                    .require_platform_download_for(MacArch::host())?
+                    .link
+                    .clone()
+            }
+        };
+
+        let mut downloaders = vec![Downloader::Reqwest];
+        if let Ok(path) = Downloader::find_aria2() {
+            downloaders.push(Downloader::Aria2(path));
+        }
+
+        let max_bytes = Config::BENCHMARK_MAX_BYTES;
+        let time_limit = Duration::from_secs(Config::BENCHMARK_TIME_LIMIT_SECS);
+        println!(
+            "{} Downloading up to {} from {url}",
+            "📶".blue(),
+            HumanBytes(max_bytes)
+        );
+        println!();
+
+        let scratch = tempfile::Builder::new()
+            .prefix("astudios-benchmark-")
+            .tempdir()?;
+        println!(
+            "  {:<40} {:>12} {:>8} {:>14}",
+            "Downloader".bold(),
+            "Downloaded".bold(),
+            "Time".bold(),
+            "Throughput".bold()
+        );
+        for downloader in &downloaders {
+            let description = downloader.description();
+            match downloader.benchmark(&url, scratch.path(), max_bytes, time_limit) {
+                Ok(result) => println!(
+                    "  {:<40} {:>12} {:>7.1}s {:>12}/s",
+                    description,
+                    HumanBytes(result.bytes).to_string(),
+                    result.elapsed.as_secs_f64(),
+                    HumanBytes(result.bytes_per_sec()).to_string()
+                ),
+                Err(e) => println!("  {:<40} {}", description, format!("failed: {e}").red()),
+            }
+        }
+
+        println!();
+        if downloaders.len() < 2 {
+            println!(
+                "{} aria2 is not installed; install it (e.g. `brew install aria2`) to compare",
+                "ℹ️".blue()
+            );
+        }
+        println!(
+            "{} Downloads use {} by default",
+            "💡".blue(),
+            Downloader::detect_best().description().cyan()
+        );
+
+        Ok(())
+    }
+
+    /// Handle the doctor command to report on every prerequisite check
+    fn handle_doctor(json: bool, strict: bool) -> Result<(), AstudiosError> {
+        let mut result = SystemDetector::detect_system_requirements(
+            &Config::versions_dir()?,
+            &Config::default_applications_dir(),
+            true,
+        )?;
+
+        let state = State::load()?;
+        let unverified = state.unverified_installs();
+        if unverified.is_empty() {
+            result.add_pass(
+                "integrity",
+                "Every recorded install was verified against its checksum".to_string(),
+            );
+        } else {
+            let paths: Vec<_> = unverified.iter().map(|p| p.display().to_string()).collect();
+            result.add_warning(
+                "integrity",
+                format!(
+                    "{} install(s) had no published checksum and were not verified: {}",
+                    unverified.len(),
+                    paths.join(", ")
+                ),
+            );
+        }
+
+        // Old releases are only pointed out; they never fail the checks
+        let installations = Installer::new()?.list_installed_studios()?;
+        for (installed, release) in
+            Self::outdated_installs(&AndroidStudioLister::new()?, &installations)
+        {
+            result.add_info(
+                "age",
+                format!(
+                    "{} was released on {} {}",
+                    installed.extract_detailed_version(),
+                    release.date,
+                    OUTDATED_NOTE
+                ),
+            );
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        } else {
+            println!("{} Checking system requirements...", "🩺".blue());
+            println!();
+
+            for check in &result.checks {
+                let (icon, detail) = match check.status {
+                    CheckStatus::Pass => ("✅".green(), check.detail.normal()),
+                    CheckStatus::Info => ("ℹ️".blue(), check.detail.normal()),
+                    CheckStatus::Warn if strict => ("❌".red(), check.detail.red()),
+                    CheckStatus::Warn => ("⚠️".yellow(), check.detail.yellow()),
+                    CheckStatus::Fail => ("❌".red(), check.detail.red()),
+                };
+                println!("   {} {:<14} {}", icon, check.check.bold(), detail);
+            }
+
+            println!();
+            if result.has_failures() {
+                println!("{} System requirements not met", "❌".red());
+            } else if result.has_warnings() && strict {
+                println!(
+                    "{} System requirements not met: warnings are errors with --strict",
+                    "❌".red()
+                );
+            } else if result.has_warnings() {
+                println!("{} System requirements met with warnings", "⚠️".yellow());
+            } else {
+                println!("{} All checks passed", "✅".green());
+            }
+        }
+
+        if result.has_failures() {
+            return Err(AstudiosError::PrerequisiteNotMet(
+                "One or more system checks failed".to_string(),
+            ));
+        }
+        if strict && result.has_warnings() {
+            return Err(AstudiosError::PrerequisiteNotMet(
+                "One or more system checks reported warnings (--strict)".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Handle `config check`: show the resolved settings, then every problem with them
+    fn handle_config_check() -> Result<(), AstudiosError> {
+        let display = |dir: Result<PathBuf, AstudiosError>| {
+            dir.map_or_else(
+                |e| e.to_string().red(),
+                |dir| dir.display().to_string().cyan(),
+            )
+        };
+
+        println!("{} astudios configuration", "🔧".blue());
+        println!();
+        println!("   {} {}", "Home:".dimmed(), display(Config::config_dir()));
+        println!("   {} {}", "Cache:".dimmed(), display(Config::cache_dir()));
+        println!(
+            "   {} {}",
+            "Versions:".dimmed(),
+            display(Config::versions_dir())
+        );
+        println!(
+            "   {} {}",
+            "Applications:".dimmed(),
+            Config::default_applications_dir()
+                .display()
+                .to_string()
+                .cyan()
+        );
+        println!(
+            "   {} {}",
+            "Releases feed:".dimmed(),
+            Config::releases_feed_url().cyan()
+        );
+        println!(
+            "   {} {}",
+            "Downloader:".dimmed(),
+            Downloader::detect_best().description().cyan()
+        );
+        println!();
+
+        Config::validate()?;
+        println!("{} No problems found", "✅".green());
+        Ok(())
+    }
+
+    /// Handle the shim command to install, remove or describe the `studio` command
+    fn handle_shim(
+        install: bool,
+        uninstall: bool,
+        bin_dir: Option<PathBuf>,
+    ) -> Result<(), AstudiosError> {
+        let bin_dir = match bin_dir {
+            Some(dir) => dir,
+            None => Config::default_bin_dir()?,
+        };
+        let shim = Shim::in_dir(&bin_dir);
+        let shim_path = shim.path().display().to_string();
+
+        if install {
+            shim.install(&std::env::current_exe()?)?;
+            println!("{} Installed {}", "✅".green(), shim_path.cyan());
+            if !astudios::shim::is_on_path(&bin_dir) {
+                println!();
+                println!(
+                    "{} {} is not on your PATH. Add this line to your shell profile:",
+                    "ℹ️".blue(),
+                    bin_dir.display()
+                );
+                println!("   export PATH=\"{}:$PATH\"", bin_dir.display());
+            }
+        } else if uninstall {
+            if shim.uninstall()? {
+                println!("{} Removed {}", "✅".green(), shim_path.cyan());
+            } else {
+                println!("{} No astudios shim at {}", "ℹ️".blue(), shim_path);
+            }
+        } else if shim.is_installed() {
+            println!("{} Shim installed at {}", "✅".green(), shim_path.cyan());
+        } else {
+            println!("{} No shim installed at {}", "ℹ️".blue(), shim_path);
+            println!();
+            println!("Use 'astudios shim --install' to create it");
+        }
+
+        Ok(())
+    }
+
+    /// Replace this process with the selected Android Studio's command-line launcher
+    fn exec_active_launcher(args: &[OsString]) -> Result<(), AstudiosError> {
+        use std::os::unix::process::CommandExt;
+
+        let installer = Installer::new()?;
+        let active = match installer.get_active_studio()? {
+            Some(studio) => studio,
+            None => Self::default_studio(&installer)?.ok_or_else(|| {
+                AstudiosError::General(
+                    "No Android Studio installation found. Use 'astudios install <version>' to install a version"
+                        .to_string(),
+                )
+            })?,
+        };
+
+        let executable = InstalledAndroidStudio::bundle_executable(&active.path)?;
+        let launcher = active.path.join("Contents").join("MacOS").join(executable);
+        let error = Command::new(&launcher).args(args).exec();
+        Err(AstudiosError::General(format!(
+            "Failed to run {}: {error}",
+            launcher.display()
+        )))
+    }
 }
@@ -1,4 +1,114 @@
-use std::path::PathBuf;
+use crate::{error::AstudiosError, json_file::JsonFile, preferences::Preferences};
+use reqwest::{
+    Certificate,
+    blocking::{Client, ClientBuilder},
+};
+use std::{
+    ffi::CString,
+    net::{IpAddr, Ipv4Addr},
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
+/// Process-wide network settings (set from global CLI flags)
+static NETWORK_SETTINGS: OnceLock<NetworkSettings> = OnceLock::new();
+
+/// Network behaviour shared by every HTTP client and aria2 invocation
+#[derive(Debug, Clone, Default)]
+pub struct NetworkSettings {
+    /// Only connect over IPv4 (for networks with broken IPv6 routing)
+    pub prefer_ipv4: bool,
+    /// Retry a failed download from `mirrors`, in order
+    pub prefer_mirror_on_failure: bool,
+    /// Base URLs of download mirrors, from `ASTUDIOS_MIRRORS`
+    pub mirrors: Vec<String>,
+    /// PEM bundle of extra trusted root certificates, from `ASTUDIOS_CA_BUNDLE`
+    pub ca_bundle: Option<PathBuf>,
+    /// Skip TLS certificate verification entirely
+    pub insecure: bool,
+    /// Seconds allowed to establish a connection, overriding `Config::CONNECT_TIMEOUT_SECS`
+    pub connect_timeout_secs: Option<u64>,
+    /// Seconds allowed for a release list request or a stalled download, overriding
+    /// `Config::NETWORK_TIMEOUT_SECS` and `Config::DOWNLOAD_IDLE_TIMEOUT_SECS`
+    pub timeout_secs: Option<u64>,
+}
+
+impl NetworkSettings {
+    /// Read network settings from `ASTUDIOS_*` environment variables
+    pub fn from_env() -> Self {
+        Self {
+            prefer_ipv4: Config::env_flag("ASTUDIOS_PREFER_IPV4"),
+            prefer_mirror_on_failure: Config::env_flag("ASTUDIOS_PREFER_MIRROR_ON_FAILURE"),
+            mirrors: std::env::var("ASTUDIOS_MIRRORS")
+                .map(|value| Self::parse_mirrors(&value))
+                .unwrap_or_default(),
+            ca_bundle: std::env::var_os("ASTUDIOS_CA_BUNDLE")
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            // Only ever enabled by the explicit --insecure flag
+            insecure: false,
+            connect_timeout_secs: None,
+            timeout_secs: None,
+        }
+    }
+
+    /// Time allowed to establish a connection, so unreachable hosts fail fast
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.connect_timeout_secs
+                .unwrap_or(Config::CONNECT_TIMEOUT_SECS),
+        )
+    }
+
+    /// Seconds given by `--timeout`, or `default` when it is not set
+    pub fn request_timeout_secs(&self, default: u64) -> u64 {
+        self.timeout_secs.unwrap_or(default)
+    }
+
+    /// Read the certificates of the configured CA bundle, if any
+    pub fn ca_certificates(&self) -> Result<Vec<Certificate>, AstudiosError> {
+        let Some(path) = &self.ca_bundle else {
+            return Ok(Vec::new());
+        };
+        let pem = std::fs::read(path).map_err(|e| {
+            AstudiosError::Config(format!("Cannot read CA bundle {}: {e}", path.display()))
+        })?;
+        let certificates = Certificate::from_pem_bundle(&pem).map_err(|e| {
+            AstudiosError::Config(format!("Invalid CA bundle {}: {e}", path.display()))
+        })?;
+        if certificates.is_empty() {
+            return Err(AstudiosError::Config(format!(
+                "CA bundle {} contains no PEM certificates",
+                path.display()
+            )));
+        }
+        Ok(certificates)
+    }
+
+    /// Split a comma- or whitespace-separated list of mirror base URLs
+    pub fn parse_mirrors(value: &str) -> Vec<String> {
+        value
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|mirror| !mirror.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Override the network settings for every client created by this process
+    pub fn set_override(settings: NetworkSettings) {
+        let _ = NETWORK_SETTINGS.set(settings);
+    }
+
+    /// The network settings in effect for this process
+    pub fn current() -> NetworkSettings {
+        NETWORK_SETTINGS
+            .get()
+            .cloned()
+            .unwrap_or_else(Self::from_env)
+    }
+}
 
 /// Application configuration constants and utilities
 pub struct Config;
@@ -10,11 +120,20 @@ impl Config {
     /// Default cache expiration time (24 hours)
     pub const CACHE_DURATION_SECS: u64 = 60 * 60 * 24;
 
+    /// Cache expiration time for install and download (1 hour), so a release
+    /// that just shipped is found
+    pub const INSTALL_CACHE_DURATION_SECS: u64 = 60 * 60;
+
     /// Default network timeout for API requests (30 seconds)
     pub const NETWORK_TIMEOUT_SECS: u64 = 30;
 
-    /// Default download timeout (5 minutes)
-    pub const DOWNLOAD_TIMEOUT_SECS: u64 = 300;
+    /// Default time the built-in downloader waits for data before giving up
+    /// (1 minute); a download that keeps receiving data is never cut off
+    pub const DOWNLOAD_IDLE_TIMEOUT_SECS: u64 = 60;
+
+    /// Default time allowed to establish a connection (10 seconds), separate
+    /// from the request timeouts that also cover the body transfer
+    pub const CONNECT_TIMEOUT_SECS: u64 = 10;
 
     /// Maximum download retry attempts
     pub const MAX_DOWNLOAD_RETRIES: u32 = 3;
@@ -28,9 +147,52 @@ impl Config {
     /// Minimum split size for aria2 downloads
     pub const ARIA2_MIN_SPLIT_SIZE: &'static str = "1M";
 
+    /// Longest wait for the aria2 RPC daemon to start answering, or to exit
+    pub const ARIA2_RPC_STARTUP_TIMEOUT_SECS: u64 = 5;
+
+    /// Delay between download status queries to the aria2 RPC daemon
+    pub const ARIA2_RPC_POLL_INTERVAL_MS: u64 = 500;
+
+    /// Default number of DMG images that may be mounted at the same time
+    pub const DEFAULT_CONCURRENT_EXTRACTIONS: usize = 1;
+
+    /// Seconds `hdiutil attach` may take before the mount is abandoned
+    pub const MOUNT_TIMEOUT_SECS: u64 = 60;
+
+    /// Age in months after which a release is flagged as outdated
+    pub const OUTDATED_AFTER_MONTHS: u32 = 18;
+
+    /// Upper bound on the default number of concurrent batch downloads, so
+    /// many-core machines do not saturate the network
+    pub const MAX_DEFAULT_PARALLEL_DOWNLOADS: usize = 4;
+
+    /// Where the opt-in self-update check looks up the latest astudios release
+    pub const SELF_UPDATE_URL: &'static str = "https://crates.io/api/v1/crates/astudios";
+
+    /// How often the self-update check queries the network (7 days)
+    pub const SELF_UPDATE_CHECK_INTERVAL_SECS: u64 = 60 * 60 * 24 * 7;
+
+    /// Timeout for the self-update check, kept short so it never holds up a command
+    pub const SELF_UPDATE_TIMEOUT_SECS: u64 = 3;
+
+    /// Delay between connectivity probes while waiting for the network (1 second)
+    pub const NETWORK_POLL_INTERVAL_MS: u64 = 1000;
+
+    /// Most data a `benchmark` probe downloads per downloader (16 MiB)
+    pub const BENCHMARK_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+    /// Longest a `benchmark` probe runs per downloader
+    pub const BENCHMARK_TIME_LIMIT_SECS: u64 = 15;
+
+    /// Size at which the history log is rotated (1 MiB)
+    pub const HISTORY_MAX_BYTES: u64 = 1024 * 1024;
+
     /// Minimum disk space required for Android Studio installation (in GB)
     pub const MIN_DISK_SPACE_GB: u64 = 8;
 
+    /// Oldest macOS release supported by current Android Studio releases
+    pub const MIN_MACOS_VERSION: &'static str = "10.14";
+
     /// Minimum RAM recommended for Android Studio (in GB)
     pub const MIN_RAM_GB: u64 = 8;
 
@@ -38,46 +200,340 @@ impl Config {
     pub const DETECTION_TIMEOUT_SECS: u64 = 10;
 
     /// Default download directory (now points to versions directory)
-    pub fn default_download_dir() -> PathBuf {
+    pub fn default_download_dir() -> Result<PathBuf, AstudiosError> {
         Self::versions_dir()
     }
 
-    /// Application cache directory
-    pub fn cache_dir() -> PathBuf {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".")
-            .join(Self::APP_NAME)
-            .join("cache")
+    /// The user's home directory, from `$HOME` or the platform lookup
+    pub fn home_dir() -> Result<PathBuf, AstudiosError> {
+        std::env::var_os("HOME")
+            .filter(|home| !home.is_empty())
+            .map(PathBuf::from)
+            .or_else(dirs::home_dir)
+            .ok_or_else(|| {
+                AstudiosError::Config(
+                    "Cannot determine the home directory. Set HOME, or ASTUDIOS_HOME to choose where astudios keeps its data.".to_string(),
+                )
+            })
+    }
+
+    /// Application configuration directory, the parent of the cache and versions directories.
+    ///
+    /// `ASTUDIOS_HOME` overrides the location under the home directory.
+    pub fn config_dir() -> Result<PathBuf, AstudiosError> {
+        if let Some(dir) = Self::astudios_home() {
+            return Ok(dir);
+        }
+
+        Ok(Self::home_dir()?.join(".").join(Self::APP_NAME))
+    }
+
+    /// Application cache directory.
+    ///
+    /// Outside macOS, a set `XDG_CACHE_HOME` places it at `$XDG_CACHE_HOME/astudios`.
+    pub fn cache_dir() -> Result<PathBuf, AstudiosError> {
+        if let Some(dir) = Self::xdg_dir("XDG_CACHE_HOME", dirs::cache_dir) {
+            return Ok(dir);
+        }
+
+        Ok(Self::config_dir()?.join("cache"))
+    }
+
+    /// `ASTUDIOS_HOME`, if set
+    fn astudios_home() -> Option<PathBuf> {
+        std::env::var_os("ASTUDIOS_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// XDG base directory for astudios when `var` is set, on platforms that follow XDG.
+    ///
+    /// `ASTUDIOS_HOME` takes precedence, and without the variable the
+    /// `~/.astudios` layout is kept so existing data stays where it is.
+    fn xdg_dir(var: &str, base_dir: fn() -> Option<PathBuf>) -> Option<PathBuf> {
+        if cfg!(target_os = "macos") || Self::astudios_home().is_some() {
+            return None;
+        }
+        std::env::var_os(var).filter(|dir| !dir.is_empty())?;
+        base_dir().map(|dir| dir.join(Self::APP_NAME))
+    }
+
+    /// Lock file serializing commands that modify installations
+    pub fn lock_path() -> Result<PathBuf, AstudiosError> {
+        Ok(Self::config_dir()?.join("astudios.lock"))
     }
 
-    /// Application versions directory
-    pub fn versions_dir() -> PathBuf {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".")
-            .join(Self::APP_NAME)
-            .join("versions")
+    /// Content-addressed archive store used by `--dedupe-downloads`
+    pub fn blobs_dir() -> Result<PathBuf, AstudiosError> {
+        Ok(Self::cache_dir()?.join("blobs"))
     }
 
-    /// Default applications directory (macOS)
+    /// Application versions directory.
+    ///
+    /// Outside macOS, a set `XDG_DATA_HOME` places it at `$XDG_DATA_HOME/astudios/versions`.
+    pub fn versions_dir() -> Result<PathBuf, AstudiosError> {
+        if let Some(dir) = Self::xdg_dir("XDG_DATA_HOME", dirs::data_dir) {
+            return Ok(dir.join("versions"));
+        }
+
+        Ok(Self::config_dir()?.join("versions"))
+    }
+
+    /// Default directory for the `studio` shim
+    pub fn default_bin_dir() -> Result<PathBuf, AstudiosError> {
+        Ok(Self::home_dir()?.join(".local").join("bin"))
+    }
+
+    /// Default applications directory (macOS), overridable with `ASTUDIOS_APPS_DIR`
+    /// for Macs where `/Applications` is not writable
     pub fn default_applications_dir() -> PathBuf {
-        PathBuf::from("/Applications")
+        std::env::var_os("ASTUDIOS_APPS_DIR")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("/Applications"))
+    }
+
+    /// Where JetBrains Toolbox installs its apps, overridable with `ASTUDIOS_TOOLBOX_DIR`
+    pub fn toolbox_apps_dir() -> Result<PathBuf, AstudiosError> {
+        if let Some(dir) = std::env::var_os("ASTUDIOS_TOOLBOX_DIR").filter(|dir| !dir.is_empty()) {
+            return Ok(PathBuf::from(dir));
+        }
+
+        Ok(Self::home_dir()?
+            .join("Library")
+            .join("Application Support")
+            .join("JetBrains")
+            .join("Toolbox")
+            .join("apps"))
     }
 
     /// JetBrains API endpoint for Android Studio releases
     pub const RELEASES_FEED_URL: &'static str = "https://teamcity.jetbrains.com/guestAuth/repository/download/AndroidStudioReleasesList/.lastSuccessful/android-studio-releases-list.xml";
 
+    /// Release notes for stable releases, release candidates and patches
+    pub const RELEASE_NOTES_URL: &'static str = "https://developer.android.com/studio/releases";
+
+    /// Release notes for Beta and Canary preview builds
+    pub const PREVIEW_RELEASE_NOTES_URL: &'static str =
+        "https://developer.android.com/studio/preview/features";
+
     /// User agent string for HTTP requests
     pub fn user_agent() -> String {
         format!("{}/0.1.0", Self::APP_NAME)
     }
 
+    /// HTTP client builder with the shared user agent, timeouts and network settings
+    /// applied; `timeout_secs` bounds waiting for the response and each read of its body
+    pub fn http_client_builder(timeout_secs: u64) -> ClientBuilder {
+        let settings = NetworkSettings::current();
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(timeout_secs))
+            .connect_timeout(settings.connect_timeout())
+            .user_agent(Self::user_agent());
+
+        if settings.prefer_ipv4 {
+            // Binding to the unspecified IPv4 address restricts connections to IPv4
+            builder = builder.local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        }
+
+        // The bundle is validated when the settings are applied, so a failure here
+        // only means it changed since; fall back to the system roots
+        for certificate in settings.ca_certificates().unwrap_or_default() {
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        if settings.insecure {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        builder
+    }
+
+    /// Check whether a boolean environment variable is set to a truthy value
+    pub fn env_flag(name: &str) -> bool {
+        std::env::var(name).is_ok_and(|value| Self::is_truthy(&value))
+    }
+
+    /// Interpret a flag value such as "1", "true" or "yes"
+    pub fn is_truthy(value: &str) -> bool {
+        matches!(
+            value.trim().to_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        )
+    }
+
     /// Get minimum disk space requirement in GB
     pub fn min_disk_space_gb() -> u64 {
         Self::MIN_DISK_SPACE_GB
     }
 
+    /// Get the releases feed URL, overridable with `ASTUDIOS_RELEASES_FEED_URL`
+    /// for internal mirrors of the feed
+    pub fn releases_feed_url() -> String {
+        std::env::var("ASTUDIOS_RELEASES_FEED_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| Self::RELEASES_FEED_URL.to_string())
+    }
+
+    /// Get the minimum macOS version, overridable with `ASTUDIOS_MIN_MACOS_VERSION`
+    /// for releases that need a newer system than the default
+    pub fn min_macos_version() -> String {
+        std::env::var("ASTUDIOS_MIN_MACOS_VERSION")
+            .ok()
+            .filter(|version| !version.trim().is_empty())
+            .unwrap_or_else(|| Self::MIN_MACOS_VERSION.to_string())
+    }
+
+    /// How long mounting a disk image may take, overridable in seconds with
+    /// `ASTUDIOS_MOUNT_TIMEOUT` for slow disks
+    pub fn mount_timeout() -> Duration {
+        let secs = std::env::var("ASTUDIOS_MOUNT_TIMEOUT")
+            .ok()
+            .and_then(|secs| secs.trim().parse().ok())
+            .filter(|&secs| secs > 0)
+            .unwrap_or(Self::MOUNT_TIMEOUT_SECS);
+        Duration::from_secs(secs)
+    }
+
+    /// Age in months after which releases are flagged as outdated, overridable
+    /// with `ASTUDIOS_OUTDATED_AFTER_MONTHS`; 0 turns the annotation off
+    pub fn outdated_after_months() -> u32 {
+        std::env::var("ASTUDIOS_OUTDATED_AFTER_MONTHS")
+            .ok()
+            .and_then(|months| months.trim().parse().ok())
+            .unwrap_or(Self::OUTDATED_AFTER_MONTHS)
+    }
+
+    /// Check the resolved configuration for problems that would otherwise only
+    /// surface partway through a command, reporting all of them in one error
+    pub fn validate() -> Result<(), AstudiosError> {
+        Self::report_problems(Self::problems(true))
+    }
+
+    /// One error listing every problem in `problems`, if there are any
+    pub fn report_problems(problems: Vec<String>) -> Result<(), AstudiosError> {
+        if problems.is_empty() {
+            return Ok(());
+        }
+
+        Err(AstudiosError::Config(format!(
+            "{} problem(s) with the astudios configuration:\n  - {}\nFix them and run 'astudios config check' to confirm.",
+            problems.len(),
+            problems.join("\n  - ")
+        )))
+    }
+
+    /// Every problem found in the resolved configuration, one message each;
+    /// the directories astudios writes to are only checked with `check_dirs`
+    pub fn problems(check_dirs: bool) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let mut dirs = vec![
+            ("astudios home", Self::config_dir()),
+            ("Cache directory", Self::cache_dir()),
+            ("Versions directory", Self::versions_dir()),
+        ];
+        // /Applications is only checked when installing; an explicit choice is checked up front
+        if std::env::var_os("ASTUDIOS_APPS_DIR").is_some_and(|dir| !dir.is_empty()) {
+            dirs.push((
+                "Applications directory (ASTUDIOS_APPS_DIR)",
+                Ok(Self::default_applications_dir()),
+            ));
+        }
+        for (label, dir) in dirs.into_iter().filter(|_| check_dirs) {
+            match dir {
+                Ok(dir) => problems.extend(Self::check_writable_dir(label, &dir)),
+                Err(e) => problems.push(e.to_string()),
+            }
+        }
+
+        problems.extend(Self::check_url(
+            "Releases feed (ASTUDIOS_RELEASES_FEED_URL)",
+            &Self::releases_feed_url(),
+        ));
+        if let Ok(url) = std::env::var("ASTUDIOS_SELF_UPDATE_URL")
+            && !url.trim().is_empty()
+        {
+            problems.extend(Self::check_url("ASTUDIOS_SELF_UPDATE_URL", &url));
+        }
+        let network = NetworkSettings::current();
+        for mirror in &network.mirrors {
+            problems.extend(Self::check_url("Mirror in ASTUDIOS_MIRRORS", mirror));
+        }
+        if let Err(e) = network.ca_certificates() {
+            problems.push(e.to_string());
+        }
+
+        for (name, minimum) in [
+            ("ASTUDIOS_MOUNT_TIMEOUT", 1),
+            ("ASTUDIOS_OUTDATED_AFTER_MONTHS", 0),
+        ] {
+            if let Ok(value) = std::env::var(name)
+                && !value.trim().is_empty()
+                && !value.trim().parse::<u64>().is_ok_and(|n| n >= minimum)
+            {
+                problems.push(format!(
+                    "{name} must be a whole number of at least {minimum}, not '{value}'"
+                ));
+            }
+        }
+
+        if let Err(e) = Preferences::load() {
+            let path = Preferences::path().map_or_else(
+                |_| "preferences.json".into(),
+                |path| path.display().to_string(),
+            );
+            problems.push(format!("Cannot read {path}: {e}"));
+        }
+
+        problems
+    }
+
+    /// Problem with `dir` as a directory astudios writes to: it must be a
+    /// writable directory, or be creatable inside its nearest existing ancestor.
+    ///
+    /// Permissions are asked of the system rather than probed with a test file,
+    /// so the check leaves nothing behind.
+    pub fn check_writable_dir(label: &str, dir: &Path) -> Option<String> {
+        let existing = dir.ancestors().find(|ancestor| ancestor.exists())?;
+        if !existing.is_dir() {
+            return Some(format!(
+                "{label} {} is not a directory: {} is a file",
+                dir.display(),
+                existing.display()
+            ));
+        }
+
+        let path = CString::new(existing.as_os_str().as_bytes()).ok()?;
+        // SAFETY: access only reads the NUL-terminated path
+        if unsafe { libc::access(path.as_ptr(), libc::W_OK) } == 0 {
+            return None;
+        }
+        Some(if existing == dir {
+            format!("{label} {} is not writable", dir.display())
+        } else {
+            format!(
+                "{label} {} cannot be created: {} is not writable",
+                dir.display(),
+                existing.display()
+            )
+        })
+    }
+
+    /// Problem with `url` as a place astudios downloads from: it must be an
+    /// absolute http or https URL
+    pub fn check_url(label: &str, url: &str) -> Option<String> {
+        match reqwest::Url::parse(url.trim()) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => None,
+            Ok(parsed) => Some(format!(
+                "{label} {url} uses the unsupported scheme '{}'; use http or https",
+                parsed.scheme()
+            )),
+            Err(e) => Some(format!("{label} '{url}' is not a valid URL: {e}")),
+        }
+    }
+
     /// Get minimum RAM requirement in GB
     pub fn min_ram_gb() -> u64 {
         Self::MIN_RAM_GB
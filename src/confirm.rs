@@ -0,0 +1,68 @@
+use crate::{config::Config, error::AstudiosError, json_file::JsonFile, preferences::Preferences};
+use colored::Colorize;
+use std::{
+    io::{IsTerminal, Write},
+    sync::OnceLock,
+};
+
+/// Whether confirmations are assumed in this process, decided once
+static ASSUME_YES: OnceLock<bool> = OnceLock::new();
+
+/// Answer yes to every confirmation in this process when `assume_yes` is set,
+/// as `--yes` does; otherwise the environment and preferences decide
+pub fn set_assume_yes(assume_yes: bool) {
+    let _ = ASSUME_YES.set(assume_yes || configured_assume_yes());
+}
+
+/// Whether confirmations are answered yes without asking: by `--yes`,
+/// `ASTUDIOS_ASSUME_YES`, or `"assume_yes": true` in the preferences
+pub fn assume_yes() -> bool {
+    *ASSUME_YES.get_or_init(configured_assume_yes)
+}
+
+/// Whether `ASTUDIOS_ASSUME_YES` or the preferences assume confirmations
+fn configured_assume_yes() -> bool {
+    Config::env_flag("ASTUDIOS_ASSUME_YES")
+        || Preferences::load().is_ok_and(|preferences| preferences.assume_yes)
+}
+
+/// Interpret an answer to a `[y/N]` prompt; anything but "y" or "yes" is no
+pub fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Ask before a destructive `action` (e.g. "uninstall Android Studio 2024.2"),
+/// printing the lines of `summary` first.
+///
+/// Succeeds without asking when confirmations are assumed. Without a terminal
+/// to ask on it fails rather than wait for input that never comes, and a "no"
+/// fails too, so the caller stops before changing anything.
+pub fn confirm(
+    action: &str,
+    summary: impl FnOnce() -> Result<Vec<String>, AstudiosError>,
+) -> Result<(), AstudiosError> {
+    if assume_yes() {
+        return Ok(());
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(AstudiosError::General(format!(
+            "Refusing to {action} without confirmation: stdin is not a terminal.\nPass --yes or set ASTUDIOS_ASSUME_YES=1 to confirm."
+        )));
+    }
+
+    for line in summary()? {
+        println!("{line}");
+    }
+    print!("{} Proceed to {action}? [y/N] ", "❓".blue());
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    if is_yes(&answer) {
+        Ok(())
+    } else {
+        Err(AstudiosError::General(format!(
+            "Cancelled, did not {action}"
+        )))
+    }
+}
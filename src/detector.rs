@@ -1,11 +1,42 @@
-use crate::{config::Config, error::AstudiosError};
-use std::{fs, path::Path, process::Command};
+use crate::{config::Config, error::AstudiosError, progress::ProgressReporter};
+use reqwest::{StatusCode, blocking::Client};
+use serde::Serialize;
+use std::{
+    fs,
+    path::Path,
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
 
 /// System detection and validation for pre-installation checks
 pub struct SystemDetector;
 
+/// Tools that can extract a DMG without mounting it, in order of preference
+pub const DMG_EXTRACTORS: [&str; 2] = ["7z", "unar"];
+
+/// Outcome of a single prerequisite check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    /// Informational note that never fails a check, even with `--strict`
+    Info,
+    Warn,
+    Fail,
+}
+
+/// A single message produced by a prerequisite check, attributed to that check
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    /// Machine-readable check name (e.g., "disk_space")
+    pub check: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
 /// Results of system detection checks
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DetectionResult {
     pub platform_compatible: bool,
     pub disk_space_sufficient: bool,
@@ -13,8 +44,7 @@ pub struct DetectionResult {
     pub network_available: bool,
     pub dependencies_available: bool,
     pub java_runtime_available: bool,
-    pub issues: Vec<String>,
-    pub warnings: Vec<String>,
+    pub checks: Vec<CheckResult>,
 }
 
 impl Default for DetectionResult {
@@ -32,8 +62,7 @@ impl DetectionResult {
             network_available: false,
             dependencies_available: false,
             java_runtime_available: false,
-            issues: Vec::new(),
-            warnings: Vec::new(),
+            checks: Vec::new(),
         }
     }
 
@@ -46,15 +75,63 @@ impl DetectionResult {
     }
 
     pub fn has_warnings(&self) -> bool {
-        !self.warnings.is_empty()
+        self.checks.iter().any(|c| c.status == CheckStatus::Warn)
+    }
+
+    /// Check whether the system may be used, treating warnings as failures when `strict`
+    pub fn passes(&self, strict: bool) -> bool {
+        self.is_valid() && !(strict && self.has_warnings())
+    }
+
+    /// Check whether any individual check reported a failure
+    pub fn has_failures(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Fail)
+    }
+
+    /// All failure messages, in the order the checks ran
+    pub fn issues(&self) -> Vec<&str> {
+        self.details_with_status(CheckStatus::Fail)
+    }
+
+    /// All warning messages, in the order the checks ran
+    pub fn warnings(&self) -> Vec<&str> {
+        self.details_with_status(CheckStatus::Warn)
+    }
+
+    pub fn add_issue(&mut self, check: &'static str, issue: String) {
+        self.push(check, CheckStatus::Fail, issue);
+    }
+
+    pub fn add_warning(&mut self, check: &'static str, warning: String) {
+        self.push(check, CheckStatus::Warn, warning);
+    }
+
+    /// Record an informational note for `check`
+    pub fn add_info(&mut self, check: &'static str, note: String) {
+        self.push(check, CheckStatus::Info, note);
     }
 
-    pub fn add_issue(&mut self, issue: String) {
-        self.issues.push(issue);
+    /// Record a passing result for `check` unless it already reported a warning or failure
+    pub fn add_pass(&mut self, check: &'static str, detail: String) {
+        if !self.checks.iter().any(|c| c.check == check) {
+            self.push(check, CheckStatus::Pass, detail);
+        }
+    }
+
+    fn push(&mut self, check: &'static str, status: CheckStatus, detail: String) {
+        self.checks.push(CheckResult {
+            check,
+            status,
+            detail,
+        });
     }
 
-    pub fn add_warning(&mut self, warning: String) {
-        self.warnings.push(warning);
+    fn details_with_status(&self, status: CheckStatus) -> Vec<&str> {
+        self.checks
+            .iter()
+            .filter(|c| c.status == status)
+            .map(|c| c.detail.as_str())
+            .collect()
     }
 }
 
@@ -63,6 +140,7 @@ impl SystemDetector {
     pub fn detect_system_requirements(
         install_dir: &Path,
         applications_dir: &Path,
+        check_network: bool,
     ) -> Result<DetectionResult, AstudiosError> {
         let mut result = DetectionResult::new();
 
@@ -77,8 +155,13 @@ impl SystemDetector {
         result.permissions_valid =
             Self::check_permissions(install_dir, applications_dir, &mut result)?;
 
-        // Check network connectivity
-        result.network_available = Self::check_network_connectivity(&mut result)?;
+        // Check network connectivity, unless the caller needs no network
+        result.network_available = if check_network {
+            Self::check_network_connectivity(&mut result)?
+        } else {
+            result.add_info("network", "Connectivity check skipped".to_string());
+            true
+        };
 
         // Check dependencies
         result.dependencies_available = Self::check_dependencies(&mut result)?;
@@ -95,16 +178,20 @@ impl SystemDetector {
         let arch = std::env::consts::ARCH;
 
         if os != "macos" {
-            result.add_issue(format!(
-                "Unsupported operating system: {os}. This tool only supports macOS."
-            ));
+            result.add_issue(
+                "platform",
+                format!("Unsupported operating system: {os}. This tool only supports macOS."),
+            );
             return Ok(false);
         }
 
         if arch != "x86_64" && arch != "aarch64" {
-            result.add_issue(format!(
-                "Unsupported architecture: {arch}. Android Studio requires x86_64 or aarch64 on macOS."
-            ));
+            result.add_issue(
+                "platform",
+                format!(
+                    "Unsupported architecture: {arch}. Android Studio requires x86_64 or aarch64 on macOS."
+                ),
+            );
             return Ok(false);
         }
 
@@ -112,25 +199,44 @@ impl SystemDetector {
         if let Ok(output) = Command::new("sw_vers").arg("-productVersion").output() {
             let version = String::from_utf8_lossy(&output.stdout);
             let version = version.trim();
-
-            // Parse version and check if it's at least macOS 10.14
-            if let Some(major_minor) = version.split('.').take(2).collect::<Vec<_>>().get(0..2)
-                && let (Ok(major), Ok(minor)) =
-                    (major_minor[0].parse::<u32>(), major_minor[1].parse::<u32>())
-                && (major < 10 || (major == 10 && minor < 14))
-            {
-                result.add_issue(format!(
-                            "macOS version {version} is not supported. Android Studio requires macOS 10.14 or later."
-                        ));
+            let minimum = Config::min_macos_version();
+
+            if Self::macos_version_satisfies(version, &minimum) == Some(false) {
+                result.add_issue(
+                    "platform",
+                    format!(
+                        "macOS version {version} is not supported. Android Studio requires macOS {minimum} or later."
+                    ),
+                );
                 return Ok(false);
             }
         } else {
-            result.add_warning("Could not determine macOS version".to_string());
+            result.add_warning("platform", "Could not determine macOS version".to_string());
         }
 
+        result.add_pass("platform", format!("{os} ({arch})"));
         Ok(true)
     }
 
+    /// Parse a macOS version such as "10.15.7" or "13" into (major, minor).
+    ///
+    /// Since macOS 11 the major number alone identifies a release, so a missing
+    /// minor number counts as 0.
+    pub fn parse_macos_version(version: &str) -> Option<(u32, u32)> {
+        let mut parts = version.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = match parts.next() {
+            Some(minor) => minor.parse().ok()?,
+            None => 0,
+        };
+        Some((major, minor))
+    }
+
+    /// Whether `version` is at least `minimum`; `None` if either cannot be parsed
+    pub fn macos_version_satisfies(version: &str, minimum: &str) -> Option<bool> {
+        Some(Self::parse_macos_version(version)? >= Self::parse_macos_version(minimum)?)
+    }
+
     /// Check available disk space
     fn check_disk_space(
         install_dir: &Path,
@@ -150,12 +256,15 @@ impl SystemDetector {
         match Self::get_available_space(install_dir) {
             Ok(space) => {
                 if space < required_space {
-                    result.add_issue(format!(
-                        "Insufficient disk space in {}. Required: {} GB, Available: {:.1} GB",
-                        install_dir.display(),
-                        Config::min_disk_space_gb(),
-                        space as f64 / (1024.0 * 1024.0 * 1024.0)
-                    ));
+                    result.add_issue(
+                        "disk_space",
+                        format!(
+                            "Insufficient disk space in {}. Required: {} GB, Available: {:.1} GB",
+                            install_dir.display(),
+                            Config::min_disk_space_gb(),
+                            space as f64 / (1024.0 * 1024.0 * 1024.0)
+                        ),
+                    );
                     insufficient_space = true;
                 }
             }
@@ -169,12 +278,15 @@ impl SystemDetector {
             match Self::get_available_space(applications_dir) {
                 Ok(space) => {
                     if space < required_space {
-                        result.add_issue(format!(
-                            "Insufficient disk space in {}. Required: {} GB, Available: {:.1} GB",
-                            applications_dir.display(),
-                            Config::min_disk_space_gb(),
-                            space as f64 / (1024.0 * 1024.0 * 1024.0)
-                        ));
+                        result.add_issue(
+                            "disk_space",
+                            format!(
+                                "Insufficient disk space in {}. Required: {} GB, Available: {:.1} GB",
+                                applications_dir.display(),
+                                Config::min_disk_space_gb(),
+                                space as f64 / (1024.0 * 1024.0 * 1024.0)
+                            ),
+                        );
                         insufficient_space = true;
                     }
                 }
@@ -187,10 +299,18 @@ impl SystemDetector {
         // Only add a warning if we couldn't check space at all and there's no other issue
         if space_check_failed && !insufficient_space {
             result.add_warning(
-                "Could not verify available disk space. Ensure you have sufficient space for installation.".to_string()
+                "disk_space",
+                "Could not verify available disk space. Ensure you have sufficient space for installation.".to_string(),
             );
         }
 
+        result.add_pass(
+            "disk_space",
+            format!(
+                "At least {} GB available for installation",
+                Config::min_disk_space_gb()
+            ),
+        );
         Ok(!insufficient_space)
     }
 
@@ -231,7 +351,8 @@ impl SystemDetector {
     /// Get disk space using df command (most reliable on macOS)
     fn get_space_via_df(path: &Path) -> Result<u64, AstudiosError> {
         let output = Command::new("df")
-            .args(["-k", path.to_str().unwrap_or(".")])
+            .arg("-k")
+            .arg(path)
             .output()
             .map_err(|_| AstudiosError::General("df command failed".to_string()))?;
 
@@ -331,22 +452,36 @@ impl SystemDetector {
 
         // Check install directory permissions
         if !Self::check_directory_permissions(install_dir)? {
-            result.add_issue(format!(
-                "No write permission for install directory: {}",
-                install_dir.display()
-            ));
+            result.add_issue(
+                "permissions",
+                format!(
+                    "No write permission for install directory: {}",
+                    install_dir.display()
+                ),
+            );
             permissions_ok = false;
         }
 
         // Check applications directory permissions
         if !Self::check_directory_permissions(applications_dir)? {
-            result.add_issue(format!(
-                "No write permission for applications directory: {}. You may need administrator privileges.",
-                applications_dir.display()
-            ));
+            result.add_issue(
+                "permissions",
+                format!(
+                    "No write permission for applications directory: {}. You may need administrator privileges.",
+                    applications_dir.display()
+                ),
+            );
             permissions_ok = false;
         }
 
+        result.add_pass(
+            "permissions",
+            format!(
+                "Write access to {} and {}",
+                install_dir.display(),
+                applications_dir.display()
+            ),
+        );
         Ok(permissions_ok)
     }
 
@@ -357,42 +492,99 @@ impl SystemDetector {
             fs::create_dir_all(dir)?;
         }
 
-        // Try to create a temporary file to test write permissions
-        let test_file = dir.join(".astudios-permission-test");
-        match fs::write(&test_file, "test") {
-            Ok(_) => {
-                // Clean up test file
-                let _ = fs::remove_file(&test_file);
-                Ok(true)
+        Ok(Self::probe_write(dir).is_ok())
+    }
+
+    /// Fail early when `dir` cannot be written, rather than partway through an install.
+    ///
+    /// A directory that is read-only, or protected by SIP or an MDM profile, is
+    /// reported as [`AstudiosError::PermissionDenied`] with ways around it.
+    pub fn ensure_writable(dir: &Path) -> Result<(), AstudiosError> {
+        fs::create_dir_all(dir)
+            .and_then(|_| Self::probe_write(dir))
+            .map_err(|e| Self::write_error(dir, e))
+    }
+
+    /// Translate a failed write probe of `dir` into an actionable error
+    pub fn write_error(dir: &Path, error: std::io::Error) -> AstudiosError {
+        match error.kind() {
+            std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::ReadOnlyFilesystem => {
+                AstudiosError::PermissionDenied(format!(
+                    "Cannot write to {} ({error}). It may be read-only or protected by System Integrity Protection or a device management profile.\nInstall into your home folder instead, e.g. with --directory ~/Applications --applications-dir ~/Applications, or set ASTUDIOS_APPS_DIR=~/Applications.",
+                    dir.display()
+                ))
             }
-            Err(_) => Ok(false),
+            _ => error.into(),
         }
     }
 
+    /// Create and remove a scratch file in `dir`
+    fn probe_write(dir: &Path) -> std::io::Result<()> {
+        let test_file = dir.join(".astudios-permission-test");
+        fs::write(&test_file, "test")?;
+        // Clean up test file
+        let _ = fs::remove_file(&test_file);
+        Ok(())
+    }
+
+    /// Send a HEAD request to the JetBrains API
+    fn probe_releases_feed(client: &Client) -> Result<StatusCode, reqwest::Error> {
+        client
+            .head(Config::releases_feed_url())
+            .send()
+            .map(|response| response.status())
+    }
+
+    /// Poll the JetBrains API until it is reachable or `timeout` elapses,
+    /// returning whether it became reachable
+    pub fn wait_for_network(timeout: Duration) -> Result<bool, AstudiosError> {
+        let client = Config::http_client_builder(Config::NETWORK_TIMEOUT_SECS).build()?;
+        let deadline = Instant::now() + timeout;
+        let mut reporter = ProgressReporter::new(true);
+        let spinner = reporter.create_spinner("Waiting for network...");
+
+        let reachable = loop {
+            if Self::probe_releases_feed(&client).is_ok_and(|status| status.is_success()) {
+                break true;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break false;
+            }
+            thread::sleep(remaining.min(Duration::from_millis(Config::NETWORK_POLL_INTERVAL_MS)));
+        };
+
+        spinner.finish_and_clear();
+        Ok(reachable)
+    }
+
     /// Check network connectivity
     fn check_network_connectivity(result: &mut DetectionResult) -> Result<bool, AstudiosError> {
         // Try to make a simple HEAD request to the JetBrains API
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(Config::NETWORK_TIMEOUT_SECS))
-            .user_agent(Config::user_agent())
-            .build()?;
-
-        match client.head(Config::RELEASES_FEED_URL).send() {
-            Ok(response) => {
-                if response.status().is_success() {
+        let client = Config::http_client_builder(Config::NETWORK_TIMEOUT_SECS).build()?;
+
+        match Self::probe_releases_feed(&client) {
+            Ok(status) => {
+                if status.is_success() {
+                    result.add_pass("network", "JetBrains API is reachable".to_string());
                     Ok(true)
                 } else {
-                    result.add_issue(format!(
-                        "JetBrains API is not accessible (HTTP {}). Check your internet connection.",
-                        response.status()
-                    ));
+                    result.add_issue(
+                        "network",
+                        format!(
+                            "JetBrains API is not accessible (HTTP {status}). Check your internet connection."
+                        ),
+                    );
                     Ok(false)
                 }
             }
             Err(e) => {
-                result.add_issue(format!(
-                    "Network connectivity check failed: {e}. Check your internet connection and firewall settings."
-                ));
+                result.add_issue(
+                    "network",
+                    format!(
+                        "Network connectivity check failed: {e}. Check your internet connection and firewall settings."
+                    ),
+                );
                 Ok(false)
             }
         }
@@ -406,9 +598,12 @@ impl SystemDetector {
         let required_tools = Self::get_required_tools();
         for tool in required_tools {
             if !Self::check_tool_available(tool) {
-                result.add_issue(format!(
-                    "Required tool '{tool}' not found in PATH. Please install it and try again."
-                ));
+                result.add_issue(
+                    "dependencies",
+                    format!(
+                        "Required tool '{tool}' not found in PATH. Please install it and try again."
+                    ),
+                );
                 dependencies_ok = false;
             }
         }
@@ -421,20 +616,25 @@ impl SystemDetector {
         // Check for download tools (aria2 is optional, but warn if not available)
         if crate::downloader::Downloader::find_aria2().is_err() {
             result.add_warning(
-                "aria2 not found. Downloads will use the built-in HTTP client, which may be slower. Install aria2 for faster downloads.".to_string()
+                "dependencies",
+                "aria2 not found. Downloads will use the built-in HTTP client, which may be slower. Install aria2 for faster downloads.".to_string(),
             );
         }
 
+        result.add_pass(
+            "dependencies",
+            "All required system tools are available".to_string(),
+        );
         Ok(dependencies_ok)
     }
 
     /// Get list of required system tools for macOS
     fn get_required_tools() -> Vec<&'static str> {
-        vec!["ditto", "rm", "hdiutil", "codesign"]
+        vec!["ditto", "rm", "codesign"]
     }
 
     /// Check if a tool is available in PATH
-    fn check_tool_available(tool: &str) -> bool {
+    pub fn check_tool_available(tool: &str) -> bool {
         Command::new("which")
             .arg(tool)
             .output()
@@ -444,13 +644,25 @@ impl SystemDetector {
 
     /// Check for archive extraction tools (macOS)
     fn check_archive_tools(result: &mut DetectionResult) -> Result<bool, AstudiosError> {
-        // On macOS, we need hdiutil for DMG files
+        // hdiutil mounts DMG files; 7z or unar can extract them without mounting
         if !Self::check_tool_available("hdiutil") {
-            result.add_issue(
-                "hdiutil not found. This tool is required for extracting DMG files on macOS."
-                    .to_string(),
-            );
-            return Ok(false);
+            let fallback = DMG_EXTRACTORS
+                .iter()
+                .find(|tool| Self::check_tool_available(tool));
+            match fallback {
+                Some(tool) => result.add_warning(
+                    "dependencies",
+                    format!("hdiutil not found. DMG files will be extracted with {tool} instead."),
+                ),
+                None => {
+                    result.add_issue(
+                        "dependencies",
+                        "hdiutil not found, and neither is 7z or unar. One of them is required for extracting DMG files."
+                            .to_string(),
+                    );
+                    return Ok(false);
+                }
+            }
         }
 
         Ok(true)
@@ -473,10 +685,12 @@ impl SystemDetector {
 
         if !java_found {
             result.add_warning(
-                "Java runtime not found. While Android Studio includes its own JDK, having Java installed system-wide is recommended for development.".to_string()
+                "java_runtime",
+                "Java runtime not found. While Android Studio includes its own JDK, having Java installed system-wide is recommended for development.".to_string(),
             );
         }
 
+        result.add_pass("java_runtime", "Java runtime found".to_string());
         Ok(java_found)
     }
 }
@@ -1,9 +1,26 @@
-use crate::{config::Config, error::AstudiosError};
+use crate::{
+    aria2_rpc::Aria2Daemon,
+    blobs,
+    config::{Config, NetworkSettings},
+    error::AstudiosError,
+    events::{self, InstallEvent},
+    output::HumanOutput,
+    sayln,
+};
+use colored::Colorize;
+use indicatif::HumanBytes;
+use reqwest::{
+    StatusCode,
+    header::{CONTENT_DISPOSITION, RANGE},
+};
 use std::fs;
-use std::io::Read;
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// Supported download methods with different performance characteristics
 #[derive(Debug, Clone)]
@@ -52,45 +69,373 @@ impl Downloader {
         ))
     }
 
-    /// Download a file from URL to destination
+    /// Download a file from URL to destination.
+    ///
+    /// If aria2 fails, the download is retried with the built-in HTTP client,
+    /// resuming from whatever aria2 left behind when possible. Returns the
+    /// number of bytes reused from an earlier partial download.
+    ///
+    /// This is the convenience form that cannot be interrupted; use
+    /// [`download_cancellable`](Self::download_cancellable) to offer a way to abort.
     pub fn download(
         &self,
         url: &str,
         destination: &Path,
         progress_name: Option<&str>,
-    ) -> Result<(), AstudiosError> {
+    ) -> Result<u64, AstudiosError> {
+        self.download_cancellable(url, destination, progress_name, &AtomicBool::new(false))
+    }
+
+    /// Download like [`download`](Self::download), stopping as soon as
+    /// `cancel` is set, e.g. from a GUI's cancel button on another thread.
+    ///
+    /// A cancelled download fails with [`AstudiosError::Cancelled`]: the
+    /// built-in client stops between chunks, and aria2 is killed or, when
+    /// going through an RPC daemon, removed from it. The partial file is left
+    /// in place, so downloading to the same destination again resumes it.
+    pub fn download_cancellable(
+        &self,
+        url: &str,
+        destination: &Path,
+        progress_name: Option<&str>,
+        cancel: &AtomicBool,
+    ) -> Result<u64, AstudiosError> {
+        match self.download_exact_cancellable(url, destination, progress_name, cancel) {
+            // Another downloader would run out of space just the same
+            Err(e @ AstudiosError::InsufficientResources(_)) => Err(e),
+            Err(e @ AstudiosError::Cancelled(_)) => Err(e),
+            Err(e) if matches!(self, Downloader::Aria2(_)) => {
+                sayln!(
+                    "      {} {e}; falling back to the built-in HTTP client",
+                    "⚠️".yellow()
+                );
+                Self::discard_segmented_partial(&Self::partial_path(destination))?;
+                Downloader::Reqwest.download_exact_cancellable(
+                    url,
+                    destination,
+                    progress_name,
+                    cancel,
+                )
+            }
+            result => result,
+        }
+    }
+
+    /// Download a file and check it against `checksum`, retrying from each
+    /// configured mirror when that fails.
+    ///
+    /// A checksum that is not a SHA-256 digest (for example an empty one) is not
+    /// verified; a mismatch deletes the file and counts as a failure. Mirrors are
+    /// only tried with `--prefer-mirror-on-failure`. With `exact`, the aria2
+    /// fallback of [`Downloader::download`] is disabled.
+    pub fn download_with_mirrors(
+        &self,
+        url: &str,
+        destination: &Path,
+        progress_name: Option<&str>,
+        exact: bool,
+        checksum: &str,
+    ) -> Result<u64, AstudiosError> {
+        let attempt = |source: &str| {
+            let reused = if exact {
+                self.download_exact(source, destination, progress_name)?
+            } else {
+                self.download(source, destination, progress_name)?
+            };
+            Self::verify_checksum(destination, checksum)?;
+            Ok(reused)
+        };
+
+        let settings = NetworkSettings::current();
+        if !settings.prefer_mirror_on_failure || settings.mirrors.is_empty() {
+            return attempt(url);
+        }
+
+        let mut sources = vec![url.to_string()];
+        sources.extend(
+            settings
+                .mirrors
+                .iter()
+                .filter_map(|base| mirror_url(url, base)),
+        );
+
+        let mut last_error = None;
+        for (index, source) in sources.iter().enumerate() {
+            if index > 0 {
+                sayln!("      {} Retrying from mirror {source}", "↻".blue());
+            }
+
+            match attempt(source) {
+                Ok(reused) => {
+                    sayln!("      {} Downloaded from {source}", "✅".green());
+                    return Ok(reused);
+                }
+                // Another source would run out of space just the same
+                Err(e @ AstudiosError::InsufficientResources(_)) => return Err(e),
+                Err(e) => {
+                    sayln!("      {} {source} failed: {e}", "⚠️".yellow());
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            AstudiosError::Download(format!("No download source available for {url}"))
+        }))
+    }
+
+    /// Check a downloaded file against a SHA-256 digest, deleting it on mismatch.
+    ///
+    /// Returns `false` without reading the file when `checksum` is not a
+    /// SHA-256 digest, since there is nothing to verify against.
+    pub fn verify_checksum(path: &Path, checksum: &str) -> Result<bool, AstudiosError> {
+        if !blobs::is_sha256(checksum) {
+            return Ok(false);
+        }
+
+        if blobs::sha256_hex(path)? != checksum.to_ascii_lowercase() {
+            fs::remove_file(path)?;
+            return Err(AstudiosError::Download(format!(
+                "Checksum mismatch for {}",
+                path.display()
+            )));
+        }
+        Ok(true)
+    }
+
+    /// Download a file with exactly this downloader, without falling back on failure.
+    ///
+    /// Data is written to `<destination>.<pid>.part` and only renamed into place
+    /// once complete, so concurrent downloads never write to the same file and an
+    /// interrupted download is resumed on the next attempt.
+    /// Returns the number of bytes reused from an earlier partial download.
+    pub fn download_exact(
+        &self,
+        url: &str,
+        destination: &Path,
+        progress_name: Option<&str>,
+    ) -> Result<u64, AstudiosError> {
+        self.download_exact_cancellable(url, destination, progress_name, &AtomicBool::new(false))
+    }
+
+    /// [`download_exact`](Self::download_exact), stopping once `cancel` is set
+    fn download_exact_cancellable(
+        &self,
+        url: &str,
+        destination: &Path,
+        progress_name: Option<&str>,
+        cancel: &AtomicBool,
+    ) -> Result<u64, AstudiosError> {
         // Ensure destination directory exists
         if let Some(parent) = destination.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        match self {
-            Downloader::Reqwest => self.download_with_reqwest(url, destination, progress_name),
+        let partial = Self::partial_path(destination);
+        Self::adopt_stale_partial(destination)?;
+        let present = if Self::aria2_control_file(&partial).exists() {
+            // aria2 preallocates the file, so its length says nothing about progress
+            sayln!("      {} Resuming segmented aria2 download", "↻".blue());
+            0
+        } else {
+            fs::metadata(&partial).map(|m| m.len()).unwrap_or(0)
+        };
+        if present > 0 {
+            sayln!(
+                "      {} Resuming download ({} already present)",
+                "↻".blue(),
+                HumanBytes(present)
+            );
+        }
+
+        let reused = match self {
+            Downloader::Reqwest => {
+                self.download_with_reqwest(url, &partial, progress_name, cancel)?
+            }
             Downloader::Aria2(path) => {
-                self.download_with_aria2(path, url, destination, progress_name)
+                self.download_with_aria2(path, url, &partial, progress_name, cancel)?;
+                present
+            }
+        };
+
+        fs::rename(&partial, destination)?;
+        Ok(reused)
+    }
+
+    /// Path of this process's in-progress file for a download destination
+    pub fn partial_path(destination: &Path) -> PathBuf {
+        let mut partial = destination.as_os_str().to_owned();
+        partial.push(format!(".{}.part", std::process::id()));
+        PathBuf::from(partial)
+    }
+
+    /// Take over a partial download left behind by a process that is no longer running.
+    ///
+    /// Partial files of running processes are left alone. Returns `true` if a
+    /// stale partial file was moved to this process's partial path.
+    pub fn adopt_stale_partial(destination: &Path) -> Result<bool, AstudiosError> {
+        let partial = Self::partial_path(destination);
+        let (Some(dir), Some(name)) = (destination.parent(), destination.file_name()) else {
+            return Ok(false);
+        };
+        if partial.exists() {
+            return Ok(false);
+        }
+
+        let name = name.to_string_lossy();
+        let legacy = format!("{name}.part");
+        let prefix = format!("{name}.");
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Ok(false);
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            // `<name>.part` was written before partial files carried a process id
+            if file_name != legacy {
+                let Some(owner) = file_name
+                    .strip_prefix(&prefix)
+                    .and_then(|rest| rest.strip_suffix(".part"))
+                else {
+                    continue;
+                };
+                match owner.parse::<u32>() {
+                    Ok(pid) if !process_is_running(pid) => {}
+                    _ => continue,
+                }
+            }
+
+            let stale = entry.path();
+            fs::rename(&stale, &partial)?;
+            let control_file = Self::aria2_control_file(&stale);
+            if control_file.exists() {
+                fs::rename(&control_file, Self::aria2_control_file(&partial))?;
+            }
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Pick the local file name for a download.
+    ///
+    /// A redirecting CDN may announce the real archive name in a
+    /// `Content-Disposition` header, which is preferred over the last URL path
+    /// segment. Falls back to `default` when neither yields a usable name.
+    pub fn resolve_filename(url: &str, default: &str) -> String {
+        Self::server_filename(url)
+            .or_else(|| {
+                Path::new(url.split(['?', '#']).next().unwrap_or(url))
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| default.to_string())
+    }
+
+    /// Ask the server for the file name via a HEAD request, ignoring any failure
+    fn server_filename(url: &str) -> Option<String> {
+        let client = Config::http_client_builder(Config::NETWORK_TIMEOUT_SECS)
+            .build()
+            .ok()?;
+        let response = client.head(url).send().ok()?;
+        let header = response.headers().get(CONTENT_DISPOSITION)?.to_str().ok()?;
+        parse_content_disposition(header)
+    }
+
+    /// Path of the control file aria2 keeps next to an unfinished download
+    fn aria2_control_file(destination: &Path) -> PathBuf {
+        let mut control_file = destination.as_os_str().to_owned();
+        control_file.push(".aria2");
+        PathBuf::from(control_file)
+    }
+
+    /// Remove a partial file that aria2 wrote in parallel segments.
+    ///
+    /// While its `.aria2` control file exists, the data is not contiguous and
+    /// cannot be resumed with a byte-range request.
+    fn discard_segmented_partial(destination: &Path) -> Result<(), AstudiosError> {
+        let control_file = Self::aria2_control_file(destination);
+
+        if control_file.exists() {
+            sayln!(
+                "      {} Discarding segmented partial download left by aria2",
+                "ℹ️".blue()
+            );
+            fs::remove_file(&control_file)?;
+            if destination.exists() {
+                fs::remove_file(destination)?;
             }
         }
+
+        Ok(())
     }
 
-    /// Download using reqwest (built-in HTTP client)
+    /// Download using reqwest (built-in HTTP client), resuming an existing partial file.
+    ///
+    /// Returns the number of bytes of the partial file that were kept.
     fn download_with_reqwest(
         &self,
         url: &str,
         destination: &Path,
         _progress_name: Option<&str>,
-    ) -> Result<(), AstudiosError> {
-        use reqwest::blocking::Client;
+        cancel: &AtomicBool,
+    ) -> Result<u64, AstudiosError> {
+        // The blocking client applies its timeout to each read rather than to the
+        // whole transfer, so a slow download is only cut off once it stalls
+        let idle_timeout =
+            NetworkSettings::current().request_timeout_secs(Config::DOWNLOAD_IDLE_TIMEOUT_SECS);
+        let client = Config::http_client_builder(idle_timeout).build()?;
 
-        let client = Client::builder()
-            .timeout(Duration::from_secs(Config::DOWNLOAD_TIMEOUT_SECS))
-            .build()?;
+        let existing_len = fs::metadata(destination).map(|m| m.len()).unwrap_or(0);
+        let mut request = client.get(url);
+        if existing_len > 0 {
+            request = request.header(RANGE, format!("bytes={existing_len}-"));
+        }
 
-        let mut response = client.get(url).send()?;
-        let mut file = fs::File::create(destination)?;
+        let response = request.send()?;
+        let (mut response, file, reused) = match response.status() {
+            StatusCode::PARTIAL_CONTENT => {
+                let file = fs::OpenOptions::new().append(true).open(destination)?;
+                (response, file, existing_len)
+            }
+            // The partial file already holds every byte
+            StatusCode::RANGE_NOT_SATISFIABLE if existing_len > 0 => return Ok(existing_len),
+            _ => {
+                let response = response.error_for_status()?;
+                if existing_len > 0 {
+                    sayln!(
+                        "      {} Server does not support resuming, restarting the download",
+                        "⚠️".yellow()
+                    );
+                }
+                (response, fs::File::create(destination)?, 0)
+            }
+        };
 
-        std::io::copy(&mut response, &mut file)?;
+        let total = response.content_length().map(|len| len + reused);
+        let mut file = ProgressWriter::new(file, reused, total);
+        let mut buffer = vec![0; DOWNLOAD_CHUNK_SIZE];
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                file.flush()?;
+                return Err(AstudiosError::Cancelled(format!("download of {url}")));
+            }
+            let read = match response.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if let Err(e) = file.write_all(&buffer[..read]) {
+                if is_out_of_space(&e) {
+                    let size = fs::metadata(destination).map(|m| m.len()).unwrap_or(0);
+                    return Err(Self::out_of_space(destination, size.saturating_sub(reused)));
+                }
+                return Err(e.into());
+            }
+        }
 
-        Ok(())
+        Ok(reused)
     }
 
     /// Download using aria2 for high-performance downloads
@@ -100,7 +445,18 @@ impl Downloader {
         url: &str,
         destination: &Path,
         _progress_name: Option<&str>,
+        cancel: &AtomicBool,
     ) -> Result<(), AstudiosError> {
+        if let Some(result) = Aria2Daemon::download_shared(url, destination, cancel) {
+            match result {
+                Err(AstudiosError::Network(e)) => sayln!(
+                    "      {} aria2 RPC daemon unreachable ({e}); running aria2 directly",
+                    "⚠️".yellow()
+                ),
+                result => return result,
+            }
+        }
+
         let mut cmd = Command::new(aria2_path);
 
         cmd.arg(url)
@@ -121,13 +477,29 @@ impl Downloader {
             .arg(format!("--retry-wait={}", Config::DOWNLOAD_RETRY_WAIT_SECS))
             .arg("--human-readable=true")
             .arg("--console-log-level=error")
+            .stdout(HumanOutput::child_stdout())
             .stderr(Stdio::piped());
 
+        Self::apply_aria2_network_settings(&mut cmd);
+
         let mut child = cmd.spawn()?;
-        let status = child.wait()?;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if cancel.load(Ordering::Relaxed) {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(AstudiosError::Cancelled(format!("download of {url}")));
+            }
+            thread::sleep(Duration::from_millis(CANCEL_POLL_INTERVAL_MS));
+        };
 
         if status.success() {
             Ok(())
+        } else if status.code() == Some(ARIA2_EXIT_DISK_FULL) {
+            let written = fs::metadata(destination).map(|m| m.len()).unwrap_or(0);
+            Err(Self::out_of_space(destination, written))
         } else {
             let stderr = child
                 .stderr
@@ -145,6 +517,148 @@ impl Downloader {
         }
     }
 
+    /// Pass the process-wide network settings on to an aria2 invocation
+    pub(crate) fn apply_aria2_network_settings(cmd: &mut Command) {
+        let settings = NetworkSettings::current();
+        cmd.arg(format!(
+            "--connect-timeout={}",
+            settings.connect_timeout().as_secs()
+        ));
+        if settings.prefer_ipv4 {
+            cmd.arg("--disable-ipv6=true");
+        }
+        if let Some(ca_bundle) = &settings.ca_bundle {
+            let mut flag = std::ffi::OsString::from("--ca-certificate=");
+            flag.push(ca_bundle);
+            cmd.arg(flag);
+        }
+        if settings.insecure {
+            cmd.arg("--check-certificate=false");
+        }
+    }
+
+    /// Measure throughput by downloading the start of `url` into `dir`.
+    ///
+    /// The download stops after `max_bytes` or `time_limit`, whichever comes
+    /// first, and the partial file is removed afterwards.
+    pub fn benchmark(
+        &self,
+        url: &str,
+        dir: &Path,
+        max_bytes: u64,
+        time_limit: Duration,
+    ) -> Result<BenchmarkResult, AstudiosError> {
+        let destination = dir.join(format!("astudios-benchmark-{}", std::process::id()));
+        let result = match self {
+            Downloader::Reqwest => Self::benchmark_reqwest(url, max_bytes, time_limit),
+            Downloader::Aria2(path) => {
+                Self::benchmark_aria2(path, url, &destination, max_bytes, time_limit)
+            }
+        };
+
+        let _ = fs::remove_file(&destination);
+        let _ = fs::remove_file(Self::aria2_control_file(&destination));
+        result
+    }
+
+    /// Stream the first `max_bytes` of `url` with a range request, discarding the data
+    fn benchmark_reqwest(
+        url: &str,
+        max_bytes: u64,
+        time_limit: Duration,
+    ) -> Result<BenchmarkResult, AstudiosError> {
+        let client = Config::http_client_builder(Config::NETWORK_TIMEOUT_SECS).build()?;
+        let started = Instant::now();
+        let mut response = client
+            .get(url)
+            .header(RANGE, format!("bytes=0-{}", max_bytes.saturating_sub(1)))
+            .send()?
+            .error_for_status()?;
+
+        let mut buffer = vec![0; 64 * 1024];
+        let mut bytes = 0;
+        // A server ignoring the range sends the whole file, so stop reading ourselves
+        while bytes < max_bytes && started.elapsed() < time_limit {
+            let read = response.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            bytes += read as u64;
+        }
+
+        Ok(BenchmarkResult {
+            bytes: bytes.min(max_bytes),
+            elapsed: started.elapsed(),
+        })
+    }
+
+    /// Run aria2 until it has written `max_bytes`, finished, or run out of time
+    fn benchmark_aria2(
+        aria2_path: &Path,
+        url: &str,
+        destination: &Path,
+        max_bytes: u64,
+        time_limit: Duration,
+    ) -> Result<BenchmarkResult, AstudiosError> {
+        let mut cmd = Command::new(aria2_path);
+        cmd.arg(url)
+            .arg("--dir")
+            .arg(destination.parent().unwrap_or_else(|| Path::new(".")))
+            .arg("--out")
+            .arg(destination.file_name().unwrap_or_default())
+            .arg(format!(
+                "--max-connection-per-server={}",
+                Config::ARIA2_MAX_CONNECTIONS
+            ))
+            .arg(format!("--split={}", Config::ARIA2_MAX_CONNECTIONS))
+            .arg(format!("--min-split-size={}", Config::ARIA2_MIN_SPLIT_SIZE))
+            // Without preallocation, the allocated blocks show how much has arrived
+            .arg("--file-allocation=none")
+            .arg("--allow-overwrite=true")
+            .arg("--console-log-level=error")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        Self::apply_aria2_network_settings(&mut cmd);
+
+        let received = || fs::metadata(destination).map_or(0, |m| (m.blocks() * 512).min(m.len()));
+
+        let started = Instant::now();
+        let mut child = cmd.spawn()?;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break Some(status);
+            }
+            if received() >= max_bytes || started.elapsed() >= time_limit {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        };
+        let elapsed = started.elapsed();
+
+        if status.is_some_and(|status| !status.success()) {
+            return Err(AstudiosError::Download(
+                "aria2 benchmark download failed".to_string(),
+            ));
+        }
+
+        Ok(BenchmarkResult {
+            bytes: received().min(max_bytes),
+            elapsed,
+        })
+    }
+
+    /// Error reported when the disk fills up mid-download; the partial file is
+    /// kept so the download resumes once space has been freed
+    pub(crate) fn out_of_space(partial: &Path, written: u64) -> AstudiosError {
+        AstudiosError::InsufficientResources(format!(
+            "Ran out of disk space after writing {} to {}. The partial download was kept and will resume on the next attempt.",
+            HumanBytes(written),
+            partial.display()
+        ))
+    }
+
     /// Get a human-readable description of the downloader
     pub fn description(&self) -> String {
         match self {
@@ -154,6 +668,163 @@ impl Downloader {
     }
 }
 
+/// Outcome of a bounded benchmark download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchmarkResult {
+    /// Bytes received before the download was stopped
+    pub bytes: u64,
+    pub elapsed: Duration,
+}
+
+impl BenchmarkResult {
+    /// Average throughput in bytes per second
+    pub fn bytes_per_sec(&self) -> u64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            (self.bytes as f64 / secs) as u64
+        } else {
+            0
+        }
+    }
+}
+
+/// File writer that reports download progress as whole percentages
+struct ProgressWriter<W> {
+    inner: W,
+    written: u64,
+    total: Option<u64>,
+    last_pct: Option<u8>,
+}
+
+impl<W> ProgressWriter<W> {
+    fn new(inner: W, written: u64, total: Option<u64>) -> Self {
+        Self {
+            inner,
+            written,
+            total,
+            last_pct: None,
+        }
+    }
+}
+
+impl<W: Write> Write for ProgressWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.written += written as u64;
+        if let Some(total) = self.total.filter(|&total| total > 0) {
+            let pct = (self.written.min(total) * 100 / total) as u8;
+            // Completion is reported once the download has been verified
+            if pct < 100 && self.last_pct != Some(pct) {
+                self.last_pct = Some(pct);
+                events::emit(InstallEvent::Download { pct });
+            }
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Check whether a process with the given id is still running
+fn process_is_running(pid: u32) -> bool {
+    Command::new("kill")
+        .arg("-0")
+        .arg(pid.to_string())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Point a download link at a mirror, keeping its path and query.
+///
+/// `https://dl.google.com/android/studio/x.dmg` with the mirror
+/// `https://mirror.example.com/google` becomes
+/// `https://mirror.example.com/google/android/studio/x.dmg`.
+pub fn mirror_url(link: &str, mirror: &str) -> Option<String> {
+    let link = reqwest::Url::parse(link).ok()?;
+    let base = reqwest::Url::parse(mirror).ok()?;
+    let mut url = format!("{}{}", base.as_str().trim_end_matches('/'), link.path());
+    if let Some(query) = link.query() {
+        url.push('?');
+        url.push_str(query);
+    }
+    Some(url)
+}
+
+/// Exit status aria2 uses when there is not enough disk space
+pub(crate) const ARIA2_EXIT_DISK_FULL: i32 = 9;
+
+/// Bytes read from the response between checks for cancellation
+const DOWNLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// How often a running aria2 process is checked for completion or cancellation
+const CANCEL_POLL_INTERVAL_MS: u64 = 100;
+
+/// Check whether an IO error means the disk is full
+pub fn is_out_of_space(error: &std::io::Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::StorageFull | ErrorKind::WriteZero | ErrorKind::QuotaExceeded
+    )
+}
+
+/// Extract the file name from a `Content-Disposition` header value.
+///
+/// `filename*` (RFC 5987) takes precedence over `filename`. Any directory
+/// components are stripped so the server cannot choose where the file lands.
+pub fn parse_content_disposition(value: &str) -> Option<String> {
+    let mut plain = None;
+    let mut extended = None;
+
+    for param in value.split(';').skip(1) {
+        let Some((key, raw)) = param.split_once('=') else {
+            continue;
+        };
+        let raw = raw.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "filename" => plain = Some(raw.trim_matches('"').to_string()),
+            "filename*" => {
+                // charset'language'percent-encoded-value
+                let encoded = raw.splitn(3, '\'').nth(2).unwrap_or(raw);
+                extended = percent_decode(encoded.trim_matches('"'));
+            }
+            _ => {}
+        }
+    }
+
+    extended.or(plain).and_then(|name| sanitize_filename(&name))
+}
+
+/// Decode `%XX` escapes, returning `None` for invalid escapes or UTF-8
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = value.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Keep only the final path component of a server-provided file name
+fn sanitize_filename(name: &str) -> Option<String> {
+    let name = name.rsplit(['/', '\\']).next()?.trim();
+    if name.is_empty() || name == "." || name == ".." {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
 impl Default for Downloader {
     fn default() -> Self {
         Self::detect_best()
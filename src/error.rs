@@ -19,8 +19,8 @@ pub enum AstudiosError {
     Download(String),
     /// Installation errors
     Installation(String),
-    /// Archive extraction errors
-    Extraction(String),
+    /// Archive extraction errors, with the step that failed
+    Extraction(ExtractionKind, String),
     /// Downloader not found errors
     DownloaderNotFound(String),
     /// Path-related errors
@@ -43,6 +43,8 @@ pub enum AstudiosError {
     PermissionDenied(String),
     /// Network unavailable errors
     NetworkUnavailable(String),
+    /// Operation cancelled by the caller
+    Cancelled(String),
 }
 
 impl fmt::Display for AstudiosError {
@@ -56,7 +58,7 @@ impl fmt::Display for AstudiosError {
             AstudiosError::Platform(msg) => write!(f, "Platform error: {msg}"),
             AstudiosError::Download(msg) => write!(f, "Download error: {msg}"),
             AstudiosError::Installation(msg) => write!(f, "Installation error: {msg}"),
-            AstudiosError::Extraction(msg) => write!(f, "Extraction error: {msg}"),
+            AstudiosError::Extraction(kind, msg) => write!(f, "Extraction error ({kind}): {msg}"),
             AstudiosError::DownloaderNotFound(msg) => write!(f, "Downloader not found: {msg}"),
             AstudiosError::Path(msg) => write!(f, "Path error: {msg}"),
             AstudiosError::Cache(msg) => write!(f, "Cache error: {msg}"),
@@ -68,12 +70,54 @@ impl fmt::Display for AstudiosError {
             AstudiosError::InsufficientResources(msg) => write!(f, "Insufficient resources: {msg}"),
             AstudiosError::PermissionDenied(msg) => write!(f, "Permission denied: {msg}"),
             AstudiosError::NetworkUnavailable(msg) => write!(f, "Network unavailable: {msg}"),
+            AstudiosError::Cancelled(msg) => write!(f, "Cancelled: {msg}"),
         }
     }
 }
 
 impl std::error::Error for AstudiosError {}
 
+/// Which step of extracting an archive failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractionKind {
+    /// The disk image could not be mounted or unpacked, e.g. because it is corrupt
+    Mount,
+    /// The image was readable but holds no Android Studio app bundle
+    MissingBundle,
+    /// Copying the app bundle out of the image failed, e.g. for lack of disk space
+    Copy,
+    /// The archive format is not supported
+    Unsupported,
+    /// The app bundle inside the image failed code signature verification
+    Signature,
+    /// No tool that can open the archive is available, e.g. hdiutil is missing
+    ToolMissing,
+    /// Mounting the image hung past the mount timeout, e.g. on a failing disk
+    Timeout,
+}
+
+impl ExtractionKind {
+    /// Whether downloading the archive again could fix the failure
+    pub fn redownload_may_help(self) -> bool {
+        matches!(self, ExtractionKind::Mount | ExtractionKind::Signature)
+    }
+}
+
+impl fmt::Display for ExtractionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            ExtractionKind::Mount => "mount failed",
+            ExtractionKind::MissingBundle => "no app bundle",
+            ExtractionKind::Copy => "copy failed",
+            ExtractionKind::Unsupported => "unsupported archive",
+            ExtractionKind::Signature => "invalid signature",
+            ExtractionKind::ToolMissing => "tool unavailable",
+            ExtractionKind::Timeout => "timed out",
+        };
+        f.write_str(kind)
+    }
+}
+
 impl From<std::io::Error> for AstudiosError {
     fn from(err: std::io::Error) -> Self {
         AstudiosError::Io(err)
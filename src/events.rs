@@ -0,0 +1,57 @@
+use serde::Serialize;
+use std::{
+    io::Write,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
+
+/// Observer notified of every install event in this process
+static EVENT_HANDLER: OnceLock<EventHandler> = OnceLock::new();
+
+/// Callback receiving install events as they happen
+pub type EventHandler = Box<dyn Fn(&InstallEvent) + Send + Sync>;
+
+/// Milestones of an install, for callers that track progress programmatically
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum InstallEvent {
+    /// The archive download reached `pct` percent
+    Download { pct: u8 },
+    /// The archive is being unpacked
+    Extract,
+    /// The app bundle was installed at `path`
+    Installed { path: PathBuf },
+    /// The install failed
+    Failed { error: String },
+}
+
+impl InstallEvent {
+    /// The event as a single line of JSON, without the trailing newline
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Register the observer for this process; later calls are ignored
+pub fn set_handler(handler: EventHandler) {
+    let _ = EVENT_HANDLER.set(handler);
+}
+
+/// Notify the registered observer, if any
+pub fn emit(event: InstallEvent) {
+    if let Some(handler) = EVENT_HANDLER.get() {
+        handler(&event);
+    }
+}
+
+/// Observer writing each event to `out` as a JSON record on its own line,
+/// flushed immediately so a reader sees it in real time
+pub fn json_lines_handler(out: impl Write + Send + 'static) -> EventHandler {
+    let out = Mutex::new(out);
+    Box::new(move |event| {
+        if let Ok(mut out) = out.lock() {
+            let _ = writeln!(out, "{}", event.to_json_line());
+            let _ = out.flush();
+        }
+    })
+}
@@ -0,0 +1,178 @@
+use crate::{config::Config, error::AstudiosError, model::civil_from_days};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Operation recorded in the history log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistoryAction {
+    Install,
+    Uninstall,
+    Use,
+    SetChannel,
+}
+
+impl HistoryAction {
+    /// Name of the command that performs the action
+    pub fn label(self) -> &'static str {
+        match self {
+            HistoryAction::Install => "install",
+            HistoryAction::Uninstall => "uninstall",
+            HistoryAction::Use => "use",
+            HistoryAction::SetChannel => "set-channel",
+        }
+    }
+}
+
+/// Whether a recorded operation succeeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryResult {
+    Success,
+    Failure,
+}
+
+/// One line of the history log
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct HistoryEntry {
+    /// Seconds since the Unix epoch at which the operation finished
+    pub timestamp: u64,
+    pub action: HistoryAction,
+    /// Version, path or flag the operation was asked for (e.g. "2024.2.1" or "latest")
+    pub version: String,
+    pub result: HistoryResult,
+    /// Error message of a failed operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HistoryEntry {
+    /// Record the outcome of an operation that just finished
+    pub fn new<T>(
+        action: HistoryAction,
+        version: impl Into<String>,
+        outcome: &Result<T, AstudiosError>,
+    ) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |age| age.as_secs());
+        let (result, error) = match outcome {
+            Ok(_) => (HistoryResult::Success, None),
+            Err(e) => (HistoryResult::Failure, Some(e.to_string())),
+        };
+
+        Self {
+            timestamp,
+            action,
+            version: version.into(),
+            result,
+            error,
+        }
+    }
+}
+
+/// Append-only log of mutating operations, stored as JSON lines in
+/// `~/.astudios/history.jsonl`.
+///
+/// Once the log reaches its size cap it is moved to `history.jsonl.1`,
+/// replacing the previous rotation, and a new log is started.
+pub struct History {
+    path: PathBuf,
+    max_bytes: u64,
+}
+
+impl History {
+    /// Default location of the history log
+    pub fn path() -> Result<PathBuf, AstudiosError> {
+        Ok(Config::config_dir()?.join("history.jsonl"))
+    }
+
+    /// The history log at the default location
+    pub fn open() -> Result<Self, AstudiosError> {
+        Ok(Self::at(Self::path()?))
+    }
+
+    /// The history log at `path`
+    pub fn at(path: PathBuf) -> Self {
+        Self {
+            path,
+            max_bytes: Config::HISTORY_MAX_BYTES,
+        }
+    }
+
+    /// Rotate the log once it reaches `max_bytes`
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Path the log is moved to when it is rotated
+    pub fn rotated_path(&self) -> PathBuf {
+        let mut rotated = self.path.as_os_str().to_owned();
+        rotated.push(".1");
+        PathBuf::from(rotated)
+    }
+
+    /// Add an entry at the end of the log, rotating it first if it is full
+    pub fn append(&self, entry: &HistoryEntry) -> Result<(), AstudiosError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::metadata(&self.path).is_ok_and(|m| m.len() >= self.max_bytes) {
+            fs::rename(&self.path, self.rotated_path())?;
+        }
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// The last `limit` entries, oldest first, including the rotated log.
+    ///
+    /// Lines that cannot be parsed, such as one cut short by a crash, are skipped.
+    pub fn recent(&self, limit: usize) -> Result<Vec<HistoryEntry>, AstudiosError> {
+        let mut entries = Self::read_entries(&self.rotated_path())?;
+        entries.extend(Self::read_entries(&self.path)?);
+
+        let skip = entries.len().saturating_sub(limit);
+        Ok(entries.split_off(skip))
+    }
+
+    /// Parse a log file, treating a missing file as empty
+    fn read_entries(path: &Path) -> Result<Vec<HistoryEntry>, AstudiosError> {
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            if let Ok(entry) = serde_json::from_str(&line?) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Format seconds since the Unix epoch as `YYYY-MM-DD HH:MM:SS` in UTC
+pub fn format_utc(timestamp: u64) -> String {
+    let (year, month, day) = civil_from_days((timestamp / 86_400) as i64);
+    let secs = timestamp % 86_400;
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60
+    )
+}
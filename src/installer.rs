@@ -1,14 +1,116 @@
 use crate::{
-    config::Config, detector::SystemDetector, downloader::Downloader, error::AstudiosError,
-    model::InstalledAndroidStudio,
+    blobs::{self, BlobStore},
+    config::Config,
+    detector::{DMG_EXTRACTORS, SystemDetector},
+    downloader::Downloader,
+    error::{AstudiosError, ExtractionKind},
+    events::{self, InstallEvent},
+    json_file::JsonFile,
+    list::AndroidStudioLister,
+    model::{
+        AndroidStudio, BundleNameTemplate, Download, InstalledAndroidStudio, MacArch,
+        ReleaseChannel, directory_size, major_track,
+    },
+    preferences::Preferences,
+    sayln,
+    state::{InstallRecord, State},
 };
 use colored::Colorize;
+use indicatif::HumanBytes;
 use std::{
+    collections::{BTreeMap, HashSet},
     fs,
-    path::{Path, PathBuf},
-    process::Command,
+    io::Read,
+    os::unix::fs::MetadataExt,
+    path::{Component, Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{Condvar, Mutex, OnceLock},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+/// Written into the extraction directory once every bundle has been copied
+const EXTRACTION_COMPLETE_MARKER: &str = ".extraction-complete";
+
+/// Suffixes of the archives staged in a version directory
+const ARCHIVE_SUFFIXES: [&str; 3] = [".dmg", ".zip", ".tar.gz"];
+
+/// Directory levels below the Toolbox apps directory searched for bundles, as
+/// in `AndroidStudio/ch-0/<build>/Android Studio.app`
+const TOOLBOX_SCAN_DEPTH: usize = 4;
+
+/// How often a running `hdiutil attach` is checked for completion
+const MOUNT_POLL_INTERVAL_MS: u64 = 100;
+
+/// Process-wide limiter shared by every DMG extraction
+static EXTRACTION_SEMAPHORE: OnceLock<ExtractionSemaphore> = OnceLock::new();
+
+/// Counting semaphore bounding how many disk images are mounted at once.
+///
+/// Concurrent `hdiutil attach` calls can contend and fail intermittently, so
+/// mounts are serialized by default while downloads may still run in parallel.
+pub struct ExtractionSemaphore {
+    state: Mutex<SemaphoreState>,
+    released: Condvar,
+}
+
+struct SemaphoreState {
+    active: usize,
+    limit: usize,
+}
+
+/// Guard holding one extraction slot; the slot is released on drop
+pub struct ExtractionPermit<'a> {
+    semaphore: &'a ExtractionSemaphore,
+}
+
+impl ExtractionSemaphore {
+    /// Create a semaphore allowing `limit` concurrent extractions (at least one)
+    pub fn new(limit: usize) -> Self {
+        Self {
+            state: Mutex::new(SemaphoreState {
+                active: 0,
+                limit: limit.max(1),
+            }),
+            released: Condvar::new(),
+        }
+    }
+
+    /// The semaphore used by all installers in this process
+    pub fn global() -> &'static Self {
+        EXTRACTION_SEMAPHORE.get_or_init(|| Self::new(Config::DEFAULT_CONCURRENT_EXTRACTIONS))
+    }
+
+    /// Change how many extractions may run concurrently (at least one)
+    pub fn set_limit(&self, limit: usize) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.limit = limit.max(1);
+        self.released.notify_all();
+    }
+
+    /// Block until an extraction slot is free and claim it
+    pub fn acquire(&self) -> ExtractionPermit<'_> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        while state.active >= state.limit {
+            state = self.released.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        state.active += 1;
+        ExtractionPermit { semaphore: self }
+    }
+}
+
+impl Drop for ExtractionPermit<'_> {
+    fn drop(&mut self) {
+        let mut state = self
+            .semaphore
+            .state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        state.active -= 1;
+        self.semaphore.released.notify_one();
+    }
+}
+
 /// Archive extraction support
 #[derive(Debug, Clone, Copy)]
 pub enum ArchiveType {
@@ -16,23 +118,104 @@ pub enum ArchiveType {
     Unsupported,
 }
 
+/// What uninstalling a version would remove
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UninstallPlan {
+    pub installation: InstalledAndroidStudio,
+    /// Launcher symlinks pointing at the bundle
+    pub symlinks: Vec<PathBuf>,
+    pub bundle_bytes: u64,
+    /// Per-version directory with the installation files, if there is one
+    pub version_dir: Option<PathBuf>,
+    /// Whether the version directory and stored archives are kept
+    pub keep_files: bool,
+    /// Bytes freed by removing the version directory, not counting archives
+    /// still linked from the blob store
+    pub version_dir_bytes: u64,
+    /// Stored archives nothing links to anymore, with their sizes
+    pub blobs: Vec<(PathBuf, u64)>,
+}
+
+impl UninstallPlan {
+    /// Total bytes the uninstall would free
+    pub fn reclaimable_bytes(&self) -> u64 {
+        self.bundle_bytes
+            + self.version_dir_bytes
+            + self.blobs.iter().map(|(_, size)| size).sum::<u64>()
+    }
+}
+
+/// Archives removed by [`Installer::evict_archives`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ArchiveEviction {
+    /// Evicted archives, by their path in a version directory
+    pub evicted: Vec<PathBuf>,
+    /// Bytes freed, including stored archives nothing links to anymore
+    pub reclaimed_bytes: u64,
+}
+
 /// Installation manager for Android Studio
 pub struct Installer {
     install_dir: PathBuf,
     applications_dir: PathBuf,
+    /// Downloader forced by the user; `None` auto-detects and allows fallback
+    downloader: Option<Downloader>,
+    /// Store archives in the shared blob store and keep them after installing
+    dedupe_downloads: bool,
+    /// Whether the applications directory was chosen by the user, in which case
+    /// the symlink is created there even for custom installation directories
+    explicit_applications_dir: bool,
+    /// macOS architecture to download; `None` uses the host architecture
+    mac_arch: Option<MacArch>,
+    /// Refuse builds that are not native to this Mac instead of running them under Rosetta
+    strict_arch: bool,
+    /// Whether installing points the "Android Studio.app" symlink at the new bundle
+    manage_symlink: bool,
+    /// Whether the prerequisite checks include network connectivity
+    check_network: bool,
+    /// Digest the archive must match, overriding the feed's checksum
+    pinned_checksum: Option<String>,
+    /// Whether prerequisite warnings stop the install like failures do
+    strict_checks: bool,
+    /// Check the bundle's code signature inside the mounted image before copying it
+    verify_source_signature: bool,
+    /// Keep one launcher symlink per release channel instead of a single one
+    per_channel_symlinks: bool,
+    /// Channel whose launcher symlink is switched and read; `None` follows the
+    /// bundle's own channel
+    symlink_channel: Option<ReleaseChannel>,
+    /// Naming scheme for installed bundles
+    bundle_name_template: BundleNameTemplate,
+    /// Channel labels set with `set-channel`, keyed by app bundle path
+    channel_overrides: BTreeMap<PathBuf, String>,
 }
 
 impl Installer {
     /// Create a new installer with default directories
     pub fn new() -> Result<Self, AstudiosError> {
-        let install_dir = Config::versions_dir();
+        let install_dir = Config::versions_dir()?;
         let applications_dir = Config::default_applications_dir();
 
         fs::create_dir_all(&install_dir)?;
+        let preferences = Preferences::load().unwrap_or_default();
 
         Ok(Self {
             install_dir,
             applications_dir,
+            downloader: None,
+            dedupe_downloads: false,
+            explicit_applications_dir: false,
+            mac_arch: None,
+            strict_arch: preferences.strict_arch,
+            manage_symlink: true,
+            check_network: true,
+            pinned_checksum: None,
+            strict_checks: false,
+            verify_source_signature: false,
+            per_channel_symlinks: preferences.per_channel_symlinks,
+            symlink_channel: None,
+            bundle_name_template: preferences.bundle_name_template.unwrap_or_default(),
+            channel_overrides: State::load().unwrap_or_default().channel_overrides,
         })
     }
 
@@ -45,9 +228,113 @@ impl Installer {
         Ok(Self {
             install_dir,
             applications_dir,
+            downloader: None,
+            dedupe_downloads: false,
+            explicit_applications_dir: false,
+            mac_arch: None,
+            strict_arch: false,
+            manage_symlink: true,
+            check_network: true,
+            pinned_checksum: None,
+            strict_checks: false,
+            verify_source_signature: false,
+            per_channel_symlinks: false,
+            symlink_channel: None,
+            bundle_name_template: BundleNameTemplate::default(),
+            channel_overrides: BTreeMap::new(),
         })
     }
 
+    /// Create the "Android Studio.app" symlink in `applications_dir`, independently
+    /// of where versioned bundles are installed
+    pub fn with_applications_dir(mut self, applications_dir: PathBuf) -> Self {
+        self.applications_dir = applications_dir;
+        self.explicit_applications_dir = true;
+        self
+    }
+
+    /// Directory holding the "Android Studio.app" symlink
+    pub fn applications_dir(&self) -> &Path {
+        &self.applications_dir
+    }
+
+    /// Skip creating the "Android Studio.app" symlink when `manage_symlink` is false
+    pub fn with_symlink(mut self, manage_symlink: bool) -> Self {
+        self.manage_symlink = manage_symlink;
+        self
+    }
+
+    /// Run `codesign -v` on the bundle while it is still in the mounted disk image,
+    /// so a bad signature aborts the install before the bundle is copied
+    pub fn with_source_signature_check(mut self, verify_source_signature: bool) -> Self {
+        self.verify_source_signature = verify_source_signature;
+        self
+    }
+
+    /// Keep a launcher symlink per release channel, e.g. "Android Studio Canary.app"
+    /// next to "Android Studio.app" for stable releases
+    pub fn with_per_channel_symlinks(mut self, per_channel_symlinks: bool) -> Self {
+        self.per_channel_symlinks = per_channel_symlinks;
+        self
+    }
+
+    /// Switch and report the launcher symlink of `channel`, whatever the channel
+    /// of the bundle it points at
+    pub fn with_symlink_channel(mut self, channel: ReleaseChannel) -> Self {
+        self.symlink_channel = Some(channel);
+        self
+    }
+
+    /// Leave network connectivity out of the prerequisite checks, for installs
+    /// that need no network
+    pub fn with_network_check(mut self, check_network: bool) -> Self {
+        self.check_network = check_network;
+        self
+    }
+
+    /// Download the build for the given macOS architecture
+    pub fn with_mac_arch(mut self, mac_arch: MacArch) -> Self {
+        self.mac_arch = Some(mac_arch);
+        self
+    }
+
+    /// Refuse to download a build that is not native to this Mac, such as an
+    /// Intel build that would run under Rosetta on Apple Silicon
+    pub fn with_strict_arch(mut self, strict_arch: bool) -> Self {
+        self.strict_arch = strict_arch;
+        self
+    }
+
+    /// Always use the given downloader, without falling back if it fails
+    pub fn with_downloader(mut self, downloader: Downloader) -> Self {
+        self.downloader = Some(downloader);
+        self
+    }
+
+    /// Refuse to install when the prerequisite checks report any warning
+    pub fn with_strict_checks(mut self, strict_checks: bool) -> Self {
+        self.strict_checks = strict_checks;
+        self
+    }
+
+    /// Verify the archive against `checksum` instead of the feed's checksum
+    pub fn with_checksum(mut self, checksum: Option<String>) -> Self {
+        self.pinned_checksum = checksum;
+        self
+    }
+
+    /// Deduplicate archives through the blob store instead of deleting them after install
+    pub fn with_dedupe_downloads(mut self, dedupe_downloads: bool) -> Self {
+        self.dedupe_downloads = dedupe_downloads;
+        self
+    }
+
+    /// Name installed bundles after `template` instead of the configured scheme
+    pub fn with_bundle_name_template(mut self, template: BundleNameTemplate) -> Self {
+        self.bundle_name_template = template;
+        self
+    }
+
     /// Install Android Studio version
     pub fn install_version(
         &self,
@@ -56,15 +343,68 @@ impl Installer {
         custom_dir: Option<&str>,
     ) -> Result<(), AstudiosError> {
         self.install_version_with_checks(version, full_name, custom_dir, true)
+            .map(drop)
     }
 
-    /// Install Android Studio version with optional prerequisite checks
+    /// Install Android Studio version with optional prerequisite checks,
+    /// returning the path of the installed bundle
     pub fn install_version_with_checks(
         &self,
         version: &str,
         full_name: &str,
         custom_dir: Option<&str>,
         run_checks: bool,
+    ) -> Result<PathBuf, AstudiosError> {
+        self.prepare_install(custom_dir, run_checks)?;
+
+        let release = Self::find_release(version)?;
+        self.install_archive(version, custom_dir, Some(&release), |installer| {
+            installer.download_version(&release, full_name)
+        })
+    }
+
+    /// Download the archive for `version` without installing it, so a later
+    /// install of the same version finds it already in place
+    pub fn download_release(
+        &self,
+        version: &str,
+        full_name: &str,
+    ) -> Result<PathBuf, AstudiosError> {
+        let release = Self::find_release(version)?;
+        self.download_version(&release, full_name)
+            .map(|(path, _)| path)
+    }
+
+    /// Install the archive at `url`, bypassing the releases feed.
+    ///
+    /// The version is read from the extracted bundle's Info.plist. Set a
+    /// checksum with [`with_checksum`](Self::with_checksum) to verify the archive.
+    pub fn install_from_url(
+        &self,
+        url: &str,
+        custom_dir: Option<&str>,
+        run_checks: bool,
+    ) -> Result<PathBuf, AstudiosError> {
+        self.prepare_install(custom_dir, run_checks)?;
+
+        let filename = Downloader::resolve_filename(url, "android-studio.dmg");
+        let label = archive_label(&filename);
+        let download = Download {
+            link: url.to_string(),
+            size: String::new(),
+            checksum: self.pinned_checksum.clone().unwrap_or_default(),
+        };
+        self.install_archive(&label, custom_dir, None, |installer| {
+            let download_path = installer.install_dir.join(&label).join(&filename);
+            installer.fetch_archive(&download, download_path, &label, &label, None)
+        })
+    }
+
+    /// Check the target directories and, if enabled, the prerequisites
+    fn prepare_install(
+        &self,
+        custom_dir: Option<&str>,
+        run_checks: bool,
     ) -> Result<(), AstudiosError> {
         let target_dir = if let Some(dir) = custom_dir {
             PathBuf::from(dir)
@@ -72,37 +412,51 @@ impl Installer {
             self.applications_dir.clone()
         };
 
+        self.ensure_unmanaged_target(&target_dir)?;
+        SystemDetector::ensure_writable(&target_dir)?;
+        if self.manage_symlink {
+            SystemDetector::ensure_writable(&self.applications_dir)?;
+        }
+
         // Run prerequisite checks if enabled
         if run_checks {
-            println!(
+            sayln!(
                 "{} {} Checking system requirements...",
                 "[1/5]".bold().blue(),
                 "🔍".blue()
             );
-            let detection_result =
-                SystemDetector::detect_system_requirements(&self.install_dir, &target_dir)?;
-
-            // Display warnings if any
-            if detection_result.has_warnings() {
-                println!();
-                for warning in &detection_result.warnings {
-                    println!("      {} {}", "⚠️".yellow(), warning.yellow());
+            let detection_result = SystemDetector::detect_system_requirements(
+                &self.install_dir,
+                &target_dir,
+                self.check_network,
+            )?;
+
+            // Display warnings if any; in strict mode they are listed as errors below
+            if detection_result.has_warnings() && !self.strict_checks {
+                sayln!();
+                for warning in detection_result.warnings() {
+                    sayln!("      {} {}", "⚠️".yellow(), warning.yellow());
                 }
-                println!();
+                sayln!();
             }
 
             // Check if system meets requirements
-            if !detection_result.is_valid() {
-                println!("      {} System requirements not met:", "❌".red());
-                for issue in &detection_result.issues {
-                    println!("        • {}", issue.red());
+            if !detection_result.passes(self.strict_checks) {
+                sayln!("      {} System requirements not met:", "❌".red());
+                for issue in detection_result.issues() {
+                    sayln!("        • {}", issue.red());
+                }
+                if self.strict_checks {
+                    for warning in detection_result.warnings() {
+                        sayln!("        • {} {}", warning.red(), "(--strict)".dimmed());
+                    }
                 }
-                println!();
-                println!(
+                sayln!();
+                sayln!(
                     "      {} Please resolve the above issues and try again.",
                     "💡".blue()
                 );
-                println!(
+                sayln!(
                     "      {} Use --skip-checks to bypass these checks (not recommended)",
                     "⚠️".yellow()
                 );
@@ -112,103 +466,412 @@ impl Installer {
                 ));
             }
 
-            println!("      {} System requirements verified", "✅".green());
-            println!();
+            sayln!("      {} System requirements verified", "✅".green());
+            sayln!();
         }
 
-        let download_path = self.download_version(version, full_name)?;
-        let extracted_path = self.extract_archive(&download_path, version)?;
-        let app_path = self.move_to_applications(version, &extracted_path, custom_dir)?;
+        Ok(())
+    }
 
-        // Only create symlink if installing to the default Applications directory
-        if custom_dir.is_none() || custom_dir == Some("/Applications") {
+    /// Download, extract and install an archive staged under `version`.
+    ///
+    /// Without a `release`, the bundle is named after the version in its own
+    /// Info.plist.
+    fn install_archive(
+        &self,
+        version: &str,
+        custom_dir: Option<&str>,
+        release: Option<&AndroidStudio>,
+        download: impl Fn(&Self) -> Result<(PathBuf, bool), AstudiosError>,
+    ) -> Result<PathBuf, AstudiosError> {
+        let (download_path, extracted_path, verified) =
+            self.download_and_extract(version, download)?;
+        let release = match release {
+            Some(release) => release.clone(),
+            None => Self::release_from_bundle(&Self::find_extracted_bundle(&extracted_path)?)?,
+        };
+        let app_path = self.move_to_applications(&release, &extracted_path, custom_dir)?;
+
+        // Only create symlink if installing to the applications directory, or the
+        // user explicitly chose where the symlink goes
+        if !self.manage_symlink {
+            sayln!(
+                "{} {} Skipping symlink creation (--no-symlink)",
+                "[5/5]".bold().blue(),
+                "🔗".blue()
+            );
+        } else if self.explicit_applications_dir
+            || custom_dir.is_none_or(|dir| Path::new(dir) == self.applications_dir)
+        {
             self.create_symlink(&app_path)?;
         } else {
-            println!(
+            sayln!(
                 "{} {} Skipping symlink creation for custom directory",
                 "[5/5]".bold().blue(),
                 "🔗".blue()
             );
-            println!(
+            sayln!(
                 "      {} Custom installation directory detected",
                 "ℹ️".blue()
             );
         }
 
+        self.finish_install(
+            app_path,
+            &download_path,
+            &extracted_path,
+            &release.version,
+            verified,
+        )
+    }
+
+    /// Reinstall the bundle at `app_path` from the archive of `release`, in
+    /// place, so every launcher symlink pointing at it keeps working
+    pub fn reinstall(
+        &self,
+        app_path: &Path,
+        release: &AndroidStudio,
+    ) -> Result<PathBuf, AstudiosError> {
+        if let Some(parent) = app_path.parent() {
+            SystemDetector::ensure_writable(parent)?;
+        }
+
+        let version = release.version.as_str();
+        let (download_path, extracted_path, verified) = self
+            .download_and_extract(version, |installer| {
+                installer.download_version(release, &release.name)
+            })?;
+
+        sayln!(
+            "{} {} Reinstalling in place...",
+            "[4/5]".bold().blue(),
+            "📲".blue()
+        );
+        sayln!(
+            "      {} {}",
+            "Target:".dimmed(),
+            app_path.display().to_string().cyan()
+        );
+        Self::install_app_bundle(&Self::find_extracted_bundle(&extracted_path)?, app_path)?;
+        sayln!(
+            "      {} Application reinstalled successfully",
+            "✅".green()
+        );
+
+        self.finish_install(
+            app_path.to_path_buf(),
+            &download_path,
+            &extracted_path,
+            version,
+            verified,
+        )
+    }
+
+    /// Fetch the archive with `download` and extract it, downloading it again
+    /// once if an unverified archive cannot be opened. Returns the archive and
+    /// extraction paths and whether the checksum was verified.
+    fn download_and_extract(
+        &self,
+        version: &str,
+        download: impl Fn(&Self) -> Result<(PathBuf, bool), AstudiosError>,
+    ) -> Result<(PathBuf, PathBuf, bool), AstudiosError> {
+        let (mut download_path, mut verified) = download(self)?;
+        events::emit(InstallEvent::Download { pct: 100 });
+        events::emit(InstallEvent::Extract);
+        let extracted_path = match self.extract_archive(&download_path, version) {
+            // A checksum-verified archive is intact, so only an unverified one
+            // is worth downloading again when the image cannot be opened
+            Err(AstudiosError::Extraction(kind, msg))
+                if kind.redownload_may_help() && !verified =>
+            {
+                sayln!(
+                    "      {} {msg}; the archive may be corrupt, downloading it again",
+                    "⚠️".yellow()
+                );
+                fs::remove_file(&download_path)?;
+                (download_path, verified) = download(self)?;
+                self.extract_archive(&download_path, version)?
+            }
+            result => result?,
+        };
+        Ok((download_path, extracted_path, verified))
+    }
+
+    /// Clean up after copying a bundle to `app_path`, verify it and record the install
+    fn finish_install(
+        &self,
+        app_path: PathBuf,
+        download_path: &Path,
+        extracted_path: &Path,
+        version: &str,
+        verified: bool,
+    ) -> Result<PathBuf, AstudiosError> {
         // Clean up temporary files silently
-        let _ = self.cleanup_files(&download_path, &extracted_path);
+        let _ = self.cleanup_files(download_path, extracted_path);
 
         // Verify installation silently
         self.verify_installation(&app_path)?;
-        Ok(())
-    }
 
-    /// Download a specific version
-    fn download_version(&self, version: &str, full_name: &str) -> Result<PathBuf, AstudiosError> {
-        use crate::list::AndroidStudioLister;
+        let mut state = State::load()?;
+        state.record_install(
+            app_path.clone(),
+            InstallRecord {
+                version: version.to_string(),
+                verified,
+            },
+        );
+        state.save()?;
 
-        let version_dir = self.install_dir.join(version);
-        fs::create_dir_all(&version_dir)?;
+        events::emit(InstallEvent::Installed {
+            path: app_path.clone(),
+        });
+        Ok(app_path)
+    }
 
-        // Get the actual download URL from the API
-        let lister = AndroidStudioLister::new()?;
-        let releases = lister.get_releases()?;
+    /// Refuse installation targets inside the versions or cache directories.
+    ///
+    /// Both are managed by astudios and get cleaned up, which would delete an
+    /// app bundle installed there.
+    pub fn ensure_unmanaged_target(&self, target_dir: &Path) -> Result<(), AstudiosError> {
+        let target = resolve_path(target_dir);
+
+        for managed in [&self.install_dir, &Config::cache_dir()?] {
+            if target.starts_with(resolve_path(managed)) {
+                return Err(AstudiosError::Path(format!(
+                    "Refusing to install into {}: it is inside {}, which astudios manages and cleans up. Choose a different --directory.",
+                    target_dir.display(),
+                    managed.display()
+                )));
+            }
+        }
 
-        let target_item = releases
+        Ok(())
+    }
+
+    /// Look up the feed entry for `version`
+    fn find_release(version: &str) -> Result<AndroidStudio, AstudiosError> {
+        AndroidStudioLister::new()?
+            .get_releases()?
             .items
-            .iter()
+            .into_iter()
             .find(|item| item.version == version)
-            .ok_or_else(|| {
-                AstudiosError::VersionNotFound(format!("Version {version} not found"))
-            })?;
+            .ok_or_else(|| AstudiosError::VersionNotFound(format!("Version {version} not found")))
+    }
+
+    /// macOS download of `release` for the selected architecture, or an error if
+    /// the strict architecture policy rules out the only one on offer
+    fn platform_download<'a>(
+        &self,
+        release: &'a AndroidStudio,
+    ) -> Result<&'a Download, AstudiosError> {
+        let download =
+            release.require_platform_download_for(self.mac_arch.unwrap_or_else(MacArch::host))?;
+        let short_name = |arch| match arch {
+            MacArch::Arm64 => "arm64",
+            MacArch::X86_64 => "x86_64",
+        };
+        match download.mac_arch() {
+            Some(arch) if self.strict_arch && arch != MacArch::host() => {
+                Err(AstudiosError::Platform(format!(
+                    "The {} build of {} ({}) does not run natively on this {} Mac, and --strict-arch refuses to install it. Pick a release with a native build, or select the native architecture.",
+                    short_name(arch),
+                    release.name,
+                    release.version,
+                    short_name(MacArch::host())
+                )))
+            }
+            _ => Ok(download),
+        }
+    }
 
-        let download = target_item
-            .get_platform_download()
-            .ok_or(AstudiosError::Download(
-                "No download available for current platform".to_string(),
-            ))?;
+    /// Download the archive for `target_item`, returning its path and whether
+    /// its checksum was verified
+    fn download_version(
+        &self,
+        target_item: &AndroidStudio,
+        full_name: &str,
+    ) -> Result<(PathBuf, bool), AstudiosError> {
+        let version = target_item.version.as_str();
+        let version_dir = self.install_dir.join(version);
+
+        let mut download = self.platform_download(target_item)?.clone();
+        if let Some(checksum) = &self.pinned_checksum {
+            download.checksum = checksum.clone();
+        }
 
         let default_filename = format!("android-studio-{version}.dmg");
-        let filename = Path::new(&download.link)
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or(&default_filename);
+        let filename = Downloader::resolve_filename(&download.link, &default_filename);
 
         let download_path = version_dir.join(filename);
 
-        // Skip if file already exists and has content
+        self.fetch_archive(
+            &download,
+            download_path,
+            version,
+            full_name,
+            Some(target_item),
+        )
+    }
+
+    /// Download `download` to `download_path`, reusing an existing or stored
+    /// copy that passes verification. Returns the path and whether its
+    /// checksum was verified.
+    fn fetch_archive(
+        &self,
+        download: &Download,
+        download_path: PathBuf,
+        version: &str,
+        full_name: &str,
+        release: Option<&AndroidStudio>,
+    ) -> Result<(PathBuf, bool), AstudiosError> {
+        if let Some(parent) = download_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Skip if file already exists and has content, unless it fails verification
         if download_path.exists() {
             let metadata = fs::metadata(&download_path)?;
             if metadata.len() > 0 {
-                println!(
+                sayln!(
                     "{} {} File already downloaded",
                     "[2/5]".bold().blue(),
                     "📦".blue()
                 );
-                println!(
+                sayln!(
                     "      {} {}",
                     "Location:".dimmed(),
                     download_path.display().to_string().cyan()
                 );
-                return Ok(download_path);
+                match Downloader::verify_checksum(&download_path, &download.checksum) {
+                    Ok(verified) => {
+                        if !verified {
+                            Self::warn_unverified();
+                        }
+                        return Ok((download_path, verified));
+                    }
+                    Err(e) => sayln!("      {} {e}; downloading it again", "⚠️".yellow()),
+                }
             }
         }
 
-        println!(
+        if self.dedupe_downloads
+            && blobs::is_sha256(&download.checksum)
+            && BlobStore::new()?.link_existing(&download.checksum, &download_path)?
+        {
+            sayln!(
+                "{} {} Reusing stored archive with the same checksum",
+                "[2/5]".bold().blue(),
+                "📦".blue()
+            );
+            // Blobs are stored under the digest of their content
+            return Ok((download_path, true));
+        }
+
+        sayln!(
             "{} {} Downloading Android Studio...",
             "[2/5]".bold().blue(),
             "📥".blue()
         );
-        println!("      {} {}", "Version:".dimmed(), version.cyan());
-        println!("      {} {}", "Size:".dimmed(), download.size.yellow());
+        sayln!("      {} {}", "Version:".dimmed(), version.cyan());
+        if !download.size.is_empty() {
+            sayln!("      {} {}", "Size:".dimmed(), download.size.yellow());
+        }
+
+        if let Some(release) = release.filter(|release| release.is_patch()) {
+            self.report_patch_base(release);
+        }
 
         // Use the downloader to actually download the file
-        let downloader = Downloader::detect_best();
-        downloader.download(&download.link, &download_path, Some(full_name))?;
+        let downloader = self
+            .downloader
+            .clone()
+            .unwrap_or_else(Downloader::detect_best);
+        let reused = downloader.download_with_mirrors(
+            &download.link,
+            &download_path,
+            Some(full_name),
+            self.downloader.is_some(),
+            &download.checksum,
+        )?;
+
+        if reused > 0 {
+            sayln!(
+                "      {} Download completed ({} reused from a partial download)",
+                "✅".green(),
+                HumanBytes(reused)
+            );
+        } else {
+            sayln!("      {} Download completed", "✅".green());
+        }
+
+        let verified = blobs::is_sha256(&download.checksum);
+        if !verified {
+            Self::warn_unverified();
+        }
+
+        if self.dedupe_downloads {
+            BlobStore::new()?.adopt(&download_path)?;
+        }
+
+        Ok((download_path, verified))
+    }
 
-        println!("      {} Download completed", "✅".green());
+    /// Warn that the feed published no checksum to verify the archive against
+    fn warn_unverified() {
+        sayln!(
+            "      {} No SHA-256 checksum is published for this download; it was not verified",
+            "⚠️".yellow()
+        );
+    }
 
-        Ok(download_path)
+    /// The installed build that the patch release `target_item` applies to
+    pub fn installed_patch_base(
+        &self,
+        target_item: &AndroidStudio,
+    ) -> Option<InstalledAndroidStudio> {
+        if !target_item.is_patch() {
+            return None;
+        }
+        self.list_installed_studios()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|installed| target_item.is_patch_of(&installed.version))
+    }
+
+    /// Tell the user when a patch release's base build is already installed
+    fn report_patch_base(&self, target_item: &AndroidStudio) {
+        let base = self.installed_patch_base(target_item);
+        if let Some(base) = &base {
+            sayln!(
+                "      {} Base build {} is already installed at {}",
+                "ℹ️".blue(),
+                base.identifier().blue(),
+                base.path.display().to_string().dimmed()
+            );
+            sayln!(
+                "      {} Android Studio can apply this patch in place via Help > Check for Updates",
+                "💡".blue()
+            );
+        }
+
+        if target_item.get_patch_download().is_some() {
+            sayln!(
+                "      {} Full download: patch artifacts can't be installed standalone",
+                "ℹ️".blue()
+            );
+            if base.is_some() {
+                sayln!(
+                    "      {} `astudios download {}` fetches only the patch artifact",
+                    "💡".blue(),
+                    target_item.version
+                );
+            }
+        } else {
+            sayln!(
+                "      {} Full download: the feed has no patch artifact for this release",
+                "ℹ️".blue()
+            );
+        }
     }
 
     /// Extract archive based on type
@@ -218,6 +881,26 @@ impl Installer {
         version: &str,
     ) -> Result<PathBuf, AstudiosError> {
         let extract_dir = self.install_dir.join(version).join("extracted");
+        let marker = extract_dir.join(EXTRACTION_COMPLETE_MARKER);
+
+        if marker.exists() {
+            sayln!(
+                "{} {} Reusing previously extracted bundle",
+                "[3/5]".bold().blue(),
+                "♻️".blue()
+            );
+            sayln!(
+                "      {} {}",
+                "Location:".dimmed(),
+                extract_dir.display().to_string().cyan()
+            );
+            return Ok(extract_dir);
+        }
+
+        // Anything left without the marker is an interrupted copy
+        if extract_dir.exists() {
+            fs::remove_dir_all(&extract_dir)?;
+        }
         fs::create_dir_all(&extract_dir)?;
 
         let archive_type = self.detect_archive_type(archive_path);
@@ -225,13 +908,17 @@ impl Installer {
         match archive_type {
             ArchiveType::Dmg => self.extract_dmg(archive_path, &extract_dir)?,
             ArchiveType::Unsupported => {
-                return Err(AstudiosError::Extraction(format!(
-                    "Unsupported archive format: {}. Only DMG files are supported on macOS.",
-                    archive_path.display()
-                )));
+                return Err(AstudiosError::Extraction(
+                    ExtractionKind::Unsupported,
+                    format!(
+                        "Unsupported archive format: {}. Only DMG files are supported on macOS.",
+                        archive_path.display()
+                    ),
+                ));
             }
         }
 
+        fs::write(&marker, "")?;
         Ok(extract_dir)
     }
 
@@ -246,41 +933,214 @@ impl Installer {
         }
     }
 
-    /// Extract DMG archive (macOS only)
+    /// Extract a DMG archive by mounting it with hdiutil, falling back to
+    /// unpacking it with 7z or unar when mounting fails or hdiutil is missing
     fn extract_dmg(&self, archive_path: &Path, destination: &Path) -> Result<(), AstudiosError> {
-        let temp_mount = tempfile::tempdir()?;
+        // Held until the image is detached so mounts never interleave beyond the limit
+        let _permit = ExtractionSemaphore::global().acquire();
+
+        let mount_error = if SystemDetector::check_tool_available("hdiutil") {
+            match self.mount_and_copy_dmg(archive_path, destination) {
+                Ok(()) => return Ok(()),
+                // Unpacking instead would only skip the check that failed
+                Err(e @ AstudiosError::Extraction(ExtractionKind::Signature, _)) => return Err(e),
+                Err(e) => e,
+            }
+        } else {
+            AstudiosError::Extraction(
+                ExtractionKind::ToolMissing,
+                "hdiutil is not available".to_string(),
+            )
+        };
+
+        // Only a tool that ran and rejected the image points at the archive;
+        // a tool that could not run, or a local IO error, says nothing about it
+        let reason = |e: &AstudiosError| match e {
+            AstudiosError::Extraction(kind, msg) => (*kind, msg.clone()),
+            AstudiosError::Io(io) if io.kind() == std::io::ErrorKind::NotFound => {
+                (ExtractionKind::ToolMissing, e.to_string())
+            }
+            other => (ExtractionKind::Copy, other.to_string()),
+        };
+        let (mut kind, first_failure) = reason(&mount_error);
+        let mut failures = vec![first_failure];
+        for tool in DMG_EXTRACTORS {
+            if !SystemDetector::check_tool_available(tool) {
+                continue;
+            }
+
+            sayln!(
+                "      {} {}; unpacking the disk image with {tool} instead",
+                "⚠️".yellow(),
+                failures[0]
+            );
+            // Drop anything a failed attempt copied before retrying
+            fs::remove_dir_all(destination)?;
+            fs::create_dir_all(destination)?;
+
+            match Self::unpack_dmg(tool, archive_path, destination) {
+                Ok(()) => {
+                    sayln!("      {} Disk image extracted with {tool}", "✅".green());
+                    if self.verify_source_signature {
+                        sayln!(
+                            "      {} The signature was not checked before copying; it needs a mounted image",
+                            "⚠️".yellow()
+                        );
+                    }
+                    return Ok(());
+                }
+                Err(e) => {
+                    let (unpack_kind, failure) = reason(&e);
+                    kind = unpack_kind;
+                    failures.push(failure);
+                }
+            }
+        }
+
+        if failures.len() == 1 {
+            return Err(mount_error);
+        }
+        Err(AstudiosError::Extraction(kind, failures.join("; ")))
+    }
+
+    /// Unpack a DMG without mounting it, using 7z or unar
+    fn unpack_dmg(
+        tool: &str,
+        archive_path: &Path,
+        destination: &Path,
+    ) -> Result<(), AstudiosError> {
+        sayln!(
+            "{} {} Unpacking disk image with {tool}...",
+            "[3/5]".bold().blue(),
+            "💿".blue()
+        );
+
+        let unpack_dir = tempfile::tempdir()?;
+        let mut command = Command::new(tool);
+        if tool == "7z" {
+            let mut output_flag = std::ffi::OsString::from("-o");
+            output_flag.push(unpack_dir.path());
+            command
+                .arg("x")
+                .arg("-y")
+                .arg(output_flag)
+                .arg(archive_path);
+        } else {
+            command
+                .arg("-quiet")
+                .arg("-force-overwrite")
+                .arg("-output-directory")
+                .arg(unpack_dir.path())
+                .arg(archive_path);
+        }
+
+        let output = command.output()?;
+        if !output.status.success() {
+            return Err(AstudiosError::Extraction(
+                ExtractionKind::Mount,
+                format!(
+                    "Failed to unpack DMG with {tool}: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            ));
+        }
+
+        // Archivers put the volume contents in a folder named after the volume
+        let app_paths = find_app_bundles(unpack_dir.path(), 3);
+        if app_paths.is_empty() {
+            return Err(AstudiosError::Extraction(
+                ExtractionKind::MissingBundle,
+                "No Android Studio .app bundle found in disk image".to_string(),
+            ));
+        }
+
+        for app_path in app_paths {
+            sayln!("      {} Found Android Studio app bundle", "📱".blue());
+            Self::copy_app_bundle(&app_path, destination)?;
+        }
+        Ok(())
+    }
+
+    /// Mount a DMG with hdiutil and copy its app bundles into `destination`
+    fn mount_and_copy_dmg(
+        &self,
+        archive_path: &Path,
+        destination: &Path,
+    ) -> Result<(), AstudiosError> {
+        // A fresh, uniquely named directory so we never mount over another volume
+        let temp_mount = tempfile::Builder::new()
+            .prefix("astudios-mount-")
+            .tempdir()?;
         let mount_point = temp_mount.path();
 
-        println!(
+        sayln!(
             "{} {} Mounting disk image...",
             "[3/5]".bold().blue(),
             "💿".blue()
         );
 
-        let output = Command::new("hdiutil")
-            .args([
-                "attach",
-                archive_path
-                    .to_str()
-                    .ok_or(AstudiosError::Path("Invalid path".to_string()))?,
-                "-mountpoint",
-                mount_point
-                    .to_str()
-                    .ok_or(AstudiosError::Path("Invalid path".to_string()))?,
-                "-nobrowse",
-                "-noverify", // Skip verification to avoid issues
-            ])
-            .output()?;
+        // An interrupted earlier run may have left this image attached, which
+        // makes hdiutil fail with "resource busy"; detach it once up front
+        let leftovers = Self::mounted_volumes(archive_path);
+        for volume in &leftovers {
+            sayln!(
+                "      {} Detaching {} left mounted by an earlier run",
+                "⚠️".yellow(),
+                volume.display()
+            );
+            self.detach_dmg(volume)?;
+        }
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(AstudiosError::Extraction(format!(
-                "Failed to mount DMG: {}",
-                error_msg.trim()
-            )));
+        let mut attach = Command::new("hdiutil")
+            .arg("attach")
+            .arg(archive_path)
+            .arg("-mountpoint")
+            .arg(mount_point)
+            .arg("-nobrowse")
+            .arg("-noverify") // Skip verification to avoid issues
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // hdiutil can hang for good on a flaky disk or a corrupt image
+        let timeout = Config::mount_timeout();
+        let Some(status) = wait_with_timeout(&mut attach, timeout)? else {
+            let _ = attach.kill();
+            let _ = attach.wait();
+            // The image may have been attached just as we gave up on it
+            self.detach_dmg(mount_point)?;
+            return Err(AstudiosError::Extraction(
+                ExtractionKind::Timeout,
+                format!(
+                    "Mounting the disk image timed out after {}s; the disk may be failing or the image corrupt. \
+                 Set ASTUDIOS_MOUNT_TIMEOUT to allow more seconds, or delete the archive to download it again.",
+                    timeout.as_secs()
+                ),
+            ));
+        };
+
+        if !status.success() {
+            let mut stderr = String::new();
+            if let Some(mut pipe) = attach.stderr.take() {
+                let _ = pipe.read_to_string(&mut stderr);
+            }
+            if leftovers.is_empty() {
+                return Err(AstudiosError::Extraction(
+                    ExtractionKind::Mount,
+                    format!("Failed to mount DMG: {}", stderr.trim()),
+                ));
+            }
+            return Err(AstudiosError::Extraction(
+                ExtractionKind::Mount,
+                format!(
+                    "Failed to mount DMG even after detaching the volume an earlier run left mounted: {}. \
+                 Run 'hdiutil info' to check for the image and detach it manually.",
+                    stderr.trim()
+                ),
+            ));
         }
 
-        println!("      {} Disk image mounted successfully", "✅".green());
+        sayln!("      {} Disk image mounted with hdiutil", "✅".green());
 
         // Find and copy app bundles
         let mut app_paths = Vec::new();
@@ -291,7 +1151,7 @@ impl Installer {
                 let name_str = name.to_string_lossy();
 
                 if name_str.ends_with(".app") {
-                    println!("      {} Found Android Studio app bundle", "📱".blue());
+                    sayln!("      {} Found Android Studio app bundle", "📱".blue());
                     app_paths.push(entry.path());
                 }
             }
@@ -311,32 +1171,29 @@ impl Installer {
 
             if !android_studio_paths.is_empty() {
                 app_paths = android_studio_paths;
-                println!("      {} Found Android Studio app bundle", "📱".blue());
+                sayln!("      {} Found Android Studio app bundle", "📱".blue());
             } else {
                 self.detach_dmg(mount_point)?;
                 return Err(AstudiosError::Extraction(
+                    ExtractionKind::MissingBundle,
                     "No Android Studio .app bundle found in disk image".to_string(),
                 ));
             }
         }
 
+        if self.verify_source_signature {
+            for app_path in &app_paths {
+                if let Err(e) = Self::verify_source_signature(app_path) {
+                    self.detach_dmg(mount_point)?;
+                    return Err(e);
+                }
+            }
+        }
+
         for app_path in app_paths {
-            let app_name = app_path.file_name().unwrap();
-            let dest_path = destination.join(app_name);
-
-            let status = Command::new("cp")
-                .args([
-                    "-R",
-                    app_path.to_str().unwrap(),
-                    dest_path.to_str().unwrap(),
-                ])
-                .status()?;
-
-            if !status.success() {
+            if let Err(e) = Self::copy_app_bundle(&app_path, destination) {
                 self.detach_dmg(mount_point)?;
-                return Err(AstudiosError::Extraction(
-                    "Failed to copy app bundle".to_string(),
-                ));
+                return Err(e);
             }
         }
 
@@ -344,36 +1201,158 @@ impl Installer {
         Ok(())
     }
 
+    /// Check the code signature of a bundle inside the mounted image.
+    ///
+    /// Unlike [`verify_signature`](Self::verify_signature), a missing `codesign`
+    /// is an error, since the check was asked for explicitly.
+    fn verify_source_signature(app_path: &Path) -> Result<(), AstudiosError> {
+        if let Some(error) = Self::codesign_error(app_path)? {
+            return Err(AstudiosError::Extraction(
+                ExtractionKind::Signature,
+                format!(
+                    "Signature verification failed at the source: {} in the disk image is not validly signed ({error}); nothing was copied",
+                    app_path.file_name().unwrap_or_default().to_string_lossy(),
+                ),
+            ));
+        }
+
+        sayln!(
+            "      {} Code signature verified in the disk image",
+            "✅".green()
+        );
+        Ok(())
+    }
+
+    /// Mount points where `archive_path` is currently attached, according to `hdiutil info`
+    fn mounted_volumes(archive_path: &Path) -> Vec<PathBuf> {
+        let Ok(output) = Command::new("hdiutil").arg("info").output() else {
+            return Vec::new();
+        };
+        if !output.status.success() {
+            return Vec::new();
+        }
+        Self::parse_hdiutil_info(&String::from_utf8_lossy(&output.stdout), archive_path)
+    }
+
+    /// Extract the mount points of `image` from the text output of `hdiutil info`,
+    /// which lists one `====`-separated section per attached image
+    pub fn parse_hdiutil_info(info: &str, image: &Path) -> Vec<PathBuf> {
+        let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let image = canonical(image);
+
+        info.split("================================================")
+            .filter(|section| {
+                section.lines().any(|line| {
+                    line.split_once(':').is_some_and(|(key, value)| {
+                        key.trim() == "image-path" && canonical(Path::new(value.trim())) == image
+                    })
+                })
+            })
+            .flat_map(|section| {
+                section
+                    .lines()
+                    .filter(|line| line.starts_with("/dev/"))
+                    .filter_map(|line| line.split('\t').nth(2))
+                    .map(str::trim)
+                    .filter(|mount_point| !mount_point.is_empty())
+                    .map(PathBuf::from)
+            })
+            .collect()
+    }
+
+    /// Copy an app bundle into `destination`, keeping its original file name
+    pub fn copy_app_bundle(app_path: &Path, destination: &Path) -> Result<PathBuf, AstudiosError> {
+        let app_name = app_path.file_name().ok_or_else(|| {
+            AstudiosError::Path(format!("Invalid app bundle path: {}", app_path.display()))
+        })?;
+        let dest_path = destination.join(app_name);
+
+        let status = Command::new("cp")
+            .arg("-R")
+            .arg(app_path)
+            .arg(&dest_path)
+            .status()?;
+
+        if !status.success() {
+            return Err(AstudiosError::Extraction(
+                ExtractionKind::Copy,
+                "Failed to copy app bundle".to_string(),
+            ));
+        }
+
+        Ok(dest_path)
+    }
+
     /// Detach DMG volume
     fn detach_dmg(&self, mount_point: &Path) -> Result<(), AstudiosError> {
         let output = Command::new("hdiutil")
-            .args(["detach", mount_point.to_str().unwrap(), "-force"])
+            .arg("detach")
+            .arg(mount_point)
+            .arg("-force")
             .output();
 
         match output {
             Ok(output) => {
                 if !output.status.success() {
                     let error_msg = String::from_utf8_lossy(&output.stderr);
-                    println!(
+                    sayln!(
                         "      {} Failed to unmount disk image: {}",
                         "⚠️".yellow(),
                         error_msg.trim()
                     );
                 } else {
-                    println!("      {} Disk image unmounted", "✅".green());
+                    sayln!("      {} Disk image unmounted", "✅".green());
                 }
             }
             Err(e) => {
-                println!("      {} Could not unmount disk image: {e}", "⚠️".yellow());
+                sayln!("      {} Could not unmount disk image: {e}", "⚠️".yellow());
             }
         }
         Ok(())
     }
 
+    /// Find the Android Studio app bundle in the extraction directory
+    fn find_extracted_bundle(extracted_path: &Path) -> Result<PathBuf, AstudiosError> {
+        if let Ok(entries) = fs::read_dir(extracted_path) {
+            for entry in entries.filter_map(|e| e.ok()) {
+                let name = entry.file_name();
+                let name_str = name.to_string_lossy();
+                if name_str.contains("Android Studio") && name_str.ends_with(".app") {
+                    return Ok(entry.path());
+                }
+            }
+        }
+
+        Err(AstudiosError::Installation(
+            "Android Studio.app not found in extracted files".to_string(),
+        ))
+    }
+
+    /// Describe a bundle that is not in the feed as a release, from its Info.plist
+    fn release_from_bundle(bundle: &Path) -> Result<AndroidStudio, AstudiosError> {
+        let installed = InstalledAndroidStudio::new(bundle.to_path_buf())?.ok_or_else(|| {
+            AstudiosError::Installation(format!(
+                "{} is not an Android Studio app bundle",
+                bundle.display()
+            ))
+        })?;
+
+        Ok(AndroidStudio {
+            name: installed.version.product_name,
+            build: installed.version.build_version,
+            version: installed.version.short_version,
+            channel: String::new(),
+            platform_build: String::new(),
+            platform_version: String::new(),
+            date: String::new(),
+            downloads: Vec::new(),
+        })
+    }
+
     /// Move extracted Android Studio to applications directory
     fn move_to_applications(
         &self,
-        version: &str,
+        release: &AndroidStudio,
         extracted_path: &Path,
         custom_dir: Option<&str>,
     ) -> Result<PathBuf, AstudiosError> {
@@ -386,54 +1365,104 @@ impl Installer {
         // Ensure target directory exists
         fs::create_dir_all(&target_dir)?;
 
-        let app_path = target_dir.join(format!("Android Studio {version}.app"));
-
-        // Find the actual app bundle in extracted directory
-        let mut app_source = None;
-        if let Ok(entries) = fs::read_dir(extracted_path) {
-            for entry in entries.filter_map(|e| e.ok()) {
-                let name = entry.file_name();
-                let name_str = name.to_string_lossy();
-                if name_str.contains("Android Studio") && name_str.ends_with(".app") {
-                    app_source = Some(entry.path());
-                    break;
-                }
-            }
-        }
-
-        let source = app_source.ok_or(AstudiosError::Installation(
-            "Android Studio.app not found in extracted files".to_string(),
-        ))?;
+        let name = self.bundle_name_template.render(release);
+        let app_path = target_dir.join(&name);
+        let source = Self::find_extracted_bundle(extracted_path)?;
 
-        println!(
+        sayln!(
             "{} {} Installing to Applications...",
             "[4/5]".bold().blue(),
             "📲".blue()
         );
-        println!(
+        sayln!(
             "      {} {}",
             "Target:".dimmed(),
             app_path.display().to_string().cyan()
         );
 
-        // Remove existing installation if it exists
-        if app_path.exists() {
-            println!("      {} Removing existing installation...", "🗑️".yellow());
-            fs::remove_dir_all(&app_path)?;
-        }
-
-        // Copy the app bundle using a more robust approach
-        // Use ditto instead of cp for better macOS app bundle handling
-        let output = Command::new("ditto")
-            .args([
-                source
-                    .to_str()
-                    .ok_or(AstudiosError::Path("Invalid source path".to_string()))?,
-                app_path
-                    .to_str()
-                    .ok_or(AstudiosError::Path("Invalid target path".to_string()))?,
-            ])
-            .output()?;
+        if let Some(variant) = Self::find_case_variant(&target_dir, &name) {
+            let variant = variant.display().to_string();
+            if Self::is_case_insensitive(&target_dir) {
+                sayln!(
+                    "      {} {} differs only in case, so on this case-insensitive volume it is the same bundle and will be replaced",
+                    "⚠️".yellow(),
+                    variant.yellow()
+                );
+            } else {
+                sayln!(
+                    "      {} {} differs only in case; both are kept here, but they would collide on a case-insensitive volume",
+                    "⚠️".yellow(),
+                    variant.yellow()
+                );
+            }
+        }
+
+        Self::install_app_bundle(&source, &app_path)?;
+
+        sayln!("      {} Application installed successfully", "✅".green());
+        Ok(app_path)
+    }
+
+    /// Entry of `dir` whose name equals `name` except for case, such as
+    /// "android studio 2024.2.app" for "Android Studio 2024.2.app"
+    pub fn find_case_variant(dir: &Path, name: &str) -> Option<PathBuf> {
+        let folded = name.to_lowercase();
+        fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .find(|entry| entry != name && entry.to_lowercase() == folded)
+            .map(|entry| dir.join(entry))
+    }
+
+    /// Whether `dir` is on a volume that ignores case in names, as APFS and
+    /// HFS+ do by default. Probed with a short-lived file; where that cannot be
+    /// written, macOS volumes are assumed to ignore case.
+    pub fn is_case_insensitive(dir: &Path) -> bool {
+        let probe = dir.join(".astudios-case-probe");
+        if fs::write(&probe, "").is_err() {
+            return cfg!(target_os = "macos");
+        }
+        let insensitive = dir.join(".ASTUDIOS-CASE-PROBE").exists();
+        let _ = fs::remove_file(&probe);
+        insensitive
+    }
+
+    /// Copy an app bundle to `app_path`, replacing whatever is there.
+    ///
+    /// A bundle left half-copied by an interrupted run is removed first. The
+    /// copy is checked against the source and retried once if it is incomplete.
+    pub fn install_app_bundle(source: &Path, app_path: &Path) -> Result<(), AstudiosError> {
+        if app_path.exists() || app_path.is_symlink() {
+            sayln!("      {} Removing existing installation...", "🗑️".yellow());
+            Self::remove_existing_bundle(app_path)?;
+        }
+
+        let copy = || {
+            Self::copy_bundle_contents(source, app_path)
+                .and_then(|()| Self::verify_bundle_copy(source, app_path))
+        };
+        if let Err(e) = copy() {
+            sayln!("      {} {e}; retrying the copy", "⚠️".yellow());
+            if app_path.exists() {
+                Self::remove_existing_bundle(app_path)?;
+            }
+            copy()?;
+        }
+        Ok(())
+    }
+
+    /// Copy a bundle with `ditto`, which keeps macOS metadata, or `cp` where it is unavailable
+    fn copy_bundle_contents(source: &Path, app_path: &Path) -> Result<(), AstudiosError> {
+        let output = if SystemDetector::check_tool_available("ditto") {
+            Command::new("ditto").arg(source).arg(app_path).output()?
+        } else {
+            Command::new("cp")
+                .arg("-R")
+                .arg(source)
+                .arg(app_path)
+                .output()?
+        };
 
         if !output.status.success() {
             let error_msg = String::from_utf8_lossy(&output.stderr);
@@ -442,16 +1471,59 @@ impl Installer {
                 error_msg.trim()
             )));
         }
+        Ok(())
+    }
 
-        // Verify the installation was successful
-        if !app_path.exists() {
-            return Err(AstudiosError::Installation(
-                "Installation completed but app bundle not found at target location".to_string(),
-            ));
+    /// Check that a copied bundle is structurally complete and as large as its source
+    fn verify_bundle_copy(source: &Path, app_path: &Path) -> Result<(), AstudiosError> {
+        if !app_path.join("Contents").join("Info.plist").is_file() {
+            return Err(AstudiosError::Installation(format!(
+                "Copied bundle at {} is missing Contents/Info.plist",
+                app_path.display()
+            )));
         }
 
-        println!("      {} Application installed successfully", "✅".green());
-        Ok(app_path)
+        let expected = directory_size(source);
+        let copied = directory_size(app_path);
+        if copied != expected {
+            return Err(AstudiosError::Installation(format!(
+                "Copied bundle at {} is incomplete ({} of {})",
+                app_path.display(),
+                HumanBytes(copied),
+                HumanBytes(expected)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Remove an existing bundle, explaining what prevents it if that fails
+    fn remove_existing_bundle(app_path: &Path) -> Result<(), AstudiosError> {
+        let result = if app_path.is_symlink() || app_path.is_file() {
+            fs::remove_file(app_path)
+        } else {
+            fs::remove_dir_all(app_path)
+        };
+        let Err(e) = result else {
+            return Ok(());
+        };
+
+        let running = Command::new("pgrep")
+            .arg("-f")
+            .arg(app_path)
+            .output()
+            .is_ok_and(|output| output.status.success());
+        let reason = if running {
+            "Android Studio is still running from it; quit it and try again".to_string()
+        } else if e.kind() == std::io::ErrorKind::PermissionDenied {
+            "permission denied; check the ownership of the bundle or remove it manually".to_string()
+        } else {
+            e.to_string()
+        };
+
+        Err(AstudiosError::Installation(format!(
+            "Cannot replace the existing installation at {}: {reason}",
+            app_path.display()
+        )))
     }
 
     /// Clean up temporary files
@@ -460,7 +1532,8 @@ impl Installer {
         archive_path: &Path,
         extracted_path: &Path,
     ) -> Result<(), AstudiosError> {
-        if archive_path.exists() {
+        // Deduplicated archives are kept so later installs can reuse them
+        if archive_path.exists() && !self.dedupe_downloads {
             fs::remove_file(archive_path)?;
         }
         if extracted_path.exists() {
@@ -470,7 +1543,7 @@ impl Installer {
     }
 
     /// Verify installation integrity
-    fn verify_installation(&self, app_path: &Path) -> Result<(), AstudiosError> {
+    pub fn verify_installation(&self, app_path: &Path) -> Result<(), AstudiosError> {
         if !app_path.exists() {
             return Err(AstudiosError::Installation(format!(
                 "Installation not found at: {}",
@@ -490,19 +1563,104 @@ impl Installer {
             }
         }
 
+        Self::verify_launcher(app_path)?;
+
         // Verify code signing (optional - don't fail if it doesn't pass)
-        let _status = Command::new("codesign")
-            .args(["-v", app_path.to_str().unwrap()])
-            .status();
+        let _ = Self::verify_signature(app_path);
 
         Ok(())
     }
 
+    /// Check the code signature of an installed bundle with `codesign -v`.
+    ///
+    /// Succeeds without checking where `codesign` does not exist, i.e. outside macOS.
+    pub fn verify_signature(app_path: &Path) -> Result<(), AstudiosError> {
+        match Self::codesign_error(app_path) {
+            Ok(None) => Ok(()),
+            Ok(Some(error)) => Err(AstudiosError::Installation(format!(
+                "Code signature is invalid: {error}"
+            ))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Run `codesign -v` on `app_path`, returning what it reported if the
+    /// signature is invalid; failing to run `codesign` is left to the caller
+    fn codesign_error(app_path: &Path) -> Result<Option<String>, std::io::Error> {
+        let output = Command::new("codesign").arg("-v").arg(app_path).output()?;
+        Ok((!output.status.success())
+            .then(|| String::from_utf8_lossy(&output.stderr).trim().to_string()))
+    }
+
+    /// Check that the launcher named by CFBundleExecutable exists and is executable
+    fn verify_launcher(app_path: &Path) -> Result<(), AstudiosError> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let executable = InstalledAndroidStudio::bundle_executable(app_path).map_err(|e| {
+            AstudiosError::Installation(format!(
+                "Could not determine launcher executable for {}: {e}",
+                app_path.display()
+            ))
+        })?;
+
+        let launcher_path = app_path.join("Contents").join("MacOS").join(&executable);
+        let metadata = fs::metadata(&launcher_path).map_err(|_| {
+            AstudiosError::Installation(format!(
+                "Launcher executable missing: {}",
+                launcher_path.display()
+            ))
+        })?;
+
+        if !metadata.is_file() || metadata.permissions().mode() & 0o111 == 0 {
+            return Err(AstudiosError::Installation(format!(
+                "Launcher is not executable: {}",
+                launcher_path.display()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Launcher symlink for `channel` in the applications directory
+    pub fn channel_symlink_path(&self, channel: ReleaseChannel) -> PathBuf {
+        self.applications_dir.join(channel.symlink_name())
+    }
+
+    /// Launcher symlink that switching to the bundle at `app_path` updates
+    fn symlink_path_for(&self, app_path: &Path) -> PathBuf {
+        let channel = match self.symlink_channel {
+            Some(channel) => channel,
+            None if self.per_channel_symlinks => self
+                .installed_at(app_path.to_path_buf())
+                .ok()
+                .flatten()
+                .map_or(ReleaseChannel::Release, |installed| {
+                    installed.release_channel()
+                }),
+            None => ReleaseChannel::Release,
+        };
+        self.channel_symlink_path(channel)
+    }
+
+    /// Every launcher symlink astudios may have created, stable one first
+    fn launcher_symlinks(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for channel in ReleaseChannel::ALL {
+            let path = self.channel_symlink_path(channel);
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
     /// Create application symlink for version switching
     fn create_symlink(&self, app_path: &Path) -> Result<(), AstudiosError> {
-        let symlink_path = self.applications_dir.join("Android Studio.app");
+        let symlink_path = self.symlink_path_for(app_path);
+        SystemDetector::ensure_writable(&self.applications_dir)?;
 
-        println!(
+        sayln!(
             "{} {} Creating symlink...",
             "[5/5]".bold().blue(),
             "🔗".blue()
@@ -514,13 +1672,21 @@ impl Installer {
             match fs::symlink_metadata(&symlink_path) {
                 Ok(metadata) => {
                     if metadata.file_type().is_symlink() {
-                        println!("      {} Updating existing symlink...", "🔄".yellow());
+                        sayln!("      {} Updating existing symlink...", "🔄".yellow());
                         fs::remove_file(&symlink_path)?;
                     } else if metadata.is_dir() {
-                        println!("      {} Removing existing directory...", "🗑️".yellow());
+                        // A real bundle named like a preview channel's launcher is most
+                        // likely one the user installed by hand, e.g. from Google's DMG
+                        if symlink_path != self.channel_symlink_path(ReleaseChannel::Release) {
+                            return Err(AstudiosError::Installation(format!(
+                                "{} is a directory, not a launcher symlink; move it away, or select it with `astudios use --path`",
+                                symlink_path.display()
+                            )));
+                        }
+                        sayln!("      {} Removing existing directory...", "🗑️".yellow());
                         fs::remove_dir_all(&symlink_path)?;
                     } else {
-                        println!("      {} Removing existing file...", "🗑️".yellow());
+                        sayln!("      {} Removing existing file...", "🗑️".yellow());
                         fs::remove_file(&symlink_path)?;
                     }
                 }
@@ -545,8 +1711,8 @@ impl Installer {
         // Create new symlink (macOS/Unix)
         match std::os::unix::fs::symlink(app_path, &symlink_path) {
             Ok(_) => {
-                println!("      {} Symlink created successfully", "✅".green());
-                println!(
+                sayln!("      {} Symlink created successfully", "✅".green());
+                sayln!(
                     "      {} {}",
                     "Link:".dimmed(),
                     symlink_path.display().to_string().blue()
@@ -563,7 +1729,112 @@ impl Installer {
     }
 
     /// Uninstall a specific version
-    pub fn uninstall_version(&self, version: &str) -> Result<(), AstudiosError> {
+    ///
+    /// With `keep_files`, the staged archive under the install directory is
+    /// kept so a later install of the same version can reuse it.
+    pub fn uninstall_version(&self, version: &str, keep_files: bool) -> Result<(), AstudiosError> {
+        let installation = &self.find_uninstall_target(version)?;
+        let app_path = &installation.path;
+
+        let detailed_version = installation.extract_detailed_version();
+
+        sayln!(
+            "Uninstalling {} from {}...",
+            installation.enhanced_display_name().green(),
+            app_path.display().to_string().dimmed()
+        );
+        sayln!(
+            "Version: {} | Build: {}",
+            detailed_version.cyan(),
+            installation.identifier().blue()
+        );
+
+        // Remove the launcher symlinks that point at this version
+        for symlink_path in self.launcher_symlinks() {
+            if points_at(&symlink_path, app_path) {
+                sayln!("Removing symlink for currently active version...");
+                fs::remove_file(&symlink_path)?;
+            }
+        }
+
+        // Remove the application bundle
+        if app_path.exists() {
+            fs::remove_dir_all(app_path)?;
+            sayln!("Removed application bundle: {}", app_path.display());
+        }
+
+        let mut state = State::load()?;
+        if state.remove_install(app_path).is_some() {
+            state.save()?;
+        }
+
+        // Remove from install directory if it exists
+        if let Some(version_dir) = self.version_dir_of(installation, version) {
+            if keep_files {
+                sayln!("Kept installation files: {}", version_dir.display());
+                return Ok(());
+            }
+            fs::remove_dir_all(&version_dir)?;
+            sayln!("Removed installation files: {}", version_dir.display());
+        }
+
+        // Drop stored archives that no version directory links to anymore
+        let pruned = BlobStore::new()?.prune(&[&self.install_dir])?;
+        if pruned > 0 {
+            sayln!("Removed {pruned} unreferenced stored archive(s)");
+        }
+
+        Ok(())
+    }
+
+    /// Work out what [`uninstall_version`](Self::uninstall_version) would
+    /// remove, without removing anything
+    pub fn plan_uninstall(
+        &self,
+        version: &str,
+        keep_files: bool,
+        blobs: &BlobStore,
+    ) -> Result<UninstallPlan, AstudiosError> {
+        let installation = self.find_uninstall_target(version)?;
+        let symlinks = self
+            .launcher_symlinks()
+            .into_iter()
+            .filter(|symlink_path| points_at(symlink_path, &installation.path))
+            .collect();
+        let version_dir = self.version_dir_of(&installation, version);
+
+        // Uninstalling stops before pruning archives when the files are kept
+        let (version_dir_bytes, blobs) = if keep_files {
+            (0, Vec::new())
+        } else {
+            let removed: Vec<&Path> = version_dir.iter().map(PathBuf::as_path).collect();
+            let blobs = blobs
+                .unreferenced(&[&self.install_dir], &removed)?
+                .into_iter()
+                .map(|blob| {
+                    let size = fs::metadata(&blob).map_or(0, |m| m.len());
+                    (blob, size)
+                })
+                .collect();
+            (version_dir.as_deref().map_or(0, unshared_size), blobs)
+        };
+
+        Ok(UninstallPlan {
+            bundle_bytes: directory_size(&installation.path),
+            installation,
+            symlinks,
+            version_dir,
+            keep_files,
+            version_dir_bytes,
+            blobs,
+        })
+    }
+
+    /// The single installation `version` refers to for uninstalling
+    fn find_uninstall_target(
+        &self,
+        version: &str,
+    ) -> Result<InstalledAndroidStudio, AstudiosError> {
         let installations = self.list_installed_studios()?;
 
         // Find matching installations by version query
@@ -613,78 +1884,225 @@ impl Installer {
             return Err(AstudiosError::General(error_msg));
         }
 
-        // Uninstall the matched version
-        let installation = &matching_installations[0];
-        let app_path = &installation.path;
+        let installation = matching_installations[0];
+        if Self::is_toolbox_managed(&installation.path) {
+            return Err(AstudiosError::General(format!(
+                "{} is managed by JetBrains Toolbox; uninstall it from Toolbox instead",
+                installation.path.display()
+            )));
+        }
+        Ok(installation.clone())
+    }
 
-        let detailed_version = installation.extract_detailed_version();
+    /// Per-version directory of an installation, matched by short version,
+    /// then build version, then the version as given
+    fn version_dir_of(
+        &self,
+        installation: &InstalledAndroidStudio,
+        version: &str,
+    ) -> Option<PathBuf> {
+        [
+            self.install_dir.join(&installation.version.short_version),
+            self.install_dir.join(&installation.version.build_version),
+            self.install_dir.join(version),
+        ]
+        .into_iter()
+        .find(|version_dir| version_dir.exists())
+    }
 
-        println!(
-            "Uninstalling {} from {}...",
-            installation.enhanced_display_name().green(),
-            app_path.display().to_string().dimmed()
-        );
-        println!(
-            "Version: {} | Build: {}",
-            detailed_version.cyan(),
-            installation.identifier().blue()
-        );
+    /// Remove staging leftovers of failed runs: empty `extracted` directories,
+    /// zero-byte archives, and version directories they leave empty.
+    ///
+    /// A version directory holding a partial download, or the `.aria2` control
+    /// file of one, is left alone, since its download may be running or resumed
+    /// later. Only call this while holding the operation lock. Best effort:
+    /// returns what was removed and skips anything it cannot remove.
+    pub fn clean_stale_staging(&self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(&self.install_dir) else {
+            return Vec::new();
+        };
 
-        // Check if this is the currently active version
-        if let Ok(Some(active)) = self.get_active_studio()
-            && active.path == *app_path
-        {
-            println!("Removing symlink for currently active version...");
-            let symlink_path = self.applications_dir.join("Android Studio.app");
-            if symlink_path.exists() || symlink_path.is_symlink() {
-                fs::remove_file(&symlink_path)?;
+        let mut removed = Vec::new();
+        for version_dir in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if !version_dir.is_dir() || version_dir.is_symlink() {
+                continue;
+            }
+            let Ok(files) = fs::read_dir(&version_dir) else {
+                continue;
+            };
+            let files: Vec<PathBuf> = files.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+            if files.iter().any(|file| {
+                file.file_name().is_some_and(|name| {
+                    let name = name.to_string_lossy();
+                    name.ends_with(".part") || name.ends_with(".aria2")
+                })
+            }) {
+                continue;
+            }
+
+            for file in files {
+                let name = file.file_name().unwrap_or_default().to_string_lossy();
+                let Ok(metadata) = fs::symlink_metadata(&file) else {
+                    continue;
+                };
+                let dead = if metadata.is_dir() {
+                    name == "extracted"
+                        && fs::read_dir(&file).is_ok_and(|mut dir| dir.next().is_none())
+                        && fs::remove_dir(&file).is_ok()
+                } else {
+                    metadata.is_file()
+                        && metadata.len() == 0
+                        && ARCHIVE_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+                        && fs::remove_file(&file).is_ok()
+                };
+                if dead {
+                    removed.push(file);
+                }
+            }
+
+            // Only a directory emptied here goes; one that was already empty may
+            // have just been created by a download
+            if removed
+                .iter()
+                .any(|path| path.parent() == Some(&version_dir))
+                && fs::remove_dir(&version_dir).is_ok()
+            {
+                removed.push(version_dir);
             }
         }
+        removed
+    }
 
-        // Remove the application bundle
-        if app_path.exists() {
-            fs::remove_dir_all(app_path)?;
-            println!("Removed application bundle: {}", app_path.display());
+    /// Keep only the `keep` most recently used archives of each major track
+    /// in the version directories and remove the rest, oldest first, then drop
+    /// stored archives nothing links to anymore.
+    ///
+    /// Archives in the version directory of an installed version are never
+    /// evicted, though they count towards `keep`. Partial downloads are not
+    /// archives and are left alone.
+    pub fn evict_archives(
+        &self,
+        keep: usize,
+        blobs: &BlobStore,
+    ) -> Result<ArchiveEviction, AstudiosError> {
+        let mut protected = HashSet::new();
+        for installation in self.list_installed_studios()? {
+            protected.insert(self.install_dir.join(&installation.version.short_version));
+            protected.insert(self.install_dir.join(&installation.version.build_version));
+        }
+        for (app_path, record) in State::load()?.installs {
+            if app_path.exists() {
+                protected.insert(self.install_dir.join(record.version));
+            }
         }
 
-        // Remove from install directory if it exists
-        // Try to match by short version first, then by build version
-        let possible_version_dirs = vec![
-            self.install_dir.join(&installation.version.short_version),
-            self.install_dir.join(&installation.version.build_version),
-            self.install_dir.join(version), // Original input
-        ];
+        // Archives per major track, with when each was last used
+        let mut tracks: BTreeMap<String, Vec<(SystemTime, PathBuf)>> = BTreeMap::new();
+        let Ok(entries) = fs::read_dir(&self.install_dir) else {
+            return Ok(ArchiveEviction::default());
+        };
+        for version_dir in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if !version_dir.is_dir() || version_dir.is_symlink() {
+                continue;
+            }
+            let name = version_dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            let track = major_track(&name);
+            for file in fs::read_dir(&version_dir)?.filter_map(|e| e.ok()) {
+                let file_name = file.file_name().to_string_lossy().into_owned();
+                let Ok(metadata) = file.metadata() else {
+                    continue;
+                };
+                if !metadata.is_file()
+                    || !ARCHIVE_SUFFIXES
+                        .iter()
+                        .any(|suffix| file_name.ends_with(suffix))
+                {
+                    continue;
+                }
+                let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+                let last_used = metadata.accessed().map_or(modified, |a| a.max(modified));
+                tracks
+                    .entry(track.clone())
+                    .or_default()
+                    .push((last_used, file.path()));
+            }
+        }
 
-        for version_dir in possible_version_dirs {
-            if version_dir.exists() {
-                fs::remove_dir_all(&version_dir)?;
-                println!("Removed installation files: {}", version_dir.display());
-                break;
+        let mut eviction = ArchiveEviction::default();
+        for mut archives in tracks.into_values() {
+            archives.sort_by(|a, b| b.cmp(a));
+            for (_, archive) in archives.into_iter().skip(keep) {
+                if archive.parent().is_some_and(|dir| protected.contains(dir)) {
+                    continue;
+                }
+                let metadata = fs::metadata(&archive)?;
+                fs::remove_file(&archive)?;
+                if metadata.nlink() == 1 {
+                    eviction.reclaimed_bytes += metadata.len();
+                }
+                eviction.evicted.push(archive);
             }
         }
 
-        Ok(())
+        if !eviction.evicted.is_empty() {
+            for blob in blobs.unreferenced(&[&self.install_dir], &[])? {
+                eviction.reclaimed_bytes += fs::metadata(&blob).map_or(0, |m| m.len());
+            }
+            blobs.prune(&[&self.install_dir])?;
+        }
+        eviction.evicted.sort();
+        Ok(eviction)
     }
 
     /// List all installed Android Studio instances
     pub fn list_installed_studios(&self) -> Result<Vec<InstalledAndroidStudio>, AstudiosError> {
         let mut installations = Vec::new();
+        // Real bundle locations, since a bundle may be present both directly and via a symlink
+        let mut seen = HashSet::new();
+        let launcher_names = self.launcher_symlinks();
 
         if let Ok(entries) = fs::read_dir(&self.applications_dir) {
-            for entry in entries.filter_map(|e| e.ok()) {
-                let path = entry.path();
+            // Bundles stored directly come first, so they win over symlinks to them
+            let (symlinks, bundles): (Vec<PathBuf>, Vec<PathBuf>) = entries
+                .filter_map(|e| e.ok())
+                .map(|entry| entry.path())
+                .partition(|path| path.is_symlink());
+
+            for path in bundles.into_iter().chain(symlinks) {
                 let name = path.file_name().unwrap_or_default().to_string_lossy();
 
-                // Check if it's an Android Studio app bundle
-                if name.contains("Android Studio") && name.ends_with(".app") {
-                    // Skip any other symlinks to avoid duplicates
-                    if path.is_symlink() {
-                        continue;
-                    }
+                // Bundles may be named by any template, so Android Studio is
+                // recognized from its metadata rather than its name
+                if !name.ends_with(".app") {
+                    continue;
+                }
+
+                // The active symlink only points at a bundle listed on its own
+                if path.is_symlink()
+                    && (name == "Android Studio.app"
+                        || self.per_channel_symlinks && launcher_names.contains(&path))
+                {
+                    continue;
+                }
 
-                    if let Ok(Some(installed)) = InstalledAndroidStudio::new(path) {
-                        installations.push(installed);
+                // Other symlinks are bundles kept elsewhere, e.g. on an external drive
+                let bundle = if path.is_symlink() {
+                    match fs::canonicalize(&path) {
+                        Ok(target) => target,
+                        Err(_) => continue,
                     }
+                } else {
+                    path
+                };
+                if !seen.insert(fs::canonicalize(&bundle).unwrap_or_else(|_| bundle.clone())) {
+                    continue;
+                }
+
+                if let Ok(Some(installed)) = self.installed_at(bundle) {
+                    installations.push(installed);
                 }
             }
         }
@@ -694,6 +2112,31 @@ impl Installer {
         Ok(installations)
     }
 
+    /// Android Studio bundles installed by JetBrains Toolbox, newest first.
+    /// astudios lists them but never modifies them.
+    pub fn list_toolbox_studios() -> Result<Vec<InstalledAndroidStudio>, AstudiosError> {
+        let overrides = State::load().unwrap_or_default().channel_overrides;
+        let mut installations: Vec<_> =
+            find_app_bundles(&Config::toolbox_apps_dir()?, TOOLBOX_SCAN_DEPTH)
+                .into_iter()
+                .filter_map(|bundle| InstalledAndroidStudio::new(bundle).ok().flatten())
+                .map(|mut installed| {
+                    installed.channel_override = overrides.get(&installed.path).cloned();
+                    installed
+                })
+                // Toolbox also manages IntelliJ IDEA and the other JetBrains IDEs
+                .filter(|installed| installed.version.product_code == "AI")
+                .collect();
+        installations.sort_by(|a, b| b.cmp(a));
+        Ok(installations)
+    }
+
+    /// Whether the bundle at `app_path` belongs to JetBrains Toolbox
+    pub fn is_toolbox_managed(app_path: &Path) -> bool {
+        Config::toolbox_apps_dir()
+            .is_ok_and(|dir| resolve_path(app_path).starts_with(resolve_path(&dir)))
+    }
+
     /// List all installed versions (legacy compatibility)
     pub fn list_installed_versions(&self) -> Result<Vec<String>, AstudiosError> {
         let installations = self.list_installed_studios()?;
@@ -703,14 +2146,25 @@ impl Installer {
             .collect())
     }
 
-    /// Get currently active Android Studio installation
+    /// Get currently active Android Studio installation, read from the launcher
+    /// symlink of the channel chosen with `with_symlink_channel` or else the
+    /// stable one
     pub fn get_active_studio(&self) -> Result<Option<InstalledAndroidStudio>, AstudiosError> {
-        let symlink_path = self.applications_dir.join("Android Studio.app");
+        let channel = self.symlink_channel.unwrap_or(ReleaseChannel::Release);
+        self.get_active_studio_for(channel)
+    }
+
+    /// Installation the launcher symlink of `channel` points at
+    pub fn get_active_studio_for(
+        &self,
+        channel: ReleaseChannel,
+    ) -> Result<Option<InstalledAndroidStudio>, AstudiosError> {
+        let symlink_path = self.channel_symlink_path(channel);
 
         if symlink_path.exists()
             && symlink_path.is_symlink()
-            && let Ok(target) = fs::read_link(&symlink_path)
-            && let Ok(Some(installed)) = InstalledAndroidStudio::new(target)
+            && let Some(target) = symlink_target(&symlink_path)
+            && let Ok(Some(installed)) = self.installed_at(target)
         {
             return Ok(Some(installed));
         }
@@ -718,6 +2172,57 @@ impl Installer {
         Ok(None)
     }
 
+    /// Active installation of every channel that has a launcher symlink, stable
+    /// first; empty unless per-channel symlinks are enabled
+    pub fn active_studios(
+        &self,
+    ) -> Result<Vec<(ReleaseChannel, InstalledAndroidStudio)>, AstudiosError> {
+        if !self.per_channel_symlinks {
+            return Ok(Vec::new());
+        }
+
+        let mut active = Vec::new();
+        let mut seen = HashSet::new();
+        for channel in ReleaseChannel::ALL {
+            if seen.insert(channel.symlink_name())
+                && let Some(installed) = self.get_active_studio_for(channel)?
+            {
+                active.push((channel, installed));
+            }
+        }
+        Ok(active)
+    }
+
+    /// Installation to treat as active when there is no symlink: `preferred` if it
+    /// is still installed, otherwise the most recently installed bundle
+    pub fn default_studio(
+        &self,
+        preferred: Option<&Path>,
+    ) -> Result<Option<InstalledAndroidStudio>, AstudiosError> {
+        if let Some(path) = preferred
+            && let Ok(Some(installed)) = self.installed_at(path.to_path_buf())
+        {
+            return Ok(Some(installed));
+        }
+
+        Ok(self
+            .list_installed_studios()?
+            .into_iter()
+            .max_by_key(InstalledAndroidStudio::installed_at))
+    }
+
+    /// The installation at `path`, if it is an Android Studio bundle, with the
+    /// channel label set for it with `set-channel`
+    pub fn installed_at(
+        &self,
+        path: PathBuf,
+    ) -> Result<Option<InstalledAndroidStudio>, AstudiosError> {
+        Ok(InstalledAndroidStudio::new(path)?.map(|mut installed| {
+            installed.channel_override = self.channel_overrides.get(&installed.path).cloned();
+            installed
+        }))
+    }
+
     /// Get currently active version (legacy compatibility)
     pub fn get_active_version(&self) -> Result<Option<String>, AstudiosError> {
         if let Some(active) = self.get_active_studio()? {
@@ -729,33 +2234,110 @@ impl Installer {
 
     /// Switch to a different Android Studio installation by identifier
     pub fn switch_to_studio(&self, identifier: &str) -> Result<(), AstudiosError> {
-        let installations = self.list_installed_studios()?;
+        let target_installation = self.find_installed(identifier)?;
+        self.create_symlink(&target_installation.path)?;
+        Ok(())
+    }
+
+    /// Find an installed bundle by build, short version, API version prefix, or
+    /// codename (e.g., "Koala")
+    pub fn find_installed(
+        &self,
+        identifier: &str,
+    ) -> Result<InstalledAndroidStudio, AstudiosError> {
+        let mut installations = self.list_installed_studios()?;
 
         // Find installation by various version identifiers
-        let target_installation = installations
-            .iter()
-            .find(|install| {
-                // Match by build version (e.g., "AI-251.26094.121.2513.14007798")
-                install.identifier() == identifier ||
-                // Match by short version (e.g., "2025.1")
-                install.version.short_version == identifier ||
+        if let Some(index) = installations.iter().position(|install| {
+            // Match by build version (e.g., "AI-251.26094.121.2513.14007798")
+            install.identifier() == identifier ||
+            // Match by short version (e.g., "2025.1")
+            install.version.short_version == identifier ||
+
+            // Match by API version (e.g., "2025.1.3.7")
+            install.get_full_version_from_api().unwrap_or(None).as_ref() == Some(&identifier.to_string()) ||
+            // Partial match for short version (e.g., "2025.1" matches "2025.1.2")
+            install.version.short_version.starts_with(identifier) ||
+
+            // Partial match for API version (e.g., "2025.1.3" matches "2025.1.3.7")
+            install.get_full_version_from_api().unwrap_or(None).as_ref().is_some_and(|v| v.starts_with(identifier))
+        }) {
+            return Ok(installations.swap_remove(index));
+        }
 
-                // Match by API version (e.g., "2025.1.3.7")
-                install.get_full_version_from_api().unwrap_or(None).as_ref() == Some(&identifier.to_string()) ||
-                // Partial match for short version (e.g., "2025.1" matches "2025.1.2")
-                install.version.short_version.starts_with(identifier) ||
+        // Codenames only appear in the feed's release names, so look the bundles up there
+        let releases = AndroidStudioLister::new()
+            .and_then(|lister| lister.get_releases())
+            .map(|releases| releases.items)
+            .unwrap_or_default();
+        let matches = Self::match_codename(&installations, &releases, identifier);
+        match matches.as_slice() {
+            [install] => Ok((*install).clone()),
+            [] => Err(AstudiosError::VersionNotFound(format!(
+                "Android Studio with identifier '{identifier}' is not installed.\nUse 'astudios installed' to see installed versions or 'astudios install {identifier}' to install it."
+            ))),
+            _ => {
+                let mut error_msg = format!(
+                    "Multiple installed versions match '{identifier}'. Please be more specific:\n"
+                );
+                for install in &matches {
+                    error_msg.push_str(&format!(
+                        "  - {} ({})\n",
+                        install.display_name(),
+                        install.identifier()
+                    ));
+                }
+                Err(AstudiosError::General(error_msg))
+            }
+        }
+    }
 
-                // Partial match for API version (e.g., "2025.1.3" matches "2025.1.3.7")
-                install.get_full_version_from_api().unwrap_or(None).as_ref().is_some_and(|v| v.starts_with(identifier))
+    /// Installations whose release in the feed is named after `codename`.
+    ///
+    /// The codename is matched case-insensitively against whole words of the
+    /// release name, so "koala" and "Koala Feature Drop" both find
+    /// "Android Studio Koala Feature Drop | 2024.1.2", while "2" matches nothing.
+    pub fn match_codename<'a>(
+        installations: &'a [InstalledAndroidStudio],
+        releases: &[AndroidStudio],
+        codename: &str,
+    ) -> Vec<&'a InstalledAndroidStudio> {
+        let words = |text: &str| {
+            text.split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>()
+        };
+        let query = words(codename);
+        if query.is_empty() || !codename.chars().any(char::is_alphabetic) {
+            return Vec::new();
+        }
+
+        installations
+            .iter()
+            .filter(|install| {
+                releases.iter().any(|release| {
+                    release.build == install.version.build_version
+                        && words(&release.name)
+                            .windows(query.len())
+                            .any(|window| window == query.as_slice())
+                })
             })
-            .ok_or_else(|| {
-                AstudiosError::VersionNotFound(format!(
-                    "Android Studio with identifier '{identifier}' is not installed.\nUse 'astudios installed' to see installed versions or 'astudios install {identifier}' to install it."
-                ))
-            })?;
+            .collect()
+    }
 
-        self.create_symlink(&target_installation.path)?;
-        Ok(())
+    /// Point the "Android Studio.app" symlink at a bundle that astudios did not install
+    pub fn switch_to_path(&self, app_path: &Path) -> Result<InstalledAndroidStudio, AstudiosError> {
+        let app_path = resolve_path(app_path);
+        let installed = self.installed_at(app_path.clone())?.ok_or_else(|| {
+            AstudiosError::Path(format!(
+                "{} is not an Android Studio app bundle",
+                app_path.display()
+            ))
+        })?;
+
+        self.create_symlink(&installed.path)?;
+        Ok(installed)
     }
 
     /// Switch to a different version (legacy compatibility)
@@ -763,3 +2345,131 @@ impl Installer {
         self.switch_to_studio(version)
     }
 }
+
+/// Wait for `child` to exit, giving up after `timeout`; `None` if it is still running
+pub fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Duration,
+) -> Result<Option<ExitStatus>, AstudiosError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(MOUNT_POLL_INTERVAL_MS));
+    }
+}
+
+/// Find `.app` bundles below `dir`, descending at most `depth` levels
+fn find_app_bundles(dir: &Path, depth: usize) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut bundles = Vec::new();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !path.is_dir() || path.is_symlink() {
+            continue;
+        }
+        if path.extension().is_some_and(|ext| ext == "app") {
+            bundles.push(path);
+        } else if depth > 0 {
+            bundles.extend(find_app_bundles(&path, depth - 1));
+        }
+    }
+    bundles
+}
+
+/// Make a path absolute and resolve symlinks, `.` and `..`, even when its
+/// trailing components do not exist yet
+fn resolve_path(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .unwrap_or_else(|_| PathBuf::from("/"))
+            .join(path)
+    };
+
+    let mut existing = absolute.as_path();
+    let mut missing = Vec::new();
+    loop {
+        if let Ok(resolved) = existing.canonicalize() {
+            let mut resolved = resolved;
+            for component in missing.iter().rev() {
+                match component {
+                    Component::ParentDir => {
+                        resolved.pop();
+                    }
+                    Component::CurDir => {}
+                    other => resolved.push(other),
+                }
+            }
+            return resolved;
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(last)) => {
+                missing.push(last);
+                existing = parent;
+            }
+            _ => return absolute,
+        }
+    }
+}
+
+/// Where the symlink at `link` points, with a relative target resolved
+/// against the directory holding the link
+fn symlink_target(link: &Path) -> Option<PathBuf> {
+    let target = fs::read_link(link).ok()?;
+    Some(match link.parent() {
+        Some(parent) => parent.join(target),
+        None => target,
+    })
+}
+
+/// Whether `link` is a symlink to `path`. Both sides are resolved first, so a
+/// relative target, a `..` or a symlinked parent directory still match.
+fn points_at(link: &Path, path: &Path) -> bool {
+    link.is_symlink()
+        && symlink_target(link).is_some_and(|target| resolve_path(&target) == resolve_path(path))
+}
+
+/// Name to stage a downloaded archive under, e.g. "android-studio-2025.1.1.1-mac_arm"
+/// for "android-studio-2025.1.1.1-mac_arm.dmg"
+fn archive_label(filename: &str) -> String {
+    Path::new(filename)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or("android-studio")
+        .to_string()
+}
+
+/// Recursively sum the sizes of files under `path` that have no other hardlink,
+/// so archives shared with the blob store are not counted
+fn unshared_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+
+    if !metadata.is_dir() {
+        return if metadata.nlink() > 1 {
+            0
+        } else {
+            metadata.len()
+        };
+    }
+
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| unshared_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
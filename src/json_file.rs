@@ -0,0 +1,55 @@
+use crate::error::AstudiosError;
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// A document astudios keeps between runs as a JSON file, such as the state
+/// or the preferences
+pub trait JsonFile: Serialize + DeserializeOwned + Default {
+    /// Default location of the file
+    fn path() -> Result<PathBuf, AstudiosError>;
+
+    /// Load from the default location
+    fn load() -> Result<Self, AstudiosError> {
+        Self::load_from(&Self::path()?)
+    }
+
+    /// Load from `path`, returning the default value if the file does not exist
+    fn load_from(path: &Path) -> Result<Self, AstudiosError> {
+        match fs::read_to_string(path) {
+            Ok(data) => Ok(serde_json::from_str(&data)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save to the default location
+    fn save(&self) -> Result<(), AstudiosError> {
+        self.save_to(&Self::path()?)
+    }
+
+    /// Save to `path`, creating its directory if needed
+    fn save_to(&self, path: &Path) -> Result<(), AstudiosError> {
+        write_atomic(path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+}
+
+/// Replace the file at `path` with `data`, creating its directory if needed.
+///
+/// The data goes to a temporary file next to `path` that is then renamed over
+/// it, so readers and a crash mid-write never leave a truncated file behind.
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), AstudiosError> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(dir)?;
+
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(data)?;
+    tmp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
@@ -1,9 +1,24 @@
 pub mod api;
+pub mod aria2_rpc;
+pub mod audit;
+pub mod batch;
+pub mod blobs;
 pub mod config;
+pub mod confirm;
 pub mod detector;
 pub mod downloader;
 pub mod error;
+pub mod events;
+pub mod history;
 pub mod installer;
+pub mod json_file;
 pub mod list;
+pub mod lock;
 pub mod model;
+pub mod output;
+pub mod preferences;
 pub mod progress;
+pub mod self_update;
+pub mod shim;
+pub mod sizes;
+pub mod state;
@@ -2,66 +2,278 @@ use crate::{
     api::ApiClient,
     config::Config,
     error::AstudiosError,
-    model::{AndroidStudio, AndroidStudioReleasesList},
+    json_file,
+    model::{AndroidStudio, AndroidStudioReleasesList, ReleaseChannel, major_track},
 };
+use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::{
+    collections::HashSet,
     fs,
     path::PathBuf,
-    time::{Duration, SystemTime},
+    str::FromStr,
+    sync::OnceLock,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+/// Version of the on-disk cache layout; bump it whenever the cached data changes shape
+pub const CACHE_SCHEMA: u32 = 1;
+
+/// On-disk wrapper around the cached releases list
+#[derive(Debug, Deserialize, Serialize)]
+struct CacheEnvelope<T> {
+    schema: u32,
+    /// Seconds since the Unix epoch at which the data was fetched
+    fetched_at: u64,
+    data: T,
+}
+
+/// Process-wide cache freshness override (set from the `--max-age` flag)
+static MAX_AGE_OVERRIDE: OnceLock<CacheMaxAge> = OnceLock::new();
+
+/// How long a cached releases list is considered fresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMaxAge {
+    /// The cache is fresh while it is younger than the given duration (zero always fetches)
+    Within(Duration),
+    /// Any existing cache is fresh, regardless of its age
+    Never,
+}
+
+impl CacheMaxAge {
+    /// Freshness `list` accepts: browsing tolerates a day-old list
+    pub const LIST: CacheMaxAge =
+        CacheMaxAge::Within(Duration::from_secs(Config::CACHE_DURATION_SECS));
+
+    /// Freshness `install` and `download` accept
+    pub const INSTALL: CacheMaxAge =
+        CacheMaxAge::Within(Duration::from_secs(Config::INSTALL_CACHE_DURATION_SECS));
+
+    /// Override the cache freshness policy for every lister created by this process
+    pub fn set_override(max_age: CacheMaxAge) {
+        let _ = MAX_AGE_OVERRIDE.set(max_age);
+    }
+
+    /// The freshness policy in effect for this process
+    pub fn current() -> CacheMaxAge {
+        MAX_AGE_OVERRIDE.get().copied().unwrap_or_default()
+    }
+
+    /// The `--max-age` override if one was given, otherwise a command's own policy
+    pub fn current_or(default: CacheMaxAge) -> CacheMaxAge {
+        MAX_AGE_OVERRIDE.get().copied().unwrap_or(default)
+    }
+
+    /// Check whether a cache of the given age should be used
+    pub fn is_fresh(&self, age: Duration) -> bool {
+        match self {
+            CacheMaxAge::Within(max_age) => age < *max_age,
+            CacheMaxAge::Never => true,
+        }
+    }
+}
+
+impl Default for CacheMaxAge {
+    fn default() -> Self {
+        CacheMaxAge::LIST
+    }
+}
+
+impl FromStr for CacheMaxAge {
+    type Err = AstudiosError;
+
+    /// Parse a human duration such as `45s`, `30m`, `2h`, `1d`, `0` or `never`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase();
+        if value == "never" {
+            return Ok(CacheMaxAge::Never);
+        }
+
+        let invalid = || {
+            AstudiosError::Config(format!(
+                "Invalid duration '{value}'. Use a number with an optional unit (s, m, h, d), e.g. '30m', '2h', '1d', or 'never'"
+            ))
+        };
+
+        let split = value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len());
+        let (amount, unit) = value.split_at(split);
+        let amount: u64 = amount.parse().map_err(|_| invalid())?;
+
+        let multiplier = match unit {
+            "" | "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 60 * 60 * 24,
+            _ => return Err(invalid()),
+        };
+
+        let secs = amount.checked_mul(multiplier).ok_or_else(invalid)?;
+        Ok(CacheMaxAge::Within(Duration::from_secs(secs)))
+    }
+}
+
+/// Outcome of a forced refresh of the releases list
+pub struct RefreshSummary {
+    /// The list that was cached before the refresh, if any
+    pub previous: Option<AndroidStudioReleasesList>,
+    /// The freshly fetched list
+    pub current: AndroidStudioReleasesList,
+}
+
+impl RefreshSummary {
+    /// Releases whose build was not in the previous cache
+    pub fn new_releases(&self) -> Vec<&AndroidStudio> {
+        let Some(previous) = &self.previous else {
+            return Vec::new();
+        };
+
+        let known: HashSet<&str> = previous
+            .items
+            .iter()
+            .map(|item| item.build.as_str())
+            .collect();
+        self.current
+            .items
+            .iter()
+            .filter(|item| !known.contains(item.build.as_str()))
+            .collect()
+    }
+}
+
 /// Manages Android Studio releases listing with caching support
 pub struct AndroidStudioLister {
     cache_dir: PathBuf,
+    max_age: CacheMaxAge,
+    feed_url: String,
 }
 
 impl AndroidStudioLister {
     /// Create a new Android Studio lister with default cache directory
     pub fn new() -> Result<Self, AstudiosError> {
-        let cache_dir = Config::cache_dir();
-        fs::create_dir_all(&cache_dir)?;
-        Ok(Self { cache_dir })
+        Self::with_cache_dir(Config::cache_dir()?)
     }
 
     /// Create a new Android Studio lister with custom cache directory
     pub fn with_cache_dir(cache_dir: PathBuf) -> Result<Self, AstudiosError> {
         fs::create_dir_all(&cache_dir)?;
-        Ok(Self { cache_dir })
+        Ok(Self {
+            cache_dir,
+            max_age: CacheMaxAge::current(),
+            feed_url: Config::releases_feed_url(),
+        })
     }
 
-    /// Get Android Studio releases with caching
-    pub fn get_releases(&self) -> Result<AndroidStudioReleasesList, AstudiosError> {
-        let cache_path = self.cache_dir.join("releases.json");
+    /// Fetch the releases list from `url` instead of the JetBrains feed
+    pub fn with_feed_url(mut self, url: impl Into<String>) -> Self {
+        self.feed_url = url.into();
+        self
+    }
 
-        // Check if cache exists and is valid
-        if let Some(cached) = self.load_cached_releases(&cache_path)? {
-            // Show brief feedback when using cached data (using stderr for immediate display)
-            eprintln!("ℹ️  Loading Android Studio versions from cache...");
-            return Ok(cached);
-        }
+    /// Use a custom cache freshness policy for this lister
+    pub fn with_max_age(mut self, max_age: CacheMaxAge) -> Self {
+        self.max_age = max_age;
+        self
+    }
 
-        // Fetch fresh data with progress indication
-        eprintln!("🌐 Fetching Android Studio releases from JetBrains...");
-        std::io::stderr().flush().ok();
+    /// Path of the cached releases list
+    pub fn cache_path(&self) -> PathBuf {
+        self.cache_dir.join("releases.json")
+    }
 
-        let client = ApiClient::new()?;
-        let content = client.fetch_releases()?;
+    /// Path of the releases list the cache held before its last refresh
+    pub fn previous_cache_path(&self) -> PathBuf {
+        self.cache_dir.join("releases.prev.json")
+    }
+
+    /// Builds in the releases list the cache held before its last refresh, or
+    /// `None` if the cache has not been refreshed since it was first written
+    pub fn previous_builds(&self) -> Option<HashSet<String>> {
+        let envelope = self.read_cache(&self.previous_cache_path())?;
+        Some(
+            envelope
+                .data
+                .items
+                .into_iter()
+                .map(|item| item.build)
+                .collect(),
+        )
+    }
+
+    /// Age of the cached releases list, or `None` if there is no usable cache
+    pub fn cache_age(&self) -> Result<Option<Duration>, AstudiosError> {
+        Ok(self
+            .read_cache(&self.cache_path())
+            .map(|envelope| fetched_age(envelope.fetched_at)))
+    }
+
+    /// Check whether the cache exists and is fresh under this lister's max age
+    pub fn is_cache_fresh(&self) -> Result<bool, AstudiosError> {
+        Ok(self
+            .cache_age()?
+            .is_some_and(|age| self.max_age.is_fresh(age)))
+    }
 
-        // Cache the data
-        self.save_releases_to_cache(&cache_path, &content)?;
+    /// Get Android Studio releases with caching, under this lister's max age
+    pub fn get_releases(&self) -> Result<AndroidStudioReleasesList, AstudiosError> {
+        self.refresh_if_stale(self.max_age)
+    }
+
+    /// Serve the cached releases list if it is fresh under `max_age`, otherwise
+    /// fetch the feed and rewrite the cache.
+    ///
+    /// When the fetch fails, an outdated cache is served with a warning rather
+    /// than failing.
+    pub fn refresh_if_stale(
+        &self,
+        max_age: CacheMaxAge,
+    ) -> Result<AndroidStudioReleasesList, AstudiosError> {
+        let cache_path = self.cache_path();
+        let cached = match self.read_cache(&cache_path) {
+            Some(envelope) if max_age.is_fresh(fetched_age(envelope.fetched_at)) => {
+                // Show brief feedback when using cached data (using stderr for immediate display)
+                eprintln!("ℹ️  Loading Android Studio versions from cache...");
+                return Ok(envelope.data);
+            }
+            cached => cached,
+        };
+
+        let content = match self.fetch_and_cache(&cache_path) {
+            Ok(content) => content,
+            Err(e) => {
+                // An outdated list beats no list when the feed is down or garbled
+                let Some(stale) = cached else {
+                    return Err(e);
+                };
+                eprintln!("⚠️  Could not fetch releases ({e}); using the cached list instead");
+                return Ok(stale.data);
+            }
+        };
         eprintln!("✅ Successfully fetched {} releases", content.items.len());
 
         Ok(content)
     }
 
-    /// Get the latest stable release
+    /// Fetch the releases list again regardless of the cache's age, failing
+    /// instead of falling back to the cache
+    pub fn refresh(&self) -> Result<RefreshSummary, AstudiosError> {
+        let cache_path = self.cache_path();
+
+        // Keep the previous list around to report what changed
+        let previous = self.read_cache(&cache_path).map(|envelope| envelope.data);
+        let current = self.fetch_and_cache(&cache_path)?;
+
+        Ok(RefreshSummary { previous, current })
+    }
+
+    /// Get the latest release the feed explicitly labels stable
     pub fn get_latest_release(&self) -> Result<AndroidStudio, AstudiosError> {
         let releases = self.get_releases()?;
         releases
             .items
             .into_iter()
-            .find(|item| item.is_release())
+            .find(|item| item.is_stable())
             .ok_or_else(|| {
                 AstudiosError::VersionNotFound("No release versions available".to_string())
             })
@@ -79,67 +291,59 @@ impl AndroidStudioLister {
             })
     }
 
-    /// Find a version by query string (supports partial matches)
+    /// Find the release a query string names (supports partial matches): an
+    /// exact version or build, or else the newest of the most relevant matches
     pub fn find_version_by_query(&self, query: &str) -> Result<AndroidStudio, AstudiosError> {
         let releases = self.get_releases()?;
-        let query = query.to_lowercase();
-
-        // Try exact version match first
-        if let Some(item) = releases.items.iter().find(|item| item.version == query) {
-            return Ok(item.clone());
-        }
-
-        // Try partial version match
-        if let Some(item) = releases
-            .items
-            .iter()
-            .find(|item| item.version.to_lowercase().contains(&query))
-        {
-            return Ok(item.clone());
-        }
-
-        // Try name match
-        if let Some(item) = releases
-            .items
-            .iter()
-            .find(|item| item.name.to_lowercase().contains(&query))
-        {
-            return Ok(item.clone());
-        }
+        rank_matches(&releases.items, query)
+            .first()
+            .map(|(_, item)| (*item).clone())
+            .ok_or_else(|| version_not_found(query))
+    }
 
-        // Try build number match
-        if let Some(item) = releases
-            .items
-            .iter()
-            .find(|item| item.build.to_lowercase().contains(&query))
-        {
-            return Ok(item.clone());
-        }
+    /// Find every release matching a query string, most relevant first
+    pub fn find_all_matching(&self, query: &str) -> Result<Vec<AndroidStudio>, AstudiosError> {
+        let releases = self.get_releases()?;
+        Ok(rank_matches(&releases.items, query)
+            .into_iter()
+            .map(|(_, item)| item.clone())
+            .collect())
+    }
 
-        // Try channel-based search
-        self.find_by_channel_query(&releases.items, &query)
+    /// Find the release with exactly this build, e.g. "AI-242.23339.11.2421.12483815"
+    pub fn find_by_build(&self, build: &str) -> Result<Option<AndroidStudio>, AstudiosError> {
+        let releases = self.get_releases()?;
+        Ok(releases.items.into_iter().find(|item| item.build == build))
     }
 
-    /// Load releases from cache if valid
-    fn load_cached_releases(
+    /// Fetch the feed and atomically replace the cache with it
+    fn fetch_and_cache(
         &self,
         cache_path: &PathBuf,
-    ) -> Result<Option<AndroidStudioReleasesList>, AstudiosError> {
-        if !cache_path.exists() {
-            return Ok(None);
-        }
+    ) -> Result<AndroidStudioReleasesList, AstudiosError> {
+        eprintln!("🌐 Fetching Android Studio releases from JetBrains...");
+        std::io::stderr().flush().ok();
+
+        let client = ApiClient::new()?.with_feed_url(&self.feed_url);
+        let content = client.fetch_releases()?;
+        self.save_releases_to_cache(cache_path, &content)?;
 
-        let metadata = fs::metadata(cache_path)?;
-        let modified = metadata.modified()?;
-        let age = SystemTime::now().duration_since(modified)?;
+        Ok(content)
+    }
 
-        if age < Duration::from_secs(Config::CACHE_DURATION_SECS) {
-            let data = fs::read_to_string(cache_path)?;
-            let content: AndroidStudioReleasesList = serde_json::from_str(&data)?;
-            return Ok(Some(content));
+    /// Read the cache envelope, treating a missing, unreadable or outdated cache as a miss
+    fn read_cache(&self, cache_path: &PathBuf) -> Option<CacheEnvelope<AndroidStudioReleasesList>> {
+        let data = fs::read_to_string(cache_path).ok()?;
+        let envelope: CacheEnvelope<serde_json::Value> = serde_json::from_str(&data).ok()?;
+        if envelope.schema != CACHE_SCHEMA {
+            return None;
         }
 
-        Ok(None)
+        Some(CacheEnvelope {
+            schema: envelope.schema,
+            fetched_at: envelope.fetched_at,
+            data: serde_json::from_value(envelope.data).ok()?,
+        })
     }
 
     /// Save releases to cache
@@ -148,34 +352,22 @@ impl AndroidStudioLister {
         cache_path: &PathBuf,
         content: &AndroidStudioReleasesList,
     ) -> Result<(), AstudiosError> {
-        let data = serde_json::to_string_pretty(content)?;
-        fs::write(cache_path, data)?;
-        Ok(())
-    }
+        let fetched_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let envelope = CacheEnvelope {
+            schema: CACHE_SCHEMA,
+            fetched_at,
+            data: content,
+        };
+        let data = serde_json::to_string_pretty(&envelope)?;
 
-    /// Find version by channel-based query (e.g., "2023.3.1 Canary 8")
-    fn find_by_channel_query(
-        &self,
-        items: &[AndroidStudio],
-        query: &str,
-    ) -> Result<AndroidStudio, AstudiosError> {
-        let parts: Vec<&str> = query.split_whitespace().collect();
-
-        if parts.len() >= 2 {
-            let version_part = parts[0];
-            let channel_part = parts[1].to_lowercase();
-
-            // Try to find matching version and channel
-            if let Some(item) = items.iter().find(|item| {
-                item.version.contains(version_part) && item.channel.to_lowercase() == channel_part
-            }) {
-                return Ok(item.clone());
-            }
+        // Keep the list being replaced, so `list --new` can tell what appeared since.
+        // Failing to is no reason to lose the fresh list; an outdated snapshot
+        // is dropped instead, so nothing is reported as new against it.
+        if cache_path.exists() && fs::copy(cache_path, self.previous_cache_path()).is_err() {
+            let _ = fs::remove_file(self.previous_cache_path());
         }
 
-        Err(AstudiosError::VersionNotFound(format!(
-            "Version '{query}' not found. Use 'astudios list' to see available versions."
-        )))
+        json_file::write_atomic(cache_path, data.as_bytes())
     }
 
     /// Filter releases by channel
@@ -201,6 +393,57 @@ impl AndroidStudioLister {
         items
     }
 
+    /// Filter releases to a single release channel
+    pub fn filter_by_release_channel(
+        &self,
+        releases: Vec<AndroidStudio>,
+        channel: ReleaseChannel,
+    ) -> Vec<AndroidStudio> {
+        releases
+            .into_iter()
+            .filter(|item| item.channel_type() == channel)
+            .collect()
+    }
+
+    /// Filter releases to those built on the given IntelliJ platform branch (e.g. 243)
+    pub fn filter_by_platform_major(
+        &self,
+        releases: Vec<AndroidStudio>,
+        major: u32,
+    ) -> Vec<AndroidStudio> {
+        releases
+            .into_iter()
+            .filter(|item| item.platform_version_parsed().map(|(branch, ..)| branch) == Some(major))
+            .collect()
+    }
+
+    /// Filter releases to those built on exactly the given IntelliJ platform
+    /// build (e.g. "243.22562.218")
+    pub fn filter_by_platform_build(
+        &self,
+        releases: Vec<AndroidStudio>,
+        platform_build: &str,
+    ) -> Vec<AndroidStudio> {
+        let platform_build = platform_build.trim();
+        releases
+            .into_iter()
+            .filter(|item| item.platform_build.trim() == platform_build)
+            .collect()
+    }
+
+    /// Filter releases to a `YYYY.N` major track (e.g. "2024.2")
+    pub fn filter_by_major_track(
+        &self,
+        releases: Vec<AndroidStudio>,
+        track: &str,
+    ) -> Vec<AndroidStudio> {
+        let track = major_track(track);
+        releases
+            .into_iter()
+            .filter(|item| item.major_track() == track)
+            .collect()
+    }
+
     /// Filter releases to only include those available for the current platform
     pub fn filter_by_current_platform(&self, releases: Vec<AndroidStudio>) -> Vec<AndroidStudio> {
         releases
@@ -209,8 +452,111 @@ impl AndroidStudioLister {
             .collect()
     }
 
+    /// Sort releases newest first and keep at most `limit` of them
+    pub fn newest_first(
+        &self,
+        mut releases: Vec<AndroidStudio>,
+        limit: Option<usize>,
+    ) -> Vec<AndroidStudio> {
+        releases.sort_by(|a, b| b.cmp_version(a));
+        if let Some(limit) = limit {
+            releases.truncate(limit);
+        }
+        releases
+    }
+
+    /// Builds of the newest release in each channel, compared by version number
+    pub fn latest_in_each_channel(&self, releases: &[AndroidStudio]) -> HashSet<String> {
+        let mut latest: Vec<&AndroidStudio> = Vec::new();
+        for item in releases {
+            match latest
+                .iter_mut()
+                .find(|newest| newest.channel_type() == item.channel_type())
+            {
+                Some(newest) => {
+                    if item.cmp_version(newest).is_gt() {
+                        *newest = item;
+                    }
+                }
+                None => latest.push(item),
+            }
+        }
+        latest.into_iter().map(|item| item.build.clone()).collect()
+    }
+
     /// Get the current platform name for display purposes
     pub fn get_current_platform_name() -> &'static str {
         "macOS"
     }
 }
+
+/// How closely a release matches a query, most relevant first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchRank {
+    /// The version or build equals the query
+    Exact,
+    /// The version contains the query
+    Version,
+    /// The name contains the query
+    Name,
+    /// The build number contains the query
+    Build,
+    /// The query is a version followed by a channel, e.g. "2024.2 beta"
+    Channel,
+}
+
+/// Candidates listed when a query is ambiguous
+pub const MAX_LISTED_CANDIDATES: usize = 10;
+
+/// Error for a query no release matches
+pub fn version_not_found(query: &str) -> AstudiosError {
+    AstudiosError::VersionNotFound(format!(
+        "Version '{query}' not found. Use 'astudios list' to see available versions."
+    ))
+}
+
+/// Rank every release matching `query`, most relevant and then newest first
+fn rank_matches<'a>(
+    items: &'a [AndroidStudio],
+    query: &str,
+) -> Vec<(MatchRank, &'a AndroidStudio)> {
+    let query = query.trim().to_lowercase();
+    let parts: Vec<&str> = query.split_whitespace().collect();
+
+    let rank = |item: &AndroidStudio| {
+        let version = item.version.to_lowercase();
+        let build = item.build.to_lowercase();
+        if version == query || build == query {
+            Some(MatchRank::Exact)
+        } else if version.contains(&query) {
+            Some(MatchRank::Version)
+        } else if item.name.to_lowercase().contains(&query) {
+            Some(MatchRank::Name)
+        } else if build.contains(&query) {
+            Some(MatchRank::Build)
+        } else if parts.len() >= 2
+            && version.contains(parts[0])
+            && item.channel.to_lowercase() == parts[1]
+        {
+            Some(MatchRank::Channel)
+        } else {
+            None
+        }
+    };
+
+    let mut matches: Vec<_> = items
+        .iter()
+        .filter_map(|item| rank(item).map(|rank| (rank, item)))
+        .collect();
+    matches.sort_by(|(a_rank, a), (b_rank, b)| a_rank.cmp(b_rank).then_with(|| b.cmp_version(a)));
+    matches
+}
+
+/// Time elapsed since a Unix timestamp, saturating at zero for timestamps in the future
+fn fetched_age(fetched_at: u64) -> Duration {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Duration::from_secs(now.saturating_sub(fetched_at))
+}
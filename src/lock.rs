@@ -0,0 +1,49 @@
+use crate::{config::Config, error::AstudiosError};
+use std::{
+    fs::{self, File, TryLockError},
+    path::Path,
+};
+
+/// Advisory lock held for the duration of a command that modifies installations.
+///
+/// The lock is released when this value is dropped, including on error paths.
+#[derive(Debug)]
+pub struct OperationLock {
+    _file: File,
+}
+
+impl OperationLock {
+    /// Acquire the process-wide lock at `~/.astudios/astudios.lock`
+    pub fn acquire(wait: bool) -> Result<Self, AstudiosError> {
+        Self::acquire_at(&Config::lock_path()?, wait)
+    }
+
+    /// Acquire a lock on the given file, blocking until it is free if `wait` is set
+    pub fn acquire_at(path: &Path, wait: bool) -> Result<Self, AstudiosError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+
+        if wait {
+            file.lock()?;
+        } else {
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => {
+                    return Err(AstudiosError::General(format!(
+                        "Another astudios operation is in progress (lock held on {}). Retry later or pass --wait.",
+                        path.display()
+                    )));
+                }
+                Err(TryLockError::Error(e)) => return Err(e.into()),
+            }
+        }
+
+        Ok(Self { _file: file })
+    }
+}
@@ -1,7 +1,7 @@
 mod cli;
 mod commands;
 
-use astudios::error::AstudiosError;
+use astudios::error::{AstudiosError, ExtractionKind};
 use clap::Parser;
 use commands::CommandHandler;
 
@@ -19,8 +19,35 @@ fn main() {
             AstudiosError::Installation(msg) => {
                 eprintln!("Installation Error: {msg}");
             }
-            AstudiosError::Extraction(msg) => {
+            AstudiosError::Extraction(kind, msg) => {
                 eprintln!("Extraction Error: {msg}");
+                match kind {
+                    ExtractionKind::Mount => {
+                        eprintln!("The disk image may be corrupt; delete it and download it again")
+                    }
+                    ExtractionKind::MissingBundle => {
+                        eprintln!(
+                            "The archive does not contain Android Studio; downloading it again will not help"
+                        )
+                    }
+                    ExtractionKind::Copy => {
+                        eprintln!(
+                            "Please check the free space and permissions of the target directory"
+                        )
+                    }
+                    ExtractionKind::Signature => {
+                        eprintln!(
+                            "The downloaded archive is suspect, not the copy; delete it and download it again"
+                        )
+                    }
+                    ExtractionKind::ToolMissing => {
+                        eprintln!("Install the missing tool and try again; the archive was kept")
+                    }
+                    ExtractionKind::Timeout => eprintln!(
+                        "The archive was kept; check the disk, or set ASTUDIOS_MOUNT_TIMEOUT to wait longer"
+                    ),
+                    ExtractionKind::Unsupported => {}
+                }
             }
             AstudiosError::Network(e) => {
                 eprintln!("Network Error: {e}");
@@ -1,4 +1,4 @@
-use crate::error::AstudiosError;
+use crate::{config::Config, error::AstudiosError};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
@@ -48,6 +48,95 @@ pub struct Download {
     pub checksum: String,
 }
 
+impl Download {
+    /// Human-readable platform name inferred from the download link
+    pub fn platform_name(&self) -> &'static str {
+        let link = self.link.to_lowercase();
+        if link.contains("mac_arm") || link.contains("mac-arm") {
+            "macOS (Apple Silicon)"
+        } else if link.contains("mac") {
+            "macOS"
+        } else if link.contains("windows") || link.ends_with(".exe") {
+            "Windows"
+        } else if link.contains("cros") || link.ends_with(".deb") {
+            "ChromeOS"
+        } else if link.contains("linux") {
+            "Linux"
+        } else {
+            "Unknown"
+        }
+    }
+
+    /// Short platform identifier suitable for file and directory names
+    pub fn platform_id(&self) -> &'static str {
+        match self.platform_name() {
+            "macOS (Apple Silicon)" => "mac-arm64",
+            "macOS" => "mac",
+            "Windows" => "windows",
+            "ChromeOS" => "chromeos",
+            "Linux" => "linux",
+            _ => "unknown",
+        }
+    }
+
+    /// Check if this is an incremental patch artifact rather than a full installer
+    /// (e.g., "AI-231.9392.1.2311.11076708-231.9392.1.2311.11330709-patch-mac.jar")
+    pub fn is_patch_artifact(&self) -> bool {
+        let link = self.link.to_lowercase();
+        link.contains("-patch") || link.ends_with(".jar")
+    }
+
+    /// File name to save the download under when neither the server nor the
+    /// link names it, keeping the link's extension (e.g., "android-studio-2023.1.1.28.jar")
+    pub fn default_filename(&self, version: &str) -> String {
+        let path = self.link.split(['?', '#']).next().unwrap_or(&self.link);
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("dmg");
+        format!("android-studio-{version}.{extension}")
+    }
+
+    /// CPU architecture of a macOS download, or `None` for other platforms
+    pub fn mac_arch(&self) -> Option<MacArch> {
+        match self.platform_name() {
+            "macOS (Apple Silicon)" => Some(MacArch::Arm64),
+            "macOS" => Some(MacArch::X86_64),
+            _ => None,
+        }
+    }
+}
+
+/// CPU architecture of a macOS build
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MacArch {
+    /// Apple Silicon
+    Arm64,
+    /// Intel, also runs on Apple Silicon through Rosetta
+    X86_64,
+}
+
+impl MacArch {
+    /// Architecture astudios itself was built for
+    pub fn host() -> Self {
+        if cfg!(target_arch = "aarch64") {
+            MacArch::Arm64
+        } else {
+            MacArch::X86_64
+        }
+    }
+}
+
+impl std::fmt::Display for MacArch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MacArch::Arm64 => write!(f, "Apple Silicon (arm64)"),
+            MacArch::X86_64 => write!(f, "Intel (x86_64, runs under Rosetta on Apple Silicon)"),
+        }
+    }
+}
+
 /// Release channel types
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ReleaseChannel {
@@ -56,49 +145,344 @@ pub enum ReleaseChannel {
     Canary,
     ReleaseCandidate,
     Patch,
+    FeatureDrop,
+}
+
+impl ReleaseChannel {
+    /// Every channel, stable ones first
+    pub const ALL: [ReleaseChannel; 6] = [
+        ReleaseChannel::Release,
+        ReleaseChannel::FeatureDrop,
+        ReleaseChannel::Patch,
+        ReleaseChannel::ReleaseCandidate,
+        ReleaseChannel::Beta,
+        ReleaseChannel::Canary,
+    ];
+
+    /// Label shown next to installed versions
+    pub fn label(self) -> &'static str {
+        match self {
+            ReleaseChannel::Release => "Release",
+            ReleaseChannel::Beta => "Beta",
+            ReleaseChannel::Canary => "Canary",
+            ReleaseChannel::ReleaseCandidate => "RC",
+            ReleaseChannel::Patch => "Patch",
+            ReleaseChannel::FeatureDrop => "Feature Drop",
+        }
+    }
+
+    /// Parse a label produced by [`ReleaseChannel::label`], ignoring case
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|channel| channel.label().eq_ignore_ascii_case(label))
+    }
+
+    /// Name of the launcher symlink kept for this channel when per-channel
+    /// symlinks are enabled; stable releases share the plain "Android Studio.app"
+    pub fn symlink_name(self) -> String {
+        match self {
+            ReleaseChannel::Release | ReleaseChannel::FeatureDrop => {
+                "Android Studio.app".to_string()
+            }
+            channel => format!("Android Studio {}.app", channel.label()),
+        }
+    }
+}
+
+/// Naming scheme for installed app bundles, e.g. "Android Studio {version}.app".
+///
+/// Supports the `{version}`, `{build}`, `{channel}` and `{codename}` placeholders,
+/// and must contain `{version}` or `{build}` so different releases never share a name.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct BundleNameTemplate(String);
+
+impl BundleNameTemplate {
+    /// Placeholders a template may use
+    pub const PLACEHOLDERS: [&'static str; 4] = ["{version}", "{build}", "{channel}", "{codename}"];
+
+    /// Bundle name for `release`, with spaces left by an empty placeholder collapsed
+    pub fn render(&self, release: &AndroidStudio) -> String {
+        let name = self
+            .0
+            .replace("{version}", &release.version)
+            .replace("{build}", &release.build)
+            .replace("{channel}", release.channel_type().label())
+            .replace("{codename}", release.codename().unwrap_or_default());
+        let stem = name.strip_suffix(".app").unwrap_or(&name);
+        format!(
+            "{}.app",
+            stem.split_whitespace().collect::<Vec<_>>().join(" ")
+        )
+    }
+}
+
+impl Default for BundleNameTemplate {
+    fn default() -> Self {
+        Self("Android Studio {version}.app".to_string())
+    }
+}
+
+impl std::str::FromStr for BundleNameTemplate {
+    type Err = AstudiosError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| {
+            AstudiosError::Config(format!("Invalid bundle name template '{value}': {reason}"))
+        };
+
+        if !value.ends_with(".app") {
+            return Err(invalid("it must end with .app"));
+        }
+        if value.contains('/') {
+            return Err(invalid("it must not contain '/'"));
+        }
+        let mut rest = value;
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .map_or(rest.len(), |end| start + end + 1);
+            let placeholder = &rest[start..end];
+            if !Self::PLACEHOLDERS.contains(&placeholder) {
+                return Err(invalid(&format!(
+                    "unknown placeholder {placeholder}; use {}",
+                    Self::PLACEHOLDERS.join(", ")
+                )));
+            }
+            rest = &rest[end..];
+        }
+        if !value.contains("{version}") && !value.contains("{build}") {
+            return Err(invalid(
+                "it must contain {version} or {build} so releases get different names",
+            ));
+        }
+
+        Ok(Self(value.to_string()))
+    }
+}
+
+impl TryFrom<String> for BundleNameTemplate {
+    type Error = AstudiosError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<BundleNameTemplate> for String {
+    fn from(template: BundleNameTemplate) -> Self {
+        template.0
+    }
+}
+
+impl std::fmt::Display for BundleNameTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
 impl AndroidStudio {
-    /// Check if this is a stable release
+    /// Check if this is a stable release (including feature drops)
     pub fn is_release(&self) -> bool {
-        self.channel == "Release"
+        matches!(
+            self.channel_type(),
+            ReleaseChannel::Release | ReleaseChannel::FeatureDrop
+        )
+    }
+
+    /// Codename from the release name, e.g. "Meerkat" for
+    /// "Android Studio Meerkat Feature Drop | 2024.3.2"
+    pub fn codename(&self) -> Option<&str> {
+        let title = self.name.split('|').next()?;
+        title
+            .trim()
+            .strip_prefix("Android Studio")?
+            .split_whitespace()
+            .next()
+            .filter(|word| word.chars().all(char::is_alphabetic))
     }
 
     /// Check if this is a beta release
     pub fn is_beta(&self) -> bool {
-        self.channel == "Beta"
+        self.channel_type() == ReleaseChannel::Beta
     }
 
     /// Check if this is a canary release
     pub fn is_canary(&self) -> bool {
-        self.channel == "Canary"
+        self.channel_type() == ReleaseChannel::Canary
     }
 
     /// Check if this is a release candidate
     pub fn is_rc(&self) -> bool {
-        self.channel == "RC"
+        self.channel_type() == ReleaseChannel::ReleaseCandidate
     }
 
     /// Check if this is a patch release
     pub fn is_patch(&self) -> bool {
-        self.channel == "Patch"
+        self.channel_type() == ReleaseChannel::Patch
     }
 
-    /// Get the release channel as an enum
+    /// Check if the feed explicitly labels this a stable release.
+    ///
+    /// Stricter than [`AndroidStudio::is_release`], which also accepts labels it
+    /// does not recognize, so an unexpected preview label is never taken for stable.
+    pub fn is_stable(&self) -> bool {
+        matches!(
+            self.normalized_channel().as_str(),
+            "release" | "stable" | "feature drop"
+        )
+    }
+
+    /// Get the release channel as an enum, ignoring case and spacing of the feed label
     pub fn channel_type(&self) -> ReleaseChannel {
-        match self.channel.as_str() {
-            "Release" => ReleaseChannel::Release,
-            "Beta" => ReleaseChannel::Beta,
-            "Canary" => ReleaseChannel::Canary,
-            "RC" => ReleaseChannel::ReleaseCandidate,
-            "Patch" => ReleaseChannel::Patch,
+        match self.normalized_channel().as_str() {
+            "beta" => ReleaseChannel::Beta,
+            "canary" => ReleaseChannel::Canary,
+            "rc" | "release candidate" => ReleaseChannel::ReleaseCandidate,
+            "patch" => ReleaseChannel::Patch,
+            "feature drop" => ReleaseChannel::FeatureDrop,
+            // Feature drops are usually published on the stable channel
+            _ if self.name.contains("Feature Drop") => ReleaseChannel::FeatureDrop,
             _ => ReleaseChannel::Release, // Default fallback
         }
     }
 
+    /// The feed's channel label, lowercased with runs of spaces, dashes and underscores collapsed
+    fn normalized_channel(&self) -> String {
+        self.channel
+            .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    }
+
     /// Get macOS download URL
     pub fn get_macos_download(&self) -> Option<&Download> {
-        self.downloads.iter().find(|d| d.link.contains("mac"))
+        self.get_macos_download_for(MacArch::host())
+    }
+
+    /// Get the macOS download for `arch`, or any macOS download if that architecture is not offered
+    pub fn get_macos_download_for(&self, arch: MacArch) -> Option<&Download> {
+        let downloads = self.macos_downloads();
+        downloads
+            .iter()
+            .find(|d| d.mac_arch() == Some(arch))
+            .or_else(|| downloads.first())
+            .copied()
+    }
+
+    /// Full (non-patch) macOS downloads, in feed order
+    pub fn macos_downloads(&self) -> Vec<&Download> {
+        self.downloads
+            .iter()
+            .filter(|d| d.link.contains("mac") && !d.is_patch_artifact())
+            .collect()
+    }
+
+    /// Architectures offered by the macOS downloads, in feed order
+    pub fn macos_arches(&self) -> Vec<MacArch> {
+        let mut arches = Vec::new();
+        for arch in self.macos_downloads().iter().filter_map(|d| d.mac_arch()) {
+            if !arches.contains(&arch) {
+                arches.push(arch);
+            }
+        }
+        arches
+    }
+
+    /// Get the download for the current platform, explaining which platforms are offered if none matches
+    pub fn require_platform_download(&self) -> Result<&Download, AstudiosError> {
+        self.require_platform_download_for(MacArch::host())
+    }
+
+    /// Like [`Self::require_platform_download`], preferring the given macOS architecture
+    pub fn require_platform_download_for(&self, arch: MacArch) -> Result<&Download, AstudiosError> {
+        if let Some(download) = self.get_macos_download_for(arch) {
+            return Ok(download);
+        }
+
+        let platforms = self.available_platforms();
+        if platforms.is_empty() {
+            return Err(AstudiosError::Platform(format!(
+                "{} ({}) has no downloadable archives in the release feed. Use 'astudios list' to pick another version.",
+                self.name, self.version
+            )));
+        }
+
+        Err(AstudiosError::Platform(format!(
+            "{} ({}) is not available for macOS. It is only offered for: {}. Use 'astudios list --all-platforms' to compare, or pick another version.",
+            self.name,
+            self.version,
+            platforms.join(", ")
+        )))
+    }
+
+    /// Distinct platforms this release offers full downloads for, in feed order
+    pub fn available_platforms(&self) -> Vec<&'static str> {
+        let mut platforms = Vec::new();
+        for download in self.downloads.iter().filter(|d| !d.is_patch_artifact()) {
+            let platform = download.platform_name();
+            if !platforms.contains(&platform) {
+                platforms.push(platform);
+            }
+        }
+        platforms
+    }
+
+    /// Get the macOS incremental patch artifact, if the feed provides one
+    pub fn get_patch_download(&self) -> Option<&Download> {
+        self.downloads
+            .iter()
+            .find(|d| d.link.contains("mac") && d.is_patch_artifact())
+    }
+
+    /// Build prefix shared by a release and the patches built on top of it
+    /// (e.g., "AI-231.9392.1.2311" for "AI-231.9392.1.2311.11076708")
+    pub fn build_train(&self) -> &str {
+        self.build
+            .rsplit_once('.')
+            .map_or(self.build.as_str(), |(train, _)| train)
+    }
+
+    /// Compare two releases by version number, then by build number, numerically
+    /// segment by segment (so "2024.10" sorts after "2024.9")
+    pub fn cmp_version(&self, other: &Self) -> std::cmp::Ordering {
+        fn numeric_segments(value: &str) -> Vec<u64> {
+            value
+                .split(|c: char| !c.is_ascii_digit())
+                .filter(|segment| !segment.is_empty())
+                .map(|segment| segment.parse().unwrap_or(u64::MAX))
+                .collect()
+        }
+
+        numeric_segments(&self.version)
+            .cmp(&numeric_segments(&other.version))
+            .then_with(|| numeric_segments(&self.build).cmp(&numeric_segments(&other.build)))
+    }
+
+    /// `YYYY.N` major track of this release, e.g. "2024.2" for "2024.2.1.11"
+    pub fn major_track(&self) -> String {
+        major_track(&self.version)
+    }
+
+    /// IntelliJ platform build as a comparable (branch, build, fix) tuple,
+    /// e.g. (243, 22562, 218) for "243.22562.218"; missing parts count as 0
+    pub fn platform_version_parsed(&self) -> Option<(u32, u32, u32)> {
+        let mut parts = self.platform_build.trim().split('.');
+        let mut next = || parts.next().map_or(Ok(0), str::parse::<u32>);
+        Some((next().ok()?, next().ok()?, next().ok()?))
+    }
+
+    /// Check whether an installed version is the base build this patch release applies to
+    pub fn is_patch_of(&self, installed: &AndroidStudioVersion) -> bool {
+        self.is_patch()
+            && installed.build_version != self.build
+            && installed
+                .build_version
+                .strip_prefix(self.build_train())
+                .is_some_and(|rest| rest.starts_with('.'))
     }
 
     /// Get download URL for macOS
@@ -106,6 +490,24 @@ impl AndroidStudio {
         self.get_macos_download()
     }
 
+    /// Page describing this release's changes.
+    ///
+    /// The feed has no per-build notes link, so this is the canonical notes
+    /// page for the release's channel.
+    pub fn release_notes_url(&self) -> &'static str {
+        match self.channel_type() {
+            ReleaseChannel::Beta | ReleaseChannel::Canary => Config::PREVIEW_RELEASE_NOTES_URL,
+            _ => Config::RELEASE_NOTES_URL,
+        }
+    }
+
+    /// Whether this release came out at least `months` months before `today`
+    /// ("YYYY-MM-DD"). A threshold of 0 disables the check, and releases whose
+    /// date does not parse are never outdated.
+    pub fn is_outdated(&self, months: u32, today: &str) -> bool {
+        months > 0 && months_between(&self.date, today).is_some_and(|age| age >= i64::from(months))
+    }
+
     /// Get display name with channel indicator
     pub fn display_name(&self) -> String {
         let channel_indicator = match self.channel_type() {
@@ -114,6 +516,8 @@ impl AndroidStudio {
             ReleaseChannel::Canary => " (Canary)",
             ReleaseChannel::ReleaseCandidate => " (RC)",
             ReleaseChannel::Patch => " (Patch)",
+            ReleaseChannel::FeatureDrop if self.name.contains("Feature Drop") => "",
+            ReleaseChannel::FeatureDrop => " (Feature Drop)",
         };
         format!("{}{}", self.name, channel_indicator)
     }
@@ -152,6 +556,55 @@ impl AndroidStudioVersion {
         }
     }
 
+    /// Parse a build string such as "AI-251.26094.121.2513.14007798", without
+    /// a bundle or feed entry to read the rest from.
+    ///
+    /// A bare build number is taken to be Android Studio's ("AI"). The short
+    /// version is derived from the IntelliJ branch (251 is "2025.1") and left
+    /// empty when the branch does not have that form; the product name is only
+    /// known for "AI".
+    pub fn from_build_string(build: &str) -> Result<Self, AstudiosError> {
+        let build = build.trim();
+        let invalid =
+            |reason: &str| AstudiosError::Parse(format!("Invalid build '{build}': {reason}"));
+
+        let (product_code, build_number) = build.split_once('-').unwrap_or(("AI", build));
+        if product_code.is_empty() || !product_code.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(invalid(
+                "the product code must be uppercase letters, e.g. AI",
+            ));
+        }
+        let components: Vec<&str> = build_number.split('.').collect();
+        if components.len() < 2
+            || components
+                .iter()
+                .any(|part| part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()))
+        {
+            return Err(invalid(
+                "expected a dotted build number such as 251.26094.121.2513.14007798",
+            ));
+        }
+
+        let branch = components[0];
+        let short_version = match branch.as_bytes() {
+            [_, _, minor] => format!("20{}.{}", &branch[..2], *minor as char),
+            _ => String::new(),
+        };
+        let product_name = if product_code == "AI" {
+            "Android Studio".to_string()
+        } else {
+            String::new()
+        };
+
+        Ok(Self::new(
+            short_version,
+            format!("{product_code}-{build_number}"),
+            product_code.to_string(),
+            build_number.to_string(),
+            product_name,
+        ))
+    }
+
     /// Get a display-friendly version string
     pub fn display_version(&self) -> String {
         format!("{} ({})", self.short_version, self.build_version)
@@ -162,6 +615,11 @@ impl AndroidStudioVersion {
         self.build_version.clone()
     }
 
+    /// `YYYY.N` major track of this version, e.g. "2024.2" for "2024.2.1.12"
+    pub fn major_track(&self) -> String {
+        major_track(&self.short_version)
+    }
+
     /// Check if this is a stable release (not beta, canary, etc.)
     pub fn is_stable(&self) -> bool {
         !self.build_version.contains("Beta")
@@ -184,6 +642,8 @@ pub struct InstalledAndroidStudio {
     pub path: PathBuf,
     /// Parsed version information from metadata
     pub version: AndroidStudioVersion,
+    /// Channel label set with `set-channel`, shown instead of the detected one
+    pub channel_override: Option<String>,
 }
 
 impl InstalledAndroidStudio {
@@ -201,7 +661,11 @@ impl InstalledAndroidStudio {
 
         // Try to parse version information
         match Self::parse_version_info(&path) {
-            Ok(version) => Ok(Some(Self { path, version })),
+            Ok(version) => Ok(Some(Self {
+                path,
+                version,
+                channel_override: None,
+            })),
             Err(_) => Ok(None), // Not a valid Android Studio installation
         }
     }
@@ -214,10 +678,14 @@ impl InstalledAndroidStudio {
         let info_plist_path = contents_path.join("Info.plist");
         let (short_version, build_version) = Self::parse_info_plist(&info_plist_path)?;
 
-        // Parse product-info.json for additional details
+        // Parse product-info.json for additional details; older bundles and
+        // non-standard layouts lack it, but the build in Info.plist suffices
         let product_info_path = contents_path.join("Resources").join("product-info.json");
-        let (product_name, product_code, build_number) =
-            Self::parse_product_info(&product_info_path)?;
+        let (product_name, product_code, build_number) = if product_info_path.exists() {
+            Self::parse_product_info(&product_info_path)?
+        } else {
+            Self::product_info_from_build(&build_version)
+        };
 
         Ok(AndroidStudioVersion::new(
             short_version,
@@ -228,8 +696,8 @@ impl InstalledAndroidStudio {
         ))
     }
 
-    /// Parse Info.plist file for version information
-    fn parse_info_plist(plist_path: &Path) -> Result<(String, String), AstudiosError> {
+    /// Read the top-level dictionary of an Info.plist file
+    fn read_info_plist(plist_path: &Path) -> Result<plist::Dictionary, AstudiosError> {
         use plist::Value;
         use std::fs::File;
 
@@ -239,14 +707,32 @@ impl InstalledAndroidStudio {
         let plist: Value = plist::from_reader(file)
             .map_err(|e| AstudiosError::General(format!("Failed to parse Info.plist: {e}")))?;
 
-        let dict = plist
-            .as_dictionary()
-            .ok_or_else(|| AstudiosError::General("Info.plist is not a dictionary".to_string()))?;
+        plist
+            .into_dictionary()
+            .ok_or_else(|| AstudiosError::General("Info.plist is not a dictionary".to_string()))
+    }
+
+    /// Read the launcher executable name (CFBundleExecutable) of an app bundle
+    pub fn bundle_executable(app_path: &Path) -> Result<String, AstudiosError> {
+        let dict = Self::read_info_plist(&app_path.join("Contents").join("Info.plist"))?;
+
+        dict.get("CFBundleExecutable")
+            .and_then(|v| v.as_string())
+            .filter(|name| !name.trim().is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| AstudiosError::General("CFBundleExecutable not found".to_string()))
+    }
+
+    /// Parse Info.plist file for version information
+    fn parse_info_plist(plist_path: &Path) -> Result<(String, String), AstudiosError> {
+        let dict = Self::read_info_plist(plist_path)?;
 
-        // Extract CFBundleShortVersionString
+        // Extract CFBundleShortVersionString; a blank value would break matching and sorting
         let short_version = dict
             .get("CFBundleShortVersionString")
             .and_then(|v| v.as_string())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
             .ok_or_else(|| {
                 AstudiosError::General("CFBundleShortVersionString not found".to_string())
             })?
@@ -256,6 +742,8 @@ impl InstalledAndroidStudio {
         let build_version = dict
             .get("CFBundleVersion")
             .and_then(|v| v.as_string())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
             .ok_or_else(|| AstudiosError::General("CFBundleVersion not found".to_string()))?
             .to_string();
 
@@ -284,9 +772,16 @@ impl InstalledAndroidStudio {
             AstudiosError::General(format!("Failed to read product-info.json: {e}"))
         })?;
 
-        let json: serde_json::Value = serde_json::from_str(&content).map_err(|e| {
-            AstudiosError::General(format!("Failed to parse product-info.json: {e}"))
-        })?;
+        // Some bundles ship the file with a UTF-8 BOM or trailing data after the
+        // object, so strip the BOM and only read the first JSON value
+        let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+        let json: serde_json::Value = serde_json::Deserializer::from_str(content)
+            .into_iter()
+            .next()
+            .ok_or_else(|| AstudiosError::General("product-info.json is empty".to_string()))?
+            .map_err(|e| {
+                AstudiosError::General(format!("Failed to parse product-info.json: {e}"))
+            })?;
 
         let product_name = json
             .get("name")
@@ -306,12 +801,30 @@ impl InstalledAndroidStudio {
             .and_then(|v| v.as_str())
             .unwrap_or(version);
 
-        // Extract product code from version (e.g., "AI" from "AI-251.26094.121.2513.14007798")
-        let product_code = version.split('-').next().unwrap_or("AI").to_string();
+        // Bundles name the product in productCode; without it, take the prefix
+        // of the version (e.g., "AI" from "AI-251.26094.121.2513.14007798")
+        let product_code = json
+            .get("productCode")
+            .and_then(|v| v.as_str())
+            .unwrap_or_else(|| version.split('-').next().unwrap_or("AI"))
+            .to_string();
 
         Ok((product_name, product_code, build_number.to_string()))
     }
 
+    /// Derive the product details from a CFBundleVersion such as "AI-251.26094.121.2513.14007798"
+    fn product_info_from_build(build_version: &str) -> (String, String, String) {
+        let (product_code, build_number) = build_version
+            .split_once('-')
+            .unwrap_or(("AI", build_version));
+
+        (
+            "Android Studio".to_string(),
+            product_code.to_string(),
+            build_number.to_string(),
+        )
+    }
+
     /// Get the display name for this installation
     pub fn display_name(&self) -> String {
         format!(
@@ -351,7 +864,7 @@ impl InstalledAndroidStudio {
     /// Get enhanced display name with detailed version information
     pub fn enhanced_display_name(&self) -> String {
         let detailed_version = self.extract_detailed_version();
-        let channel_info = self.detect_channel_from_name();
+        let channel_info = self.channel();
 
         if channel_info.is_empty() {
             format!("{} {}", self.version.product_name, detailed_version)
@@ -363,6 +876,19 @@ impl InstalledAndroidStudio {
         }
     }
 
+    /// Release channel of this installation, preferring a label set with
+    /// `set-channel` over the one detected from the app name
+    pub fn channel(&self) -> String {
+        self.channel_override
+            .clone()
+            .unwrap_or_else(|| self.detect_channel_from_name())
+    }
+
+    /// [`channel`](Self::channel) as a [`ReleaseChannel`], treating unknown labels as releases
+    pub fn release_channel(&self) -> ReleaseChannel {
+        ReleaseChannel::from_label(&self.channel()).unwrap_or(ReleaseChannel::Release)
+    }
+
     /// Detect release channel information from app name
     fn detect_channel_from_name(&self) -> String {
         let app_name = self
@@ -395,6 +921,197 @@ impl InstalledAndroidStudio {
     pub fn is_valid(&self) -> bool {
         self.path.exists() && self.path.join("Contents").exists()
     }
+
+    /// Total size of the app bundle on disk, in bytes
+    pub fn disk_size(&self) -> u64 {
+        directory_size(&self.path)
+    }
+
+    /// When the bundle was installed, taken from its modification time
+    pub fn installed_at(&self) -> std::time::SystemTime {
+        std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .unwrap_or(std::time::UNIX_EPOCH)
+    }
+
+    /// CPU architectures of the bundle's launcher, e.g. ["arm64"], or
+    /// ["x86_64", "arm64"] for a universal binary.
+    ///
+    /// Asks `lipo -archs`, then `file`; `None` when neither is available or
+    /// can tell.
+    pub fn architectures(&self) -> Option<Vec<String>> {
+        let executable = self
+            .path
+            .join("Contents")
+            .join("MacOS")
+            .join(Self::bundle_executable(&self.path).ok()?);
+
+        [("lipo", "-archs"), ("file", "-b")]
+            .into_iter()
+            .filter_map(|(tool, flag)| {
+                let output = std::process::Command::new(tool)
+                    .arg(flag)
+                    .arg(&executable)
+                    .output()
+                    .ok()?;
+                output
+                    .status
+                    .success()
+                    .then(|| parse_architectures(&String::from_utf8_lossy(&output.stdout)))
+            })
+            .find(|architectures| !architectures.is_empty())
+    }
+}
+
+/// Architectures named in the output of `lipo -archs` ("x86_64 arm64") or
+/// `file` ("Mach-O universal binary with 2 architectures: [x86_64:...] [arm64]"),
+/// in order of first mention
+pub fn parse_architectures(output: &str) -> Vec<String> {
+    const KNOWN: [&str; 4] = ["arm64", "arm64e", "x86_64", "i386"];
+
+    let mut architectures: Vec<String> = Vec::new();
+    for word in output.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_')) {
+        if KNOWN.contains(&word) && !architectures.iter().any(|arch| arch == word) {
+            architectures.push(word.to_string());
+        }
+    }
+    architectures
+}
+
+/// Whether a bundle built for `architectures` only runs through Rosetta on
+/// this Mac: it has no arm64 slice and astudios runs on Apple Silicon
+pub fn needs_rosetta(architectures: &[String]) -> bool {
+    MacArch::host() == MacArch::Arm64
+        && !architectures.is_empty()
+        && !architectures.iter().any(|arch| arch.starts_with("arm64"))
+}
+
+/// The `YYYY.N` major track of a version string such as "2024.2.1.12" or
+/// "2023.3.1 Canary 8"; strings without a numeric year and feature number are
+/// returned as their first word
+pub fn major_track(version: &str) -> String {
+    let token = version.split_whitespace().next().unwrap_or_default();
+    let mut parts = token.split('.');
+    match (parts.next(), parts.next()) {
+        (Some(year), Some(number))
+            if [year, number]
+                .iter()
+                .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit())) =>
+        {
+            format!("{year}.{number}")
+        }
+        _ => token.to_string(),
+    }
+}
+
+/// Today's date in UTC as "YYYY-MM-DD", the format of release dates
+pub fn today() -> String {
+    let days = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |age| age.as_secs() / 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Whole calendar months from `earlier` to `later`, each either "YYYY-MM-DD"
+/// or "November 28, 2024"; `None` if either date does not parse
+pub fn months_between(earlier: &str, later: &str) -> Option<i64> {
+    let (from_year, from_month, from_day) = parse_date(earlier)?;
+    let (to_year, to_month, to_day) = parse_date(later)?;
+    let months = (to_year - from_year) * 12 + (to_month - from_month);
+    Some(if to_day < from_day {
+        months - 1
+    } else {
+        months
+    })
+}
+
+/// Parse a "YYYY-MM-DD" or "Month D, YYYY" date into (year, month, day)
+fn parse_date(date: &str) -> Option<(i64, i64, i64)> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+
+    let date = date.trim();
+    let (year, month, day) = if let Some((month, rest)) = date.split_once(' ') {
+        let (day, year) = rest.split_once(',')?;
+        let month = MONTHS
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(month))?
+            + 1;
+        (
+            year.trim().parse().ok()?,
+            month as i64,
+            day.trim().parse().ok()?,
+        )
+    } else {
+        let mut parts = date.splitn(3, '-').map(str::parse::<i64>);
+        (
+            parts.next()?.ok()?,
+            parts.next()?.ok()?,
+            parts.next()?.ok()?,
+        )
+    };
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then_some((year, month, day))
+}
+
+/// Civil (year, month, day) of a count of days since 1970-01-01, using
+/// Howard Hinnant's algorithm
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+    (year, month as u32, day as u32)
+}
+
+/// Numeric sort key of a major track, so "2024.10" sorts after "2024.9"
+pub fn major_track_key(track: &str) -> Vec<u32> {
+    track
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// Recursively sum file sizes under `path` without following symlinks
+pub fn directory_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| directory_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
 }
 
 impl PartialOrd for InstalledAndroidStudio {
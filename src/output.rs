@@ -0,0 +1,76 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    process::Stdio,
+    sync::OnceLock,
+};
+
+/// Process-wide stream for human-readable output (set by `install --json-lines`)
+static HUMAN_OUTPUT: OnceLock<HumanOutput> = OnceLock::new();
+
+/// Stream that human-readable messages are written to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HumanOutput {
+    #[default]
+    Stdout,
+    /// Used when stdout carries machine-readable records, so messages cannot
+    /// interleave with them
+    Stderr,
+}
+
+impl HumanOutput {
+    /// Choose the stream for the rest of the process; later calls are ignored
+    pub fn set_override(output: HumanOutput) {
+        let _ = io::stdout().flush();
+        let _ = HUMAN_OUTPUT.set(output);
+    }
+
+    /// The stream in effect for this process
+    pub fn current() -> HumanOutput {
+        HUMAN_OUTPUT.get().copied().unwrap_or_default()
+    }
+
+    /// Where child processes whose output is meant for the user should write
+    pub fn child_stdout() -> Stdio {
+        match Self::current() {
+            HumanOutput::Stdout => Stdio::inherit(),
+            HumanOutput::Stderr => io::stderr().into(),
+        }
+    }
+
+    /// Flush the stream, e.g. after a prompt printed without a newline
+    pub fn flush() -> io::Result<()> {
+        match Self::current() {
+            HumanOutput::Stdout => io::stdout().flush(),
+            HumanOutput::Stderr => io::stderr().flush(),
+        }
+    }
+}
+
+/// Write formatted text to the human-readable output stream; used by [`say!`]
+/// and [`sayln!`]
+pub fn write_fmt(args: fmt::Arguments) {
+    match HumanOutput::current() {
+        HumanOutput::Stdout => print!("{args}"),
+        HumanOutput::Stderr => eprint!("{args}"),
+    }
+}
+
+/// Like `print!`, but to the human-readable output stream
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        $crate::output::write_fmt(format_args!($($arg)*))
+    };
+}
+
+/// Like `println!`, but to the human-readable output stream
+#[macro_export]
+macro_rules! sayln {
+    () => {
+        $crate::output::write_fmt(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write_fmt(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
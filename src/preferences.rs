@@ -0,0 +1,53 @@
+use crate::{
+    config::Config,
+    error::AstudiosError,
+    json_file::JsonFile,
+    model::{BundleNameTemplate, MacArch},
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Choices remembered between runs, stored in `~/.astudios/preferences.json`
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Preferences {
+    /// macOS architecture picked when a release offers more than one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mac_arch: Option<MacArch>,
+    /// Bundle treated as active when the "Android Studio.app" symlink is absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_studio: Option<PathBuf>,
+    /// Bundles outside the applications directory that were selected with `use --path`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_studios: Vec<PathBuf>,
+    /// Keep one launcher symlink per release channel (e.g. "Android Studio Canary.app")
+    /// instead of a single "Android Studio.app"
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub per_channel_symlinks: bool,
+    /// Naming scheme for installed bundles; `None` uses "Android Studio {version}.app"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_name_template: Option<BundleNameTemplate>,
+    /// Downloaded archives kept per major track after each install; `None` keeps all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_archives: Option<usize>,
+    /// Refuse to install builds that would run under Rosetta, as `install --strict-arch` does
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict_arch: bool,
+    /// Answer yes to confirmation prompts of destructive commands, as `--yes` does
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub assume_yes: bool,
+}
+
+impl JsonFile for Preferences {
+    fn path() -> Result<PathBuf, AstudiosError> {
+        Ok(Config::config_dir()?.join("preferences.json"))
+    }
+}
+
+impl Preferences {
+    /// Remember an externally managed bundle so `installed` keeps listing it
+    pub fn add_external_studio(&mut self, path: PathBuf) {
+        if !self.external_studios.contains(&path) {
+            self.external_studios.push(path);
+        }
+    }
+}
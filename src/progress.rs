@@ -1,5 +1,62 @@
 use indicatif::{ProgressBar, ProgressStyle};
-use std::time::Duration;
+use std::{sync::Once, time::Duration};
+
+/// Return to the start of the line, erase it and show the cursor again
+const TERMINAL_RESET: &[u8] = b"\r\x1b[2K\x1b[?25h";
+
+/// Leave the terminal clean if the process is interrupted or panics while a
+/// progress bar is drawn.
+///
+/// On SIGINT or SIGTERM the half-drawn line is cleared and the cursor shown,
+/// then the signal is raised again with its default action so the exit status
+/// is unchanged. Does nothing when stderr is not a terminal; safe to call repeatedly.
+pub fn restore_terminal_on_exit() {
+    static INSTALLED: Once = Once::new();
+
+    INSTALLED.call_once(|| {
+        // SAFETY: isatty only inspects the file descriptor
+        if unsafe { libc::isatty(libc::STDERR_FILENO) } != 1 {
+            return;
+        }
+
+        for signal in [libc::SIGINT, libc::SIGTERM] {
+            let handler = reset_terminal_and_reraise as extern "C" fn(libc::c_int);
+            // SAFETY: the handler only makes async-signal-safe calls
+            let previous = unsafe { libc::signal(signal, handler as libc::sighandler_t) };
+            // A signal the parent chose to ignore stays ignored
+            if previous == libc::SIG_IGN {
+                unsafe { libc::signal(signal, libc::SIG_IGN) };
+            }
+        }
+
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            reset_terminal();
+            previous_hook(info);
+        }));
+    });
+}
+
+/// Write [`TERMINAL_RESET`] to stderr without locking or allocating
+fn reset_terminal() {
+    // SAFETY: write is async-signal-safe and the buffer is a static byte string
+    unsafe {
+        libc::write(
+            libc::STDERR_FILENO,
+            TERMINAL_RESET.as_ptr().cast(),
+            TERMINAL_RESET.len(),
+        );
+    }
+}
+
+extern "C" fn reset_terminal_and_reraise(signal: libc::c_int) {
+    reset_terminal();
+    // SAFETY: signal and raise are async-signal-safe
+    unsafe {
+        libc::signal(signal, libc::SIG_DFL);
+        libc::raise(signal);
+    }
+}
 
 /// Unified progress reporting system
 #[derive(Clone)]
@@ -23,6 +80,7 @@ impl ProgressReporter {
             return ProgressBar::hidden();
         }
 
+        restore_terminal_on_exit();
         let pb = ProgressBar::new_spinner();
         pb.set_style(
             ProgressStyle::default_spinner()
@@ -43,6 +101,7 @@ impl ProgressReporter {
             return ProgressBar::hidden();
         }
 
+        restore_terminal_on_exit();
         let pb = ProgressBar::new(total);
         pb.set_style(
             ProgressStyle::default_bar()
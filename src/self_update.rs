@@ -0,0 +1,137 @@
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Result of the last check, cached so most invocations make no request
+#[derive(Debug, Deserialize, Serialize)]
+struct CachedCheck {
+    /// Seconds since the Unix epoch at which the check ran
+    checked_at: u64,
+    /// Latest published version, or `None` if the check failed
+    latest: Option<String>,
+}
+
+/// Opt-in check for newer releases of astudios itself.
+///
+/// Every failure is swallowed: this check must never get in the way of the
+/// command that triggered it.
+pub struct SelfUpdateCheck {
+    url: String,
+    cache_path: PathBuf,
+    max_age: Duration,
+}
+
+impl SelfUpdateCheck {
+    /// The check configured through `ASTUDIOS_CHECK_SELF_UPDATE` and
+    /// `ASTUDIOS_SELF_UPDATE_URL`, or `None` when it is not enabled
+    pub fn from_env() -> Option<Self> {
+        if !Config::env_flag("ASTUDIOS_CHECK_SELF_UPDATE") {
+            return None;
+        }
+
+        let url = std::env::var("ASTUDIOS_SELF_UPDATE_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| Config::SELF_UPDATE_URL.to_string());
+        let cache_path = Config::cache_dir().ok()?.join("self-update.json");
+        Some(Self::new(
+            url,
+            cache_path,
+            Duration::from_secs(Config::SELF_UPDATE_CHECK_INTERVAL_SECS),
+        ))
+    }
+
+    /// A check against `url`, caching its result at `cache_path` for `max_age`
+    pub fn new(url: String, cache_path: PathBuf, max_age: Duration) -> Self {
+        Self {
+            url,
+            cache_path,
+            max_age,
+        }
+    }
+
+    /// The latest published version if it is newer than `current`
+    pub fn newer_version(&self, current: &str) -> Option<String> {
+        let latest = match self.cached() {
+            Some(cached) => cached.latest,
+            None => self.fetch_and_cache(),
+        }?;
+        is_newer(&latest, current).then_some(latest)
+    }
+
+    /// The cached result, if it is recent enough
+    fn cached(&self) -> Option<CachedCheck> {
+        let data = fs::read_to_string(&self.cache_path).ok()?;
+        let cached: CachedCheck = serde_json::from_str(&data).ok()?;
+        let age = now_secs().saturating_sub(cached.checked_at);
+        (age < self.max_age.as_secs()).then_some(cached)
+    }
+
+    /// Query the releases URL and remember the answer, including a failure
+    fn fetch_and_cache(&self) -> Option<String> {
+        let latest = self.fetch();
+        let cached = CachedCheck {
+            checked_at: now_secs(),
+            latest: latest.clone(),
+        };
+        if let (Some(parent), Ok(data)) = (self.cache_path.parent(), serde_json::to_string(&cached))
+        {
+            let _ = fs::create_dir_all(parent).and_then(|()| fs::write(&self.cache_path, data));
+        }
+        latest
+    }
+
+    /// Ask the releases URL for the latest version
+    fn fetch(&self) -> Option<String> {
+        let client = Config::http_client_builder(Config::SELF_UPDATE_TIMEOUT_SECS)
+            .build()
+            .ok()?;
+        let body = client
+            .get(&self.url)
+            .send()
+            .ok()?
+            .error_for_status()
+            .ok()?
+            .text()
+            .ok()?;
+        parse_latest_version(&body)
+    }
+}
+
+/// Extract the latest version from a crates.io crate or GitHub release response
+pub fn parse_latest_version(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let version = value
+        .pointer("/crate/max_stable_version")
+        .or_else(|| value.pointer("/crate/max_version"))
+        .or_else(|| value.get("tag_name"))?
+        .as_str()?;
+    let version = version.trim().trim_start_matches('v');
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// Compare dotted numeric versions, ignoring any pre-release or build suffix
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    let parse = |version: &str| -> Vec<u64> {
+        version
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+    parse(candidate) > parse(current)
+}
+
+/// Current time in seconds since the Unix epoch
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
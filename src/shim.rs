@@ -0,0 +1,78 @@
+use crate::error::AstudiosError;
+use std::{
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+/// File name of the command-line shim
+pub const SHIM_NAME: &str = "studio";
+
+/// Marker line identifying a shim written by astudios
+const SHIM_MARKER: &str = "# Generated by astudios";
+
+/// A `studio` command that launches whichever Android Studio is selected.
+///
+/// The shim calls back into astudios, which resolves the active installation
+/// at run time, so switching versions with `use` needs no reinstall.
+pub struct Shim {
+    path: PathBuf,
+}
+
+impl Shim {
+    /// Shim located in `bin_dir`
+    pub fn in_dir(bin_dir: &Path) -> Self {
+        Self {
+            path: bin_dir.join(SHIM_NAME),
+        }
+    }
+
+    /// Path of the shim file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check whether the shim file exists and was written by astudios
+    pub fn is_installed(&self) -> bool {
+        fs::read_to_string(&self.path).is_ok_and(|content| content.contains(SHIM_MARKER))
+    }
+
+    /// Write the shim, pointing it at the given astudios executable
+    pub fn install(&self, astudios: &Path) -> Result<(), AstudiosError> {
+        if self.path.exists() && !self.is_installed() {
+            return Err(AstudiosError::Path(format!(
+                "{} already exists and was not created by astudios",
+                self.path.display()
+            )));
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&self.path, script(astudios))?;
+        fs::set_permissions(&self.path, fs::Permissions::from_mode(0o755))?;
+        Ok(())
+    }
+
+    /// Remove the shim; returns `false` if it was not installed
+    pub fn uninstall(&self) -> Result<bool, AstudiosError> {
+        if !self.is_installed() {
+            return Ok(false);
+        }
+        fs::remove_file(&self.path)?;
+        Ok(true)
+    }
+}
+
+/// Shell script that forwards its arguments to `astudios shim --exec`
+fn script(astudios: &Path) -> String {
+    let quoted = astudios.display().to_string().replace('\'', r"'\''");
+    format!(
+        "#!/bin/sh\n{SHIM_MARKER}; launches the selected Android Studio\nexec '{quoted}' shim --exec -- \"$@\"\n"
+    )
+}
+
+/// Check whether `dir` is listed in `$PATH`
+pub fn is_on_path(dir: &Path) -> bool {
+    std::env::var_os("PATH").is_some_and(|path| std::env::split_paths(&path).any(|p| p == dir))
+}
@@ -0,0 +1,72 @@
+use crate::{config::Config, error::AstudiosError, json_file::JsonFile, model::directory_size};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+/// On-disk sizes of app bundles, stored in the cache directory so `installed`
+/// does not walk every bundle on each run
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SizeCache {
+    /// Sizes keyed by bundle path
+    #[serde(default)]
+    pub bundles: BTreeMap<PathBuf, CachedSize>,
+}
+
+/// Size of one bundle, valid while the bundle's modification time is unchanged
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CachedSize {
+    /// Modification time of the bundle directory, in seconds since the epoch
+    pub modified: u64,
+    pub size_bytes: u64,
+}
+
+impl JsonFile for SizeCache {
+    fn path() -> Result<PathBuf, AstudiosError> {
+        Ok(Config::cache_dir()?.join("bundle-sizes.json"))
+    }
+
+    /// Load the cache from `path`; a missing or unreadable file is an empty cache
+    fn load_from(path: &Path) -> Result<Self, AstudiosError> {
+        Ok(fs::read_to_string(path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default())
+    }
+}
+
+impl SizeCache {
+    /// Size of the bundle at `path`, walking it only when it changed since the
+    /// cached size was recorded
+    pub fn size_of(&mut self, path: &Path) -> u64 {
+        let modified = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |age| age.as_secs());
+
+        if let Some(cached) = self.bundles.get(path)
+            && cached.modified == modified
+        {
+            return cached.size_bytes;
+        }
+
+        let size_bytes = directory_size(path);
+        self.bundles.insert(
+            path.to_path_buf(),
+            CachedSize {
+                modified,
+                size_bytes,
+            },
+        );
+        size_bytes
+    }
+
+    /// Drop entries for bundles that no longer exist
+    pub fn retain_existing(&mut self) {
+        self.bundles.retain(|path, _| path.exists());
+    }
+}
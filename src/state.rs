@@ -0,0 +1,134 @@
+use crate::{config::Config, error::AstudiosError, json_file::JsonFile};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// What astudios knows about the installations it performed, stored in
+/// `~/.astudios/state.json`
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct State {
+    /// Installations keyed by app bundle path
+    #[serde(default)]
+    pub installs: BTreeMap<PathBuf, InstallRecord>,
+    /// Channel labels set with `set-channel`, keyed by app bundle path
+    #[serde(default)]
+    pub channel_overrides: BTreeMap<PathBuf, String>,
+}
+
+/// Record of a single installation
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct InstallRecord {
+    /// Version that was requested from the releases feed
+    pub version: String,
+    /// Whether the downloaded archive was checked against a published checksum
+    pub verified: bool,
+}
+
+impl JsonFile for State {
+    fn path() -> Result<PathBuf, AstudiosError> {
+        Ok(Config::config_dir()?.join("state.json"))
+    }
+}
+
+impl State {
+    /// Remember an installation, replacing any earlier record for the same bundle
+    pub fn record_install(&mut self, app_path: PathBuf, record: InstallRecord) {
+        self.installs.insert(app_path, record);
+    }
+
+    /// Forget an installation that has been removed
+    pub fn remove_install(&mut self, app_path: &Path) -> Option<InstallRecord> {
+        self.channel_overrides.remove(app_path);
+        self.installs.remove(app_path)
+    }
+
+    /// Channel label the user set for a bundle, if any
+    pub fn channel_override(&self, app_path: &Path) -> Option<&str> {
+        self.channel_overrides.get(app_path).map(String::as_str)
+    }
+
+    /// Set or, with `None`, clear the channel label of a bundle
+    pub fn set_channel_override(&mut self, app_path: PathBuf, label: Option<String>) {
+        match label {
+            Some(label) => {
+                self.channel_overrides.insert(app_path, label);
+            }
+            None => {
+                self.channel_overrides.remove(&app_path);
+            }
+        }
+    }
+
+    /// Bundles still on disk whose archive could not be verified
+    pub fn unverified_installs(&self) -> Vec<&Path> {
+        self.installs
+            .iter()
+            .filter(|(path, record)| !record.verified && path.exists())
+            .map(|(path, _)| path.as_path())
+            .collect()
+    }
+}
+
+/// Progress of an install of several versions, stored in
+/// `~/.astudios/install-checkpoint.json` so an interrupted run resumes where it stopped
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct InstallCheckpoint {
+    /// Versions requested on the command line, in order
+    pub versions: Vec<String>,
+    /// Requested versions that finished installing
+    #[serde(default)]
+    pub completed: Vec<String>,
+}
+
+impl JsonFile for InstallCheckpoint {
+    fn path() -> Result<PathBuf, AstudiosError> {
+        Ok(Config::config_dir()?.join("install-checkpoint.json"))
+    }
+}
+
+impl InstallCheckpoint {
+    /// Continue the checkpoint at `path` if it was written for the same
+    /// versions, or start a new one
+    pub fn resume(path: &Path, versions: &[String]) -> Self {
+        Self::load_from(path)
+            .ok()
+            .filter(|checkpoint| checkpoint.versions == versions)
+            .unwrap_or_else(|| Self {
+                versions: versions.to_vec(),
+                completed: Vec::new(),
+            })
+    }
+
+    /// Remove the checkpoint at `path` once every version is installed
+    pub fn clear(path: &Path) -> Result<(), AstudiosError> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Requested versions that still need to be installed, in order
+    pub fn remaining(&self) -> Vec<String> {
+        self.versions
+            .iter()
+            .filter(|version| !self.completed.contains(version))
+            .cloned()
+            .collect()
+    }
+
+    /// Forget completed versions for which `is_installed` is false, e.g. because
+    /// they were uninstalled since, so they are installed again
+    pub fn retain_installed(&mut self, is_installed: impl Fn(&str) -> bool) {
+        self.completed.retain(|version| is_installed(version));
+    }
+
+    /// Record that `version` finished installing
+    pub fn mark_completed(&mut self, version: &str) {
+        if !self.completed.iter().any(|done| done == version) {
+            self.completed.push(version.to_string());
+        }
+    }
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use astudios::audit::{AuditReport, CodeSignature, file_manifest_of};
+use astudios::blobs;
+use astudios::model::InstalledAndroidStudio;
+use common::create_fake_bundle;
+use std::{fs, path::Path};
+
+/// Create a bundle with a launcher, an Info.plist and a nested resource
+fn create_bundle(dir: &Path) -> InstalledAndroidStudio {
+    let app_path = create_fake_bundle(
+        dir,
+        "Android Studio.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+    let lib = app_path.join("Contents").join("Resources").join("lib");
+    fs::create_dir_all(&lib).unwrap();
+    fs::write(lib.join("app.jar"), "jar").unwrap();
+
+    InstalledAndroidStudio::new(app_path).unwrap().unwrap()
+}
+
+/// Test parsing the signer details printed by `codesign -dvv`
+#[test]
+fn test_parse_codesign_details() {
+    let details = "\
+Executable=/Applications/Android Studio.app/Contents/MacOS/studio
+Identifier=com.google.android.studio
+Format=app bundle with Mach-O universal (x86_64 arm64)
+CodeDirectory v=20500 size=1234 flags=0x10000(runtime) hashes=27+7 location=embedded
+Signature size=9001
+Authority=Developer ID Application: Google LLC (EQHXZ8M8AV)
+Authority=Developer ID Certification Authority
+Authority=Apple Root CA
+Timestamp=3 Oct 2024 at 18:12:04
+TeamIdentifier=EQHXZ8M8AV
+";
+
+    assert_eq!(
+        CodeSignature::parse(details),
+        CodeSignature {
+            identifier: Some("com.google.android.studio".to_string()),
+            authority: vec![
+                "Developer ID Application: Google LLC (EQHXZ8M8AV)".to_string(),
+                "Developer ID Certification Authority".to_string(),
+                "Apple Root CA".to_string(),
+            ],
+            team_id: Some("EQHXZ8M8AV".to_string()),
+        }
+    );
+
+    // Ad-hoc signatures have no team
+    let adhoc =
+        CodeSignature::parse("Identifier=studio\nSignature=adhoc\nTeamIdentifier=not set\n");
+    assert_eq!(adhoc.team_id, None);
+    assert!(adhoc.authority.is_empty());
+}
+
+/// Test that the file manifest covers every regular file by relative path
+#[test]
+fn test_file_manifest() {
+    let temp = tempfile::tempdir().unwrap();
+    let installation = create_bundle(temp.path());
+    std::os::unix::fs::symlink(
+        temp.path().join("elsewhere"),
+        installation.path.join("Contents").join("link"),
+    )
+    .unwrap();
+
+    let manifest = file_manifest_of(&installation.path).unwrap();
+    assert_eq!(
+        manifest.keys().collect::<Vec<_>>(),
+        vec![
+            "Contents/Info.plist",
+            "Contents/MacOS/studio",
+            "Contents/Resources/lib/app.jar",
+            "Contents/Resources/product-info.json",
+        ]
+    );
+    assert_eq!(
+        manifest["Contents/Resources/lib/app.jar"],
+        blobs::sha256_hex(
+            &installation
+                .path
+                .join("Contents")
+                .join("Resources")
+                .join("lib")
+                .join("app.jar")
+        )
+        .unwrap()
+    );
+}
+
+/// Test that an audit records each bundle and detects a changed Info.plist
+#[test]
+fn test_audit_report() {
+    let temp = tempfile::tempdir().unwrap();
+    let installation = create_bundle(temp.path());
+
+    let before = AuditReport::new(std::slice::from_ref(&installation), false).unwrap();
+    let bundle = &before.bundles[0];
+    assert_eq!(bundle.path, installation.path);
+    assert_eq!(bundle.build, "AI-242.23339.11.2421.12483815");
+    assert!(bundle.files.is_none());
+
+    let plist = installation.path.join("Contents").join("Info.plist");
+    let changed = fs::read_to_string(&plist)
+        .unwrap()
+        .replace("studio</string>", "studio2</string>");
+    fs::write(&plist, changed).unwrap();
+    let after = AuditReport::new(std::slice::from_ref(&installation), true).unwrap();
+    assert_ne!(after.bundles[0].info_plist_sha256, bundle.info_plist_sha256);
+    assert_eq!(after.bundles[0].files.as_ref().unwrap().len(), 4);
+}
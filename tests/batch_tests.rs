@@ -0,0 +1,47 @@
+use astudios::batch::run_batch;
+use astudios::error::AstudiosError;
+
+fn items() -> Vec<String> {
+    ["2024.1", "2024.2", "2024.3"].map(String::from).to_vec()
+}
+
+/// Fail every item but the second
+fn fail_odd(item: &str) -> Result<(), AstudiosError> {
+    if item == "2024.2" {
+        Ok(())
+    } else {
+        Err(AstudiosError::General(format!("{item} broke")))
+    }
+}
+
+/// Test that a batch stops at the first failure and returns its error
+#[test]
+fn test_run_batch_stops_at_first_failure() {
+    let mut attempted = Vec::new();
+    let err = run_batch(&items(), false, |item| {
+        attempted.push(item.to_string());
+        fail_odd(item)
+    })
+    .unwrap_err();
+
+    assert_eq!(attempted, ["2024.1"]);
+    assert_eq!(err.to_string(), "Error: 2024.1 broke");
+}
+
+/// Test that --keep-going attempts every item and names each failed one
+#[test]
+fn test_run_batch_keep_going_aggregates_failures() {
+    let mut attempted = Vec::new();
+    let err = run_batch(&items(), true, |item| {
+        attempted.push(item.to_string());
+        fail_odd(item)
+    })
+    .unwrap_err();
+
+    assert_eq!(attempted, items());
+    assert_eq!(
+        err.to_string(),
+        "Error: 2 of 3 operations failed: 2024.1, 2024.3"
+    );
+    assert!(run_batch(&items(), true, |_| Ok(())).is_ok());
+}
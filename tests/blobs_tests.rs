@@ -0,0 +1,121 @@
+use astudios::blobs::{self, BlobStore};
+use std::{fs, os::unix::fs::MetadataExt};
+
+/// Test that adopted archives are stored once and linked back in place
+#[test]
+fn test_adopt_deduplicates_identical_archives() {
+    let temp = tempfile::tempdir().unwrap();
+    let store = BlobStore::with_dir(temp.path().join("blobs")).unwrap();
+    let versions = temp.path().join("versions");
+    fs::create_dir_all(versions.join("2024.2.1")).unwrap();
+    fs::create_dir_all(versions.join("2024.2.2")).unwrap();
+
+    let first = versions.join("2024.2.1").join("studio.dmg");
+    let second = versions.join("2024.2.2").join("studio.dmg");
+    fs::write(&first, "same archive").unwrap();
+    fs::write(&second, "same archive").unwrap();
+
+    let digest = store.adopt(&first).unwrap();
+    assert!(blobs::is_sha256(&digest));
+    assert_eq!(store.adopt(&second).unwrap(), digest);
+
+    let blob = store.blob_path(&digest);
+    assert_eq!(fs::metadata(&blob).unwrap().nlink(), 3);
+    assert_eq!(fs::read_to_string(&second).unwrap(), "same archive");
+
+    let third = temp.path().join("reused.dmg");
+    assert!(store.link_existing(&digest, &third).unwrap());
+    assert!(!store.link_existing(&"0".repeat(64), &third).unwrap());
+}
+
+/// Test that digests are computed in-process and in the order files are given
+#[test]
+fn test_sha256_hex() {
+    let temp = tempfile::tempdir().unwrap();
+    let empty = temp.path().join("empty");
+    let abc = temp.path().join("abc");
+    fs::write(&empty, "").unwrap();
+    fs::write(&abc, "abc").unwrap();
+
+    assert_eq!(
+        blobs::sha256_hex_many(&[&abc, &empty]).unwrap(),
+        vec![
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        ]
+    );
+    let missing = blobs::sha256_hex(&temp.path().join("missing")).unwrap_err();
+    assert!(missing.to_string().contains("Failed to checksum"));
+}
+
+/// Test that only blobs without remaining links are pruned
+#[test]
+fn test_prune_keeps_referenced_blobs() {
+    let temp = tempfile::tempdir().unwrap();
+    let store = BlobStore::with_dir(temp.path().join("blobs")).unwrap();
+    let versions = temp.path().join("versions");
+    fs::create_dir_all(versions.join("2024.2.1")).unwrap();
+
+    let archive = versions.join("2024.2.1").join("studio.dmg");
+    fs::write(&archive, "archive").unwrap();
+    let digest = store.adopt(&archive).unwrap();
+
+    assert_eq!(store.prune(&[&versions]).unwrap(), 0);
+    assert!(store.blob_path(&digest).exists());
+
+    fs::remove_dir_all(versions.join("2024.2.1")).unwrap();
+    assert_eq!(store.prune(&[&versions]).unwrap(), 1);
+    assert!(!store.blob_path(&digest).exists());
+}
+
+/// Test that blobs only linked from a directory about to be removed are reported
+/// without being deleted
+#[test]
+fn test_unreferenced_after_removing_directory() {
+    let temp = tempfile::tempdir().unwrap();
+    let store = BlobStore::with_dir(temp.path().join("blobs")).unwrap();
+    let versions = temp.path().join("versions");
+    let (older, newer) = (versions.join("2024.2.1"), versions.join("2024.2.2"));
+    fs::create_dir_all(&older).unwrap();
+    fs::create_dir_all(&newer).unwrap();
+
+    fs::write(older.join("studio.dmg"), "old archive").unwrap();
+    fs::write(newer.join("studio.dmg"), "new archive").unwrap();
+    let old_digest = store.adopt(&older.join("studio.dmg")).unwrap();
+    let new_digest = store.adopt(&newer.join("studio.dmg")).unwrap();
+    // A symlinked copy elsewhere keeps the newer archive referenced
+    std::os::unix::fs::symlink(store.blob_path(&new_digest), versions.join("shared.dmg")).unwrap();
+
+    assert!(store.unreferenced(&[&versions], &[]).unwrap().is_empty());
+    assert_eq!(
+        store.unreferenced(&[&versions], &[&older]).unwrap(),
+        vec![store.blob_path(&old_digest)]
+    );
+    assert_eq!(
+        store.unreferenced(&[&versions], &[&older, &newer]).unwrap(),
+        vec![store.blob_path(&old_digest)]
+    );
+    assert!(store.blob_path(&old_digest).exists());
+    assert!(older.join("studio.dmg").exists());
+}
+
+/// Test parsing checksums pinned on the command line
+#[test]
+fn test_parse_checksum() {
+    let digest = "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855";
+    let expected = digest.to_ascii_lowercase();
+
+    assert_eq!(
+        blobs::parse_checksum(&format!("sha256:{digest}")).unwrap(),
+        expected
+    );
+    assert_eq!(
+        blobs::parse_checksum(&format!("SHA256:{digest}")).unwrap(),
+        expected
+    );
+    assert_eq!(blobs::parse_checksum(digest).unwrap(), expected);
+
+    assert!(blobs::parse_checksum(&format!("md5:{digest}")).is_err());
+    assert!(blobs::parse_checksum("sha256:abc123").is_err());
+    assert!(blobs::parse_checksum("").is_err());
+}
@@ -99,6 +99,54 @@ fn test_uninstall_help() {
     assert_snapshot!("uninstall_help_output", stdout);
 }
 
+/// Test benchmark command help
+#[test]
+fn test_benchmark_help() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd.args(["benchmark", "--help"]).output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_snapshot!("benchmark_help_output", stdout);
+}
+
+/// Test audit command help
+#[test]
+fn test_audit_help() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd.args(["audit", "--help"]).output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_snapshot!("audit_help_output", stdout);
+}
+
+/// Test history command help
+#[test]
+fn test_history_help() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd.args(["history", "--help"]).output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_snapshot!("history_help_output", stdout);
+}
+
+/// Test set-channel command help
+#[test]
+fn test_set_channel_help() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd.args(["set-channel", "--help"]).output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_snapshot!("set_channel_help_output", stdout);
+}
+
 /// Test which command help
 #[test]
 fn test_which_help() {
@@ -135,6 +183,90 @@ fn test_open_help() {
     assert_snapshot!("open_help_output", stdout);
 }
 
+/// Test status command help
+#[test]
+fn test_status_help() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd.args(["status", "--help"]).output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_snapshot!("status_help_output", stdout);
+}
+
+/// Test info command help
+#[test]
+fn test_info_help() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd.args(["info", "--help"]).output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_snapshot!("info_help_output", stdout);
+}
+
+/// Test outdated command help
+#[test]
+fn test_outdated_help() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd.args(["outdated", "--help"]).output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_snapshot!("outdated_help_output", stdout);
+}
+
+/// Test shim command help
+#[test]
+fn test_shim_help() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd.args(["shim", "--help"]).output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_snapshot!("shim_help_output", stdout);
+}
+
+/// Test open-dir command help
+#[test]
+fn test_open_dir_help() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd.args(["open-dir", "--help"]).output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_snapshot!("open_dir_help_output", stdout);
+}
+
+/// Test doctor command help
+#[test]
+fn test_doctor_help() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd.args(["doctor", "--help"]).output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_snapshot!("doctor_help_output", stdout);
+}
+
+/// Test that an unknown channel name is rejected at parse time
+#[test]
+fn test_invalid_channel_error() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd.args(["list", "--channel", "beeta"]).output().unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_snapshot!("invalid_channel_error", stderr);
+}
+
 /// Test invalid command error
 #[test]
 fn test_invalid_command() {
@@ -146,3 +278,18 @@ fn test_invalid_command() {
 
     assert_snapshot!("invalid_command_error", stderr);
 }
+
+/// Test that `--parallel 0` is rejected at parse time
+#[test]
+fn test_invalid_parallel_error() {
+    let mut cmd = Command::cargo_bin("astudios").unwrap();
+    let output = cmd
+        .args(["install", "2024.2.1", "2024.3.1", "--parallel", "0"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_snapshot!("invalid_parallel_error", stderr);
+}
@@ -0,0 +1,50 @@
+use std::{
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+/// Create a minimal Android Studio app bundle with an executable launcher
+pub fn create_fake_bundle(dir: &Path, app_name: &str, short_version: &str, build: &str) -> PathBuf {
+    let app_path = dir.join(app_name);
+    let contents = app_path.join("Contents");
+    fs::create_dir_all(contents.join("MacOS")).unwrap();
+    fs::create_dir_all(contents.join("Resources")).unwrap();
+
+    fs::write(
+        contents.join("Info.plist"),
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>CFBundleIdentifier</key>
+  <string>com.google.android.studio</string>
+  <key>CFBundleExecutable</key>
+  <string>studio</string>
+  <key>CFBundleShortVersionString</key>
+  <string>{short_version}</string>
+  <key>CFBundleVersion</key>
+  <string>{build}</string>
+</dict>
+</plist>
+"#
+        ),
+    )
+    .unwrap();
+
+    fs::write(
+        contents.join("Resources").join("product-info.json"),
+        format!(
+            r#"{{"name": "Android Studio", "version": "{short_version}", "buildNumber": "{}", "productCode": "AI"}}"#,
+            build.trim_start_matches("AI-")
+        ),
+    )
+    .unwrap();
+
+    let launcher = contents.join("MacOS").join("studio");
+    fs::write(&launcher, "#!/bin/sh\n").unwrap();
+    fs::set_permissions(&launcher, fs::Permissions::from_mode(0o755)).unwrap();
+
+    app_path
+}
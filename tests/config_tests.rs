@@ -0,0 +1,178 @@
+use astudios::config::{Config, NetworkSettings};
+use astudios::json_file::JsonFile;
+use astudios::model::MacArch;
+use astudios::preferences::Preferences;
+use std::{fs, time::Duration};
+
+/// Test interpretation of boolean environment flag values
+#[test]
+fn test_truthy_flag_values() {
+    for value in ["1", "true", "TRUE", "yes", "on", " true "] {
+        assert!(Config::is_truthy(value), "{value}");
+    }
+
+    for value in ["", "0", "false", "no", "off", "maybe"] {
+        assert!(!Config::is_truthy(value), "{value}");
+    }
+}
+
+/// Test parsing of the ASTUDIOS_MIRRORS list
+#[test]
+fn test_parse_mirrors() {
+    assert_eq!(
+        NetworkSettings::parse_mirrors(
+            "https://a.example.com, https://b.example.com\nhttps://c.example.com"
+        ),
+        vec![
+            "https://a.example.com",
+            "https://b.example.com",
+            "https://c.example.com"
+        ]
+    );
+    assert!(NetworkSettings::parse_mirrors(" , ").is_empty());
+}
+
+/// Test that remembered preferences round-trip and default when missing
+#[test]
+fn test_preferences_round_trip() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("preferences.json");
+
+    assert_eq!(
+        Preferences::load_from(&path).unwrap(),
+        Preferences::default()
+    );
+
+    let preferences = Preferences {
+        mac_arch: Some(MacArch::X86_64),
+        ..Default::default()
+    };
+    preferences.save_to(&path).unwrap();
+    assert_eq!(Preferences::load_from(&path).unwrap(), preferences);
+}
+
+/// Test that ASTUDIOS_HOME and the XDG variables relocate the astudios directories
+#[test]
+fn test_directory_overrides() {
+    let temp = tempfile::tempdir().unwrap();
+    let xdg_cache = temp.path().join("xdg-cache");
+    let xdg_data = temp.path().join("xdg-data");
+    // Only this test touches the environment in this binary
+    unsafe {
+        std::env::set_var("ASTUDIOS_HOME", temp.path());
+        std::env::set_var("XDG_CACHE_HOME", &xdg_cache);
+        std::env::set_var("XDG_DATA_HOME", &xdg_data);
+    }
+
+    // ASTUDIOS_HOME wins over the XDG directories
+    assert_eq!(Config::config_dir().unwrap(), temp.path());
+    assert_eq!(Config::cache_dir().unwrap(), temp.path().join("cache"));
+    assert_eq!(
+        Config::versions_dir().unwrap(),
+        temp.path().join("versions")
+    );
+
+    unsafe { std::env::remove_var("ASTUDIOS_HOME") };
+    if cfg!(target_os = "macos") {
+        assert!(!Config::cache_dir().unwrap().starts_with(&xdg_cache));
+    } else {
+        assert_eq!(Config::cache_dir().unwrap(), xdg_cache.join("astudios"));
+        assert_eq!(
+            Config::versions_dir().unwrap(),
+            xdg_data.join("astudios").join("versions")
+        );
+        assert_eq!(
+            Config::blobs_dir().unwrap(),
+            xdg_cache.join("astudios").join("blobs")
+        );
+    }
+
+    unsafe {
+        std::env::remove_var("XDG_CACHE_HOME");
+        std::env::remove_var("XDG_DATA_HOME");
+    }
+}
+
+/// Test loading a custom CA bundle and rejecting unusable ones
+#[test]
+fn test_ca_bundle() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut settings = NetworkSettings::default();
+    assert!(settings.ca_certificates().unwrap().is_empty());
+
+    let bundle = temp.path().join("corporate-ca.pem");
+    settings.ca_bundle = Some(bundle.clone());
+    assert!(settings.ca_certificates().is_err());
+
+    fs::write(&bundle, "not a certificate").unwrap();
+    assert!(settings.ca_certificates().is_err());
+}
+
+/// Test that the connect timeout defaults to a short value independent of request timeouts
+#[test]
+fn test_connect_timeout() {
+    let mut settings = NetworkSettings::default();
+    assert_eq!(
+        settings.connect_timeout(),
+        Duration::from_secs(Config::CONNECT_TIMEOUT_SECS)
+    );
+
+    settings.connect_timeout_secs = Some(3);
+    assert_eq!(settings.connect_timeout(), Duration::from_secs(3));
+}
+
+/// Test that --timeout replaces a client's default request timeout only when given
+#[test]
+fn test_request_timeout() {
+    let mut settings = NetworkSettings::default();
+    assert_eq!(
+        settings.request_timeout_secs(Config::NETWORK_TIMEOUT_SECS),
+        Config::NETWORK_TIMEOUT_SECS
+    );
+
+    settings.timeout_secs = Some(600);
+    assert_eq!(
+        settings.request_timeout_secs(Config::DOWNLOAD_IDLE_TIMEOUT_SECS),
+        600
+    );
+}
+
+/// Test checking directories astudios writes to, whether they exist yet or not
+#[test]
+fn test_check_writable_dir() {
+    let temp = tempfile::tempdir().unwrap();
+    assert_eq!(Config::check_writable_dir("Cache", temp.path()), None);
+    assert_eq!(
+        Config::check_writable_dir("Cache", &temp.path().join("not").join("yet")),
+        None
+    );
+
+    let file = temp.path().join("file");
+    fs::write(&file, "").unwrap();
+    let problem = Config::check_writable_dir("Cache", &file.join("cache")).unwrap();
+    assert!(problem.starts_with("Cache "), "{problem}");
+    assert!(problem.contains("is not a directory"), "{problem}");
+}
+
+/// Test that only absolute http and https URLs are accepted
+#[test]
+fn test_check_url() {
+    assert_eq!(
+        Config::check_url("Feed", "https://example.com/releases.xml"),
+        None
+    );
+    assert_eq!(
+        Config::check_url("Feed", "http://127.0.0.1:8080/feed"),
+        None
+    );
+    assert!(
+        Config::check_url("Feed", "example.com/releases.xml")
+            .unwrap()
+            .contains("is not a valid URL")
+    );
+    assert!(
+        Config::check_url("Feed", "ftp://example.com/releases.xml")
+            .unwrap()
+            .contains("unsupported scheme 'ftp'")
+    );
+}
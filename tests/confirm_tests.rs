@@ -0,0 +1,12 @@
+use astudios::confirm;
+
+/// Test which answers to a `[y/N]` prompt count as yes
+#[test]
+fn test_is_yes() {
+    for yes in ["y", "Y", "yes", " YES\n"] {
+        assert!(confirm::is_yes(yes), "{yes:?}");
+    }
+    for no in ["", "\n", "n", "no", "yep", "sure"] {
+        assert!(!confirm::is_yes(no), "{no:?}");
+    }
+}
@@ -0,0 +1,74 @@
+use astudios::detector::{DetectionResult, SystemDetector};
+use astudios::error::AstudiosError;
+use std::io::{Error, ErrorKind};
+
+/// Test macOS version parsing across the 10.x and 11+ numbering schemes
+#[test]
+fn test_parse_macos_version() {
+    assert_eq!(SystemDetector::parse_macos_version("10.13"), Some((10, 13)));
+    assert_eq!(SystemDetector::parse_macos_version("11.0"), Some((11, 0)));
+    assert_eq!(SystemDetector::parse_macos_version("13.4.1"), Some((13, 4)));
+    assert_eq!(SystemDetector::parse_macos_version("14"), Some((14, 0)));
+    assert_eq!(SystemDetector::parse_macos_version(""), None);
+    assert_eq!(SystemDetector::parse_macos_version("Sonoma"), None);
+}
+
+/// Test comparing the detected macOS version against the required minimum
+#[test]
+fn test_macos_version_satisfies() {
+    let satisfies = SystemDetector::macos_version_satisfies;
+
+    assert_eq!(satisfies("10.13", "10.14"), Some(false));
+    assert_eq!(satisfies("11.0", "10.14"), Some(true));
+    assert_eq!(satisfies("13.4", "10.14"), Some(true));
+
+    // Newer releases require macOS 12 or later
+    assert_eq!(satisfies("11.0", "12"), Some(false));
+    assert_eq!(satisfies("13.4", "12"), Some(true));
+    assert_eq!(satisfies("12.0.1", "12.0"), Some(true));
+
+    assert_eq!(satisfies("unknown", "12"), None);
+}
+
+/// Test that a read-only or protected directory is reported with a way around it
+#[test]
+fn test_write_error_guidance() {
+    let dir = std::path::Path::new("/Applications");
+
+    for kind in [ErrorKind::PermissionDenied, ErrorKind::ReadOnlyFilesystem] {
+        match SystemDetector::write_error(dir, Error::from(kind)) {
+            AstudiosError::PermissionDenied(msg) => {
+                assert!(msg.contains("/Applications"));
+                assert!(msg.contains("ASTUDIOS_APPS_DIR"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    assert!(matches!(
+        SystemDetector::write_error(dir, Error::from(ErrorKind::NotFound)),
+        AstudiosError::Io(_)
+    ));
+}
+
+/// Test that strict mode turns warnings into a failed result
+#[test]
+fn test_strict_treats_warnings_as_failures() {
+    let mut result = DetectionResult {
+        platform_compatible: true,
+        disk_space_sufficient: true,
+        permissions_valid: true,
+        network_available: true,
+        dependencies_available: true,
+        ..DetectionResult::new()
+    };
+    assert!(result.passes(false));
+    assert!(result.passes(true));
+
+    result.add_warning("java", "No Java runtime found".to_string());
+    assert!(result.passes(false));
+    assert!(!result.passes(true));
+
+    result.network_available = false;
+    assert!(!result.passes(false));
+}
@@ -0,0 +1,108 @@
+use astudios::downloader::{Downloader, is_out_of_space, mirror_url, parse_content_disposition};
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// Test file name extraction from Content-Disposition headers
+#[test]
+fn test_parse_content_disposition() {
+    assert_eq!(
+        parse_content_disposition(r#"attachment; filename="android-studio-2024.2.1.11-mac.dmg""#),
+        Some("android-studio-2024.2.1.11-mac.dmg".to_string())
+    );
+    assert_eq!(
+        parse_content_disposition("attachment; filename=studio.dmg"),
+        Some("studio.dmg".to_string())
+    );
+
+    // filename* wins over filename and is percent-decoded
+    assert_eq!(
+        parse_content_disposition(
+            "attachment; filename=\"fallback.dmg\"; filename*=UTF-8''android%20studio.dmg"
+        ),
+        Some("android studio.dmg".to_string())
+    );
+
+    assert_eq!(parse_content_disposition("inline"), None);
+}
+
+/// Test that path components in server-provided names are stripped
+#[test]
+fn test_parse_content_disposition_strips_paths() {
+    assert_eq!(
+        parse_content_disposition(r#"attachment; filename="../../etc/studio.dmg""#),
+        Some("studio.dmg".to_string())
+    );
+    assert_eq!(
+        parse_content_disposition(r#"attachment; filename="C:\temp\studio.dmg""#),
+        Some("studio.dmg".to_string())
+    );
+    assert_eq!(
+        parse_content_disposition(r#"attachment; filename="..""#),
+        None
+    );
+    assert_eq!(
+        parse_content_disposition(r#"attachment; filename="dir/""#),
+        None
+    );
+}
+
+/// Test that in-progress downloads are staged next to their destination
+#[test]
+fn test_partial_path() {
+    assert_eq!(
+        Downloader::partial_path(Path::new("/tmp/versions/2024.2/android studio.dmg")),
+        Path::new(&format!(
+            "/tmp/versions/2024.2/android studio.dmg.{}.part",
+            std::process::id()
+        ))
+    );
+}
+
+/// Test that partial files of finished processes are resumed, but not those of running ones
+#[test]
+fn test_adopt_stale_partial() {
+    let temp = tempfile::tempdir().unwrap();
+    let destination = temp.path().join("android-studio.dmg");
+    let ours = Downloader::partial_path(&destination);
+
+    // PID 1 is always running
+    let running = temp.path().join("android-studio.dmg.1.part");
+    fs::write(&running, b"in flight").unwrap();
+    assert!(!Downloader::adopt_stale_partial(&destination).unwrap());
+    assert!(running.exists());
+
+    let stale = temp.path().join("android-studio.dmg.999999999.part");
+    fs::write(&stale, b"stale").unwrap();
+    assert!(Downloader::adopt_stale_partial(&destination).unwrap());
+    assert!(!stale.exists());
+    assert_eq!(fs::read(&ours).unwrap(), b"stale");
+    assert!(running.exists());
+}
+
+/// Test that a full disk is told apart from other write failures
+#[test]
+fn test_is_out_of_space() {
+    assert!(is_out_of_space(&Error::from(ErrorKind::StorageFull)));
+    assert!(is_out_of_space(&Error::from(ErrorKind::WriteZero)));
+    assert!(is_out_of_space(&Error::from_raw_os_error(28)));
+    assert!(!is_out_of_space(&Error::from(ErrorKind::PermissionDenied)));
+    assert!(!is_out_of_space(&Error::from(ErrorKind::ConnectionReset)));
+}
+
+/// Test rewriting a download link onto a mirror
+#[test]
+fn test_mirror_url() {
+    let link = "https://redirector.gvt1.com/edgedl/android/studio/install/2024.2.1.12/android-studio-2024.2.1.12-mac.dmg";
+    assert_eq!(
+        mirror_url(link, "https://mirror.example.com/google/").as_deref(),
+        Some(
+            "https://mirror.example.com/google/edgedl/android/studio/install/2024.2.1.12/android-studio-2024.2.1.12-mac.dmg"
+        )
+    );
+    assert_eq!(
+        mirror_url("https://example.com/a.dmg?token=1", "http://10.0.0.2:8080").as_deref(),
+        Some("http://10.0.0.2:8080/a.dmg?token=1")
+    );
+    assert_eq!(mirror_url(link, "not a url"), None);
+}
@@ -0,0 +1,1143 @@
+//! Tests driving the releases feed client and the built-in downloader against
+//! a local mock server, so nothing here depends on the real JetBrains API
+
+mod common;
+
+use assert_cmd::Command;
+use astudios::api::{ApiClient, FeedFormat};
+use astudios::aria2_rpc::Aria2Daemon;
+use astudios::blobs;
+use astudios::downloader::Downloader;
+use astudios::error::AstudiosError;
+use astudios::list::{AndroidStudioLister, CacheMaxAge};
+use astudios::model::MacArch;
+use common::create_fake_bundle;
+use flate2::{Compression, write::GzEncoder};
+use mockito::{Matcher, Server};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Releases feed with one stable and one canary build
+const RELEASES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<content version="1">
+  <item>
+    <name>Android Studio Meerkat | 2024.3.1 Canary 2</name>
+    <build>AI-243.22562.145.2431.12836789</build>
+    <version>2024.3.1.2</version>
+    <channel>Canary</channel>
+    <platformBuild>243.22562.145</platformBuild>
+    <platformVersion>2024.3</platformVersion>
+    <date>2024-11-28</date>
+    <download>
+      <link>https://redirector.gvt1.com/edgedl/android/studio/ide-zips/2024.3.1.2/android-studio-2024.3.1.2-mac_arm.dmg</link>
+      <size>1.2 GB</size>
+      <checksum>4b227777d4dd1fc61c6f884f48641d02b4d121d3fd328cb08b5531fcacdabf8a</checksum>
+    </download>
+  </item>
+  <item>
+    <name>Android Studio Ladybug | 2024.2.1</name>
+    <build>AI-242.21829.142.2421.12409432</build>
+    <version>2024.2.1.11</version>
+    <channel>Release</channel>
+    <platformBuild>242.21829.142</platformBuild>
+    <platformVersion>2024.2</platformVersion>
+    <date>2024-10-01</date>
+    <download>
+      <link>https://redirector.gvt1.com/edgedl/android/studio/ide-zips/2024.2.1.11/android-studio-2024.2.1.11-mac_arm.dmg</link>
+      <size>1.1 GB</size>
+      <checksum>ef2d127de37b942baad06145e54b0c619a1f22327b2ebbcfbec78f5564afe39d</checksum>
+    </download>
+  </item>
+</content>
+"#;
+
+/// Preview build whose channel label astudios does not recognize
+const DEV_ITEM: &str = r#"<item>
+    <name>Android Studio Narwhal | 2025.1.1 Dev 1</name>
+    <build>AI-251.23774.16.2511.13130393</build>
+    <version>2025.1.1.1</version>
+    <channel>Dev</channel>
+    <platformBuild>251.23774.16</platformBuild>
+    <platformVersion>2025.1</platformVersion>
+    <date>2025-01-15</date>
+    <download>
+      <link>https://redirector.gvt1.com/edgedl/android/studio/ide-zips/2025.1.1.1/android-studio-2025.1.1.1-mac_arm.dmg</link>
+      <size>1.3 GB</size>
+      <checksum></checksum>
+    </download>
+  </item>"#;
+
+/// Stand-in for a disk image; only its bytes matter to the downloader
+const FAKE_DMG: &[u8] = b"koly: not really an Apple disk image, but close enough for a download";
+
+fn sha256_of(data: &[u8]) -> String {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("data");
+    fs::write(&path, data).unwrap();
+    blobs::sha256_hex(&path).unwrap()
+}
+
+/// Lister with a private cache, fetching from the mock server's feed
+fn lister(server: &Server, cache_dir: &Path) -> AndroidStudioLister {
+    AndroidStudioLister::with_cache_dir(cache_dir.to_path_buf())
+        .unwrap()
+        .with_feed_url(format!("{}/releases.xml", server.url()))
+}
+
+/// The astudios binary with its state confined to `home` and, given a mock
+/// server, its releases feed served from there
+fn astudios(home: &Path, server: Option<&Server>) -> Command {
+    let mut command = Command::cargo_bin("astudios").unwrap();
+    command
+        .env("ASTUDIOS_HOME", home)
+        .env_remove("XDG_CACHE_HOME")
+        .env_remove("XDG_DATA_HOME")
+        .env_remove("ASTUDIOS_ASSUME_YES")
+        .env("NO_COLOR", "1");
+    if let Some(server) = server {
+        command.env(
+            "ASTUDIOS_RELEASES_FEED_URL",
+            format!("{}/releases.xml", server.url()),
+        );
+    }
+    command
+}
+
+/// Test that the feed is parsed from the server
+#[test]
+fn test_fetch_releases() {
+    let mut server = Server::new();
+    let feed = server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML)
+        .create();
+
+    let releases = ApiClient::new()
+        .unwrap()
+        .with_feed_url(format!("{}/releases.xml", server.url()))
+        .fetch_releases()
+        .unwrap();
+
+    feed.assert();
+    assert_eq!(releases.items.len(), 2);
+    assert_eq!(releases.items[1].version, "2024.2.1.11");
+    assert!(releases.items[1].is_release());
+}
+
+/// Test that a gzip-encoded feed is decompressed before parsing
+#[test]
+fn test_fetch_gzip_releases() {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(RELEASES_XML.as_bytes()).unwrap();
+    let gzipped = encoder.finish().unwrap();
+
+    let mut server = Server::new();
+    server
+        .mock("GET", "/releases.xml")
+        .with_header("content-encoding", "gzip")
+        .with_body(&gzipped)
+        .create();
+
+    let releases = ApiClient::new()
+        .unwrap()
+        .with_feed_url(format!("{}/releases.xml", server.url()))
+        .fetch_releases()
+        .unwrap();
+    assert_eq!(releases.items.len(), 2);
+
+    // A truncated stream is reported as such rather than as broken XML
+    let result = ApiClient::parse_releases(&gzipped[..gzipped.len() / 2]);
+    assert!(matches!(result, Err(AstudiosError::Parse(message)) if message.contains("decompress")));
+}
+
+/// Test that a JSON feed is recognized by its content type or URL, unless a format is forced
+#[test]
+fn test_fetch_json_releases() {
+    let json = serde_json::to_string(&ApiClient::parse_releases(RELEASES_XML.as_bytes()).unwrap())
+        .unwrap();
+    let mut server = Server::new();
+    server
+        .mock("GET", "/releases")
+        .with_header("content-type", "application/json; charset=utf-8")
+        .with_body(&json)
+        .create();
+    server
+        .mock("GET", "/releases.json")
+        .with_header("content-type", "text/plain")
+        .with_body(&json)
+        .create();
+
+    for path in ["/releases", "/releases.json"] {
+        let releases = ApiClient::new()
+            .unwrap()
+            .with_feed_url(format!("{}{path}", server.url()))
+            .fetch_releases()
+            .unwrap();
+        assert_eq!(releases.items.len(), 2, "{path}");
+    }
+
+    let forced = ApiClient::new()
+        .unwrap()
+        .with_feed_url(format!("{}/releases.json", server.url()))
+        .with_feed_format(FeedFormat::Xml)
+        .fetch_releases();
+    assert!(matches!(forced, Err(AstudiosError::Parse(_))));
+
+    assert_eq!(
+        FeedFormat::Auto.resolve(Some("application/xml"), "https://example.com/feed.json"),
+        FeedFormat::Json
+    );
+    assert_eq!(
+        FeedFormat::Auto.resolve(Some("text/xml"), "https://example.com/feed"),
+        FeedFormat::Xml
+    );
+    assert_eq!(
+        FeedFormat::Auto.resolve(None, "https://example.com/feed.json?v=2"),
+        FeedFormat::Json
+    );
+    assert_eq!(
+        FeedFormat::Json.resolve(Some("text/xml"), "https://example.com/feed.xml"),
+        FeedFormat::Json
+    );
+}
+
+/// Test that an error status is reported instead of being parsed as a feed
+#[test]
+fn test_fetch_releases_error_status() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/releases.xml")
+        .with_status(503)
+        .create();
+
+    let result = ApiClient::new()
+        .unwrap()
+        .with_feed_url(format!("{}/releases.xml", server.url()))
+        .fetch_releases();
+
+    assert!(matches!(result, Err(AstudiosError::Network(_))));
+}
+
+/// Test that the feed is fetched once and then served from the cache
+#[test]
+fn test_cache_miss_then_hit() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut server = Server::new();
+    let feed = server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML)
+        .expect(1)
+        .create();
+
+    let lister = lister(&server, temp.path());
+    assert!(!lister.is_cache_fresh().unwrap());
+    assert_eq!(lister.get_releases().unwrap().items.len(), 2);
+    assert!(lister.is_cache_fresh().unwrap());
+    assert_eq!(lister.get_latest_release().unwrap().version, "2024.2.1.11");
+
+    feed.assert();
+}
+
+/// Test that a refresh keeps the list it replaces, for telling which builds are new
+#[test]
+fn test_refresh_keeps_previous_snapshot() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut server = Server::new();
+    server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML)
+        .create();
+
+    let lister = lister(&server, temp.path());
+    lister.get_releases().unwrap();
+    assert!(lister.previous_builds().is_none());
+
+    server.reset();
+    let feed = RELEASES_XML.replacen("<item>", &format!("{DEV_ITEM}\n  <item>"), 1);
+    server.mock("GET", "/releases.xml").with_body(feed).create();
+    lister.refresh().unwrap();
+
+    let previous = lister.previous_builds().unwrap();
+    assert_eq!(previous.len(), 2);
+    assert!(previous.contains("AI-242.21829.142.2421.12409432"));
+    assert!(!previous.contains("AI-251.23774.16.2511.13130393"));
+}
+
+/// Test that --latest skips previews, including ones with a channel label it does not know
+#[test]
+fn test_latest_release_skips_previews() {
+    let temp = tempfile::tempdir().unwrap();
+    let feed = RELEASES_XML.replacen("<item>", &format!("{DEV_ITEM}\n  <item>"), 1);
+    let mut server = Server::new();
+    server.mock("GET", "/releases.xml").with_body(feed).create();
+
+    let lister = lister(&server, temp.path());
+    assert_eq!(lister.get_releases().unwrap().items[0].channel, "Dev");
+    assert_eq!(lister.get_latest_release().unwrap().version, "2024.2.1.11");
+    assert_eq!(
+        lister.get_latest_prerelease().unwrap().version,
+        "2024.3.1.2"
+    );
+}
+
+/// Test that an expired cache is refetched
+#[test]
+fn test_expired_cache_is_refetched() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut server = Server::new();
+    let feed = server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML)
+        .expect(2)
+        .create();
+
+    let lister = lister(&server, temp.path()).with_max_age(CacheMaxAge::Within(Default::default()));
+    lister.get_releases().unwrap();
+    lister.get_releases().unwrap();
+
+    feed.assert();
+}
+
+/// Test that the freshness tolerance is chosen per call, and that a forced
+/// refresh fails without touching the cache when the feed is down
+#[test]
+fn test_refresh_if_stale() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut server = Server::new();
+    let good = server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML)
+        .expect(2)
+        .create();
+
+    let lister = lister(&server, temp.path());
+    lister.refresh_if_stale(CacheMaxAge::Never).unwrap();
+    lister.refresh_if_stale(CacheMaxAge::Never).unwrap();
+    lister
+        .refresh_if_stale(CacheMaxAge::Within(Duration::ZERO))
+        .unwrap();
+    good.assert();
+    good.remove();
+
+    server
+        .mock("GET", "/releases.xml")
+        .with_status(503)
+        .create();
+    assert!(matches!(lister.refresh(), Err(AstudiosError::Network(_))));
+    assert_eq!(
+        lister
+            .refresh_if_stale(CacheMaxAge::Within(Duration::ZERO))
+            .unwrap()
+            .items
+            .len(),
+        2
+    );
+}
+
+/// Test that a malformed feed falls back to the cached list, and fails without one
+#[test]
+fn test_malformed_feed_falls_back_to_cache() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut server = Server::new();
+    let good = server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML)
+        .create();
+
+    let lister = lister(&server, temp.path()).with_max_age(CacheMaxAge::Within(Default::default()));
+    lister.get_releases().unwrap();
+    good.remove();
+
+    server
+        .mock("GET", "/releases.xml")
+        .with_body("<content version=\"1\"><item><name>truncated")
+        .create();
+    assert_eq!(lister.get_releases().unwrap().items.len(), 2);
+
+    let empty = tempfile::tempdir().unwrap();
+    let uncached = AndroidStudioLister::with_cache_dir(empty.path().to_path_buf())
+        .unwrap()
+        .with_feed_url(format!("{}/releases.xml", server.url()));
+    assert!(matches!(
+        uncached.get_releases(),
+        Err(AstudiosError::Parse(_))
+    ));
+}
+
+/// Test that a verified download lands at its destination
+#[test]
+fn test_download_verifies_checksum() {
+    let temp = tempfile::tempdir().unwrap();
+    let destination = temp.path().join("android-studio.dmg");
+    let mut server = Server::new();
+    server
+        .mock("GET", "/android-studio.dmg")
+        .with_body(FAKE_DMG)
+        .create();
+
+    let reused = Downloader::Reqwest
+        .download_with_mirrors(
+            &format!("{}/android-studio.dmg", server.url()),
+            &destination,
+            None,
+            true,
+            &sha256_of(FAKE_DMG),
+        )
+        .unwrap();
+
+    assert_eq!(reused, 0);
+    assert_eq!(fs::read(&destination).unwrap(), FAKE_DMG);
+}
+
+/// Test that a download not matching its checksum is deleted
+#[test]
+fn test_download_checksum_mismatch() {
+    let temp = tempfile::tempdir().unwrap();
+    let destination = temp.path().join("android-studio.dmg");
+    let mut server = Server::new();
+    server
+        .mock("GET", "/android-studio.dmg")
+        .with_body(FAKE_DMG)
+        .create();
+
+    let result = Downloader::Reqwest.download_with_mirrors(
+        &format!("{}/android-studio.dmg", server.url()),
+        &destination,
+        None,
+        true,
+        &sha256_of(b"a different archive"),
+    );
+
+    assert!(
+        matches!(result, Err(AstudiosError::Download(msg)) if msg.contains("Checksum mismatch"))
+    );
+    assert!(!destination.exists());
+}
+
+/// Test that a partial download left by an earlier process is resumed with a range request
+#[test]
+fn test_download_resumes_partial_file() {
+    let temp = tempfile::tempdir().unwrap();
+    let destination = temp.path().join("android-studio.dmg");
+    let (head, tail) = FAKE_DMG.split_at(20);
+    fs::write(temp.path().join("android-studio.dmg.999999999.part"), head).unwrap();
+
+    let mut server = Server::new();
+    let resumed = server
+        .mock("GET", "/android-studio.dmg")
+        .match_header("range", "bytes=20-")
+        .with_status(206)
+        .with_body(tail)
+        .create();
+
+    let reused = Downloader::Reqwest
+        .download_with_mirrors(
+            &format!("{}/android-studio.dmg", server.url()),
+            &destination,
+            None,
+            true,
+            &sha256_of(FAKE_DMG),
+        )
+        .unwrap();
+
+    resumed.assert();
+    assert_eq!(reused, 20);
+    assert_eq!(fs::read(&destination).unwrap(), FAKE_DMG);
+}
+
+/// Test that a server ignoring the range request restarts the download from scratch
+#[test]
+fn test_download_restarts_without_range_support() {
+    let temp = tempfile::tempdir().unwrap();
+    let destination = temp.path().join("android-studio.dmg");
+    fs::write(
+        temp.path().join("android-studio.dmg.999999999.part"),
+        b"garbage from another file",
+    )
+    .unwrap();
+
+    let mut server = Server::new();
+    server
+        .mock("GET", "/android-studio.dmg")
+        .match_header("range", Matcher::Any)
+        .with_body(FAKE_DMG)
+        .create();
+
+    let reused = Downloader::Reqwest
+        .download_with_mirrors(
+            &format!("{}/android-studio.dmg", server.url()),
+            &destination,
+            None,
+            true,
+            &sha256_of(FAKE_DMG),
+        )
+        .unwrap();
+
+    assert_eq!(reused, 0);
+    assert_eq!(fs::read(&destination).unwrap(), FAKE_DMG);
+}
+
+/// Test that a cancelled download fails as such and never lands at its destination
+#[test]
+fn test_download_cancellable() {
+    let temp = tempfile::tempdir().unwrap();
+    let destination = temp.path().join("android-studio.dmg");
+    let mut server = Server::new();
+    server
+        .mock("GET", "/android-studio.dmg")
+        .with_body(FAKE_DMG)
+        .create();
+    let url = format!("{}/android-studio.dmg", server.url());
+
+    let cancel = AtomicBool::new(true);
+    let result = Downloader::Reqwest.download_cancellable(&url, &destination, None, &cancel);
+    assert!(matches!(result, Err(AstudiosError::Cancelled(_))));
+    assert!(!destination.exists());
+
+    cancel.store(false, Ordering::Relaxed);
+    Downloader::Reqwest
+        .download_cancellable(&url, &destination, None, &cancel)
+        .unwrap();
+    assert_eq!(fs::read(&destination).unwrap(), FAKE_DMG);
+}
+
+/// Test that a benchmark download stops at its byte cap and leaves nothing behind
+#[test]
+fn test_benchmark_is_bounded() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut server = Server::new();
+    let probe = server
+        .mock("GET", "/android-studio.dmg")
+        .match_header("range", "bytes=0-99999")
+        .with_body(vec![0u8; 256 * 1024])
+        .create();
+
+    let result = Downloader::Reqwest
+        .benchmark(
+            &format!("{}/android-studio.dmg", server.url()),
+            temp.path(),
+            100_000,
+            Duration::from_secs(30),
+        )
+        .unwrap();
+
+    probe.assert();
+    assert_eq!(result.bytes, 100_000);
+    assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 0);
+}
+
+/// Mock the aria2 JSON-RPC endpoint answering `method` calls that carry the secret
+fn mock_rpc(server: &mut Server, method: &str, result: &str) -> mockito::Mock {
+    server
+        .mock("POST", "/jsonrpc")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(format!(r#""method":"{method}""#)),
+            Matcher::Regex(r#""token:s3cret""#.to_string()),
+        ]))
+        .with_body(format!(
+            r#"{{"jsonrpc":"2.0","id":"astudios","result":{result}}}"#
+        ))
+        .create()
+}
+
+/// Test that a download submitted to the aria2 RPC daemon waits until it completes
+#[test]
+fn test_aria2_rpc_download() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut server = Server::new();
+    mock_rpc(&mut server, "aria2.getVersion", r#"{"version":"1.37.0"}"#);
+    let add = mock_rpc(&mut server, "aria2.addUri", r#""2089b05ecca3d829""#);
+    let status = mock_rpc(
+        &mut server,
+        "aria2.tellStatus",
+        r#"{"status":"complete","totalLength":"1024","completedLength":"1024"}"#,
+    );
+
+    let daemon = Aria2Daemon::connect(&format!("{}/jsonrpc", server.url()), "s3cret").unwrap();
+    daemon
+        .download(
+            "https://example.com/android-studio.dmg",
+            &temp.path().join("android-studio.dmg"),
+        )
+        .unwrap();
+
+    add.assert();
+    status.assert();
+}
+
+/// Test that downloads failed by the daemon report its error, and a full disk as such
+#[test]
+fn test_aria2_rpc_download_failures() {
+    let temp = tempfile::tempdir().unwrap();
+    let destination = temp.path().join("android-studio.dmg");
+    let mut server = Server::new();
+    mock_rpc(&mut server, "aria2.getVersion", r#"{"version":"1.37.0"}"#);
+    mock_rpc(&mut server, "aria2.addUri", r#""2089b05ecca3d829""#);
+    let daemon = Aria2Daemon::connect(&format!("{}/jsonrpc", server.url()), "s3cret").unwrap();
+
+    let failed = mock_rpc(
+        &mut server,
+        "aria2.tellStatus",
+        r#"{"status":"error","errorCode":"3","errorMessage":"Resource not found"}"#,
+    );
+    let error = daemon
+        .download("https://example.com/missing.dmg", &destination)
+        .unwrap_err();
+    assert!(error.to_string().contains("Resource not found"), "{error}");
+    failed.remove();
+
+    mock_rpc(
+        &mut server,
+        "aria2.tellStatus",
+        r#"{"status":"error","errorCode":"9","errorMessage":"No space left on device"}"#,
+    );
+    let error = daemon
+        .download("https://example.com/android-studio.dmg", &destination)
+        .unwrap_err();
+    assert!(
+        matches!(error, AstudiosError::InsufficientResources(_)),
+        "{error}"
+    );
+}
+
+/// Test that `list` reads the feed named by ASTUDIOS_RELEASES_FEED_URL
+#[test]
+fn test_list_against_mock_feed() {
+    let home = tempfile::tempdir().unwrap();
+    let mut server = Server::new();
+    server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML)
+        .create();
+
+    let output = astudios(home.path(), Some(&server))
+        .args(["list", "--all-platforms"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2024.2.1.11"));
+    assert!(stdout.contains("2024.3.1.2"));
+}
+
+/// Test that `info` flags releases older than the configured threshold
+#[test]
+fn test_info_flags_outdated_release() {
+    let home = tempfile::tempdir().unwrap();
+    let mut server = Server::new();
+    server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML)
+        .create();
+
+    let info = |months: &str| {
+        let output = astudios(home.path(), Some(&server))
+            .args(["info", "2024.2.1.11"])
+            .env("ASTUDIOS_OUTDATED_AFTER_MONTHS", months)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    // Released 2024-10-01: over a month old, but never ten thousand months
+    assert!(info("1").contains("Released: 2024-10-01 (outdated — consider upgrading)"));
+    assert!(!info("10000").contains("outdated"));
+    assert!(!info("0").contains("outdated"));
+}
+
+/// Test that `info` shows the parsed IntelliJ platform build, padding short
+/// builds and flagging ones that are not numeric
+#[test]
+fn test_info_platform_build() {
+    let home = tempfile::tempdir().unwrap();
+    let mut server = Server::new();
+    let feed = RELEASES_XML
+        .replace(
+            "<platformBuild>242.21829.142</platformBuild>",
+            "<platformBuild>242.21829</platformBuild>",
+        )
+        .replace(
+            "<platformBuild>243.22562.145</platformBuild>",
+            "<platformBuild>243.SNAPSHOT</platformBuild>",
+        );
+    server.mock("GET", "/releases.xml").with_body(feed).create();
+
+    let info = |version: &str| {
+        let output = astudios(home.path(), Some(&server))
+            .args(["info", version])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    assert!(info("2024.2.1.11").contains("Platform: 2024.2 (242.21829.0)"));
+    assert!(info("2024.3.1.2").contains("Platform: 243.SNAPSHOT (unrecognized platform build)"));
+}
+
+/// Test that `info --json` tells whether a release is the newest in its channel
+#[test]
+fn test_info_json_latest_in_channel() {
+    let home = tempfile::tempdir().unwrap();
+    let mut server = Server::new();
+    // Both releases in one channel, so only the newer one is the latest
+    server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML.replace("<channel>Canary</channel>", "<channel>Release</channel>"))
+        .create();
+
+    let info = |version: &str| -> serde_json::Value {
+        let output = astudios(home.path(), Some(&server))
+            .args(["info", version, "--json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    let newest = info("2024.3.1.2");
+    assert_eq!(newest["version"], "2024.3.1.2");
+    assert_eq!(newest["latest_in_channel"], true);
+    assert_eq!(newest["platform_build"], "243.22562.145");
+    assert_eq!(newest["platform_version"], "2024.3");
+    let older = info("2024.2.1.11");
+    assert_eq!(older["channel"], "Release");
+    assert_eq!(older["latest_in_channel"], false);
+}
+
+/// Test that `install --only-if-missing` succeeds without downloading when the
+/// build is already installed
+#[test]
+fn test_install_only_if_missing() {
+    let home = tempfile::tempdir().unwrap();
+    let apps = home.path().join("Apps");
+    create_fake_bundle(
+        &apps,
+        "Android Studio Ladybug.app",
+        "2024.2",
+        "AI-242.21829.142.2421.12409432",
+    );
+
+    let mut server = Server::new();
+    server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML)
+        .create();
+
+    let output = astudios(home.path(), Some(&server))
+        .args(["install", "2024.2.1.11", "--only-if-missing", "--directory"])
+        .arg(&apps)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2024.2.1.11 is already installed"));
+    assert!(!home.path().join("versions").join("2024.2.1.11").exists());
+}
+
+/// Test that `install --json-lines` keeps stdout for JSON records and writes
+/// its messages to stderr
+#[test]
+fn test_install_json_lines_keeps_stdout_clean() {
+    let home = tempfile::tempdir().unwrap();
+    let apps = home.path().join("Apps");
+    create_fake_bundle(
+        &apps,
+        "Android Studio Ladybug.app",
+        "2024.2",
+        "AI-242.21829.142.2421.12409432",
+    );
+
+    let mut server = Server::new();
+    server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML)
+        .create();
+
+    let output = astudios(home.path(), Some(&server))
+        .args([
+            "install",
+            "2024.2.1.11",
+            "--only-if-missing",
+            "--json-lines",
+            "--directory",
+        ])
+        .arg(&apps)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for line in stdout.lines() {
+        serde_json::from_str::<serde_json::Value>(line).unwrap();
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("2024.2.1.11 is already installed"),
+        "{stderr}"
+    );
+}
+
+/// Test that `install --strict-arch` refuses a build that is not native to this
+/// Mac before downloading anything
+#[test]
+fn test_install_strict_arch_refuses_foreign_build() {
+    let home = tempfile::tempdir().unwrap();
+    // Offer only the build for the other architecture
+    let feed = match MacArch::host() {
+        MacArch::Arm64 => RELEASES_XML.replace("-mac_arm.dmg", "-mac.dmg"),
+        MacArch::X86_64 => RELEASES_XML.to_string(),
+    };
+    let mut server = Server::new();
+    server.mock("GET", "/releases.xml").with_body(feed).create();
+
+    let output = astudios(home.path(), Some(&server))
+        .args(["install", "2024.2.1.11", "--skip-checks", "--strict-arch"])
+        .env("ASTUDIOS_APPS_DIR", home.path().join("Apps"))
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Platform error"), "{stderr}");
+    assert!(stderr.contains("does not run natively"), "{stderr}");
+    assert!(!home.path().join("versions").join("2024.2.1.11").exists());
+}
+
+/// Test that `list` tolerates a cache `install` and `download` refetch, that
+/// `update` always refetches and that `--max-age` overrides them all
+#[test]
+fn test_cache_age_per_command() {
+    let home = tempfile::tempdir().unwrap();
+    let apps = home.path().join("Apps");
+    create_fake_bundle(
+        &apps,
+        "Android Studio Ladybug.app",
+        "2024.2",
+        "AI-242.21829.142.2421.12409432",
+    );
+    let cache = home.path().join("cache").join("releases.json");
+    let age_cache = |secs: u64| {
+        let mut envelope: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&cache).unwrap()).unwrap();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        envelope["fetched_at"] = (now - secs).into();
+        fs::write(&cache, envelope.to_string()).unwrap();
+    };
+
+    let mut server = Server::new();
+    let mut run = |args: &[&str], fetches: usize| {
+        let feed = server
+            .mock("GET", "/releases.xml")
+            .with_body(RELEASES_XML)
+            .expect(fetches)
+            .create();
+        let output = astudios(home.path(), Some(&server))
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{args:?}: {output:?}");
+        feed.assert();
+        feed.remove();
+    };
+
+    // A fresh cache is fetched anyway by update
+    run(&["update"], 1);
+    run(&["update"], 1);
+
+    // Two hours old: fine for list, too old for install and download
+    age_cache(2 * 60 * 60);
+    run(&["list"], 0);
+    run(
+        &["download", "2024.2.1.11", "--probe", "--all-platforms"],
+        1,
+    );
+    age_cache(2 * 60 * 60);
+    let apps = apps.to_str().unwrap();
+    run(
+        &[
+            "install",
+            "2024.2.1.11",
+            "--only-if-missing",
+            "--directory",
+            apps,
+        ],
+        1,
+    );
+    age_cache(2 * 60 * 60);
+    run(
+        &[
+            "install",
+            "2024.2.1.11",
+            "--only-if-missing",
+            "--directory",
+            apps,
+            "--max-age",
+            "1d",
+        ],
+        0,
+    );
+
+    // Two days old: too old for list as well
+    age_cache(2 * 24 * 60 * 60);
+    run(&["list", "--max-age", "never"], 0);
+    run(&["list"], 1);
+}
+
+/// Test that misconfiguration is reported up front, all problems at once
+#[test]
+fn test_config_check_reports_every_problem() {
+    let home = tempfile::tempdir().unwrap();
+    let run = |args: &[&str]| {
+        astudios(home.path(), None)
+            .args(args)
+            .env(
+                "ASTUDIOS_RELEASES_FEED_URL",
+                "releases.example.com/feed.xml",
+            )
+            .env("ASTUDIOS_MOUNT_TIMEOUT", "soon")
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&["config", "check"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Releases feed: releases.example.com/feed.xml"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("2 problem(s)"), "{stderr}");
+    assert!(stderr.contains("is not a valid URL"), "{stderr}");
+    assert!(stderr.contains("ASTUDIOS_MOUNT_TIMEOUT"), "{stderr}");
+
+    // Other commands stop before doing anything
+    let output = run(&["list"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Configuration Error"));
+}
+
+/// Test that only commands that modify installations require writable directories
+#[test]
+fn test_unwritable_dirs_only_block_modifying_commands() {
+    let home = tempfile::tempdir().unwrap();
+    let file = home.path().join("file");
+    fs::write(&file, "").unwrap();
+    let run = |args: &[&str]| {
+        astudios(home.path(), None)
+            .args(args)
+            .env("ASTUDIOS_APPS_DIR", file.join("Applications"))
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&["installed"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("Configuration Error"), "{stderr}");
+
+    let output = run(&["install", "2024.1.1"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Configuration Error"), "{stderr}");
+    assert!(stderr.contains("is not a directory"), "{stderr}");
+}
+
+/// Test that `download --url` fetches an archive that is not in the feed
+#[test]
+fn test_download_from_url() {
+    let home = tempfile::tempdir().unwrap();
+    let directory = home.path().join("archives");
+    let mut server = Server::new();
+    server
+        .mock("GET", "/builds/android-studio-custom.dmg")
+        .with_body(FAKE_DMG)
+        .create();
+
+    let output = astudios(home.path(), None)
+        .arg("download")
+        .arg("--url")
+        .arg(format!("{}/builds/android-studio-custom.dmg", server.url()))
+        .arg("--checksum")
+        .arg(format!("sha256:{}", sha256_of(FAKE_DMG)))
+        .args(["--downloader", "reqwest", "--directory"])
+        .arg(&directory)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("WARNING"));
+    assert_eq!(
+        fs::read(directory.join("android-studio-custom.dmg")).unwrap(),
+        FAKE_DMG
+    );
+}
+
+/// Test that `which --json` reports the launcher symlink, and inactivity with success
+#[test]
+fn test_which_json() {
+    let home = tempfile::tempdir().unwrap();
+    let apps = home.path().join("Apps");
+    fs::create_dir_all(&apps).unwrap();
+    let which = || {
+        let output = astudios(home.path(), None)
+            .args(["which", "--json", "--applications-dir"])
+            .arg(&apps)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    assert_eq!(which(), serde_json::json!({ "active": false }));
+
+    let bundle = create_fake_bundle(
+        &apps,
+        "Android Studio Ladybug.app",
+        "2024.2",
+        "AI-242.21829.142.2421.12409432",
+    );
+
+    // Without a symlink the newest bundle is reported, but not as managed
+    let fallback = which();
+    assert_eq!(fallback["active"], true);
+    assert_eq!(fallback["managed"], false);
+    assert!(fallback.get("symlink_target").is_none());
+
+    std::os::unix::fs::symlink(&bundle, apps.join("Android Studio.app")).unwrap();
+    let report = which();
+    assert_eq!(report["active"], true);
+    assert_eq!(report["managed"], true);
+    assert_eq!(report["path"], bundle.to_str().unwrap());
+    assert_eq!(report["symlink_target"], bundle.to_str().unwrap());
+    assert_eq!(report["identifier"], "AI-242.21829.142.2421.12409432");
+    assert_eq!(report["short_version"], "2024.2");
+}
+
+/// Test that uninstall refuses without a terminal to confirm on, unless told yes
+#[test]
+fn test_uninstall_requires_confirmation() {
+    let home = tempfile::tempdir().unwrap();
+    let apps = home.path().join("Apps");
+    let bundle = create_fake_bundle(
+        &apps,
+        "Android Studio 2024.2.app",
+        "2024.2",
+        "AI-242.21829.142.2421.12409432",
+    );
+    let uninstall = |extra_args: &[&str]| {
+        astudios(home.path(), None)
+            .args(["uninstall", "2024.2"])
+            .args(extra_args)
+            .env("ASTUDIOS_APPS_DIR", &apps)
+            .output()
+            .unwrap()
+    };
+
+    let refused = uninstall(&[]);
+    assert!(!refused.status.success());
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("without confirmation"));
+    assert!(bundle.exists());
+
+    assert!(uninstall(&["--yes"]).status.success());
+    assert!(!bundle.exists());
+}
+
+/// Test that verify flags an incomplete bundle, and that repairing skips a
+/// build the feed does not have
+#[test]
+fn test_verify_repair_skips_unknown_build() {
+    let home = tempfile::tempdir().unwrap();
+    let apps = home.path().join("Apps");
+    let bundle = create_fake_bundle(
+        &apps,
+        "Android Studio 2023.1.app",
+        "2023.1",
+        "AI-231.9392.1.2311.11076708",
+    );
+    fs::remove_dir_all(bundle.join("Contents").join("MacOS")).unwrap();
+    let mut server = Server::new();
+    server
+        .mock("GET", "/releases.xml")
+        .with_body(RELEASES_XML)
+        .create();
+    let verify = |extra_args: &[&str]| {
+        astudios(home.path(), Some(&server))
+            .arg("verify")
+            .args(extra_args)
+            .env("ASTUDIOS_APPS_DIR", &apps)
+            .output()
+            .unwrap()
+    };
+
+    let checked = verify(&[]);
+    assert!(!checked.status.success());
+    let stdout = String::from_utf8_lossy(&checked.stdout);
+    assert!(stdout.contains("Required directory missing"));
+    assert!(String::from_utf8_lossy(&checked.stderr).contains("verify --repair"));
+
+    let repaired = verify(&["--repair"]);
+    assert!(!repaired.status.success());
+    let stdout = String::from_utf8_lossy(&repaired.stdout);
+    assert!(stdout.contains("build AI-231.9392.1.2311.11076708 is not in the releases feed"));
+    assert!(bundle.join("Contents").join("Info.plist").exists());
+}
+
+/// Test that `installed --include-toolbox` lists Toolbox bundles as such, and
+/// that uninstall leaves them alone
+#[test]
+fn test_installed_includes_toolbox() {
+    let home = tempfile::tempdir().unwrap();
+    let apps = home.path().join("Apps");
+    let toolbox = home.path().join("Toolbox");
+    fs::create_dir_all(&apps).unwrap();
+    let bundle = create_fake_bundle(
+        &toolbox
+            .join("AndroidStudio")
+            .join("ch-0")
+            .join("242.21829.142.2421.12409432"),
+        "Android Studio.app",
+        "2024.2",
+        "AI-242.21829.142.2421.12409432",
+    );
+    let run = |args: &[&str]| {
+        astudios(home.path(), None)
+            .args(args)
+            .env("ASTUDIOS_APPS_DIR", &apps)
+            .env("ASTUDIOS_TOOLBOX_DIR", &toolbox)
+            .output()
+            .unwrap()
+    };
+
+    let without = run(&["installed", "--json"]);
+    let report: serde_json::Value = serde_json::from_slice(&without.stdout).unwrap();
+    assert_eq!(report["installations"].as_array().unwrap().len(), 0);
+
+    let with = run(&["installed", "--json", "--include-toolbox"]);
+    let report: serde_json::Value = serde_json::from_slice(&with.stdout).unwrap();
+    let installations = report["installations"].as_array().unwrap();
+    assert_eq!(installations.len(), 1);
+    assert_eq!(installations[0]["toolbox"], true);
+    assert_eq!(installations[0]["path"], bundle.to_str().unwrap());
+
+    let listed = run(&["installed", "--include-toolbox"]);
+    assert!(String::from_utf8_lossy(&listed.stdout).contains("(Toolbox)"));
+
+    let which = run(&["which", "--json", "--include-toolbox"]);
+    let report: serde_json::Value = serde_json::from_slice(&which.stdout).unwrap();
+    assert_eq!(report["toolbox"], true);
+    assert_eq!(report["managed"], false);
+
+    assert!(!run(&["uninstall", "2024.2", "--yes"]).status.success());
+    assert!(bundle.exists());
+}
@@ -1,4 +1,4 @@
-use astudios::error::AstudiosError;
+use astudios::error::{AstudiosError, ExtractionKind};
 use insta::assert_yaml_snapshot;
 
 /// Test AstudiosError variants and their display messages
@@ -9,7 +9,7 @@ fn test_error_variants() {
         AstudiosError::VersionNotFound("2023.1.1".to_string()),
         AstudiosError::Download("Download failed".to_string()),
         AstudiosError::Installation("Installation failed".to_string()),
-        AstudiosError::Extraction("Extraction failed".to_string()),
+        AstudiosError::Extraction(ExtractionKind::Mount, "Extraction failed".to_string()),
         AstudiosError::Config("Configuration error".to_string()),
         AstudiosError::PrerequisiteNotMet("Prerequisite check failed".to_string()),
         AstudiosError::InsufficientResources("Not enough resources".to_string()),
@@ -20,6 +20,7 @@ fn test_error_variants() {
         AstudiosError::Cache("Cache error".to_string()),
         AstudiosError::Parse("Parse error".to_string()),
         AstudiosError::DownloaderNotFound("aria2 not found".to_string()),
+        AstudiosError::Cancelled("download of android-studio.dmg".to_string()),
     ];
 
     let error_messages: Vec<(String, String)> = errors
@@ -30,7 +31,7 @@ fn test_error_variants() {
                 AstudiosError::VersionNotFound(_) => "VersionNotFound",
                 AstudiosError::Download(_) => "Download",
                 AstudiosError::Installation(_) => "Installation",
-                AstudiosError::Extraction(_) => "Extraction",
+                AstudiosError::Extraction(..) => "Extraction",
                 AstudiosError::Network(_) => "Network",
                 AstudiosError::Config(_) => "Config",
                 AstudiosError::PrerequisiteNotMet(_) => "PrerequisiteNotMet",
@@ -46,6 +47,7 @@ fn test_error_variants() {
                 AstudiosError::Utf8(_) => "Utf8",
                 AstudiosError::Zip(_) => "Zip",
                 AstudiosError::SystemTime(_) => "SystemTime",
+                AstudiosError::Cancelled(_) => "Cancelled",
             };
             (variant_name.to_string(), e.to_string())
         })
@@ -114,7 +116,10 @@ fn test_error_context() {
         ),
         (
             "extraction_failure",
-            AstudiosError::Extraction("Failed to extract DMG: Invalid archive format".to_string()),
+            AstudiosError::Extraction(
+                ExtractionKind::Unsupported,
+                "Failed to extract DMG: Invalid archive format".to_string(),
+            ),
         ),
         (
             "prerequisite_failure",
@@ -235,3 +240,27 @@ fn test_system_time_error_conversion() {
 
     assert_yaml_snapshot!("system_time_error_conversion", error_info);
 }
+
+/// Test that only mount and signature failures suggest downloading the archive again
+#[test]
+fn test_extraction_kinds() {
+    let kinds: Vec<(String, bool)> = [
+        ExtractionKind::Mount,
+        ExtractionKind::MissingBundle,
+        ExtractionKind::Copy,
+        ExtractionKind::Unsupported,
+        ExtractionKind::Signature,
+        ExtractionKind::ToolMissing,
+        ExtractionKind::Timeout,
+    ]
+    .into_iter()
+    .map(|kind| {
+        (
+            AstudiosError::Extraction(kind, "details".to_string()).to_string(),
+            kind.redownload_may_help(),
+        )
+    })
+    .collect();
+
+    assert_yaml_snapshot!("extraction_kinds", kinds);
+}
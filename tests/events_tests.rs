@@ -0,0 +1,30 @@
+use astudios::events::InstallEvent;
+use std::path::PathBuf;
+
+/// Test the JSON records streamed by `install --json-lines`
+#[test]
+fn test_install_event_json_lines() {
+    let lines: Vec<String> = [
+        InstallEvent::Download { pct: 42 },
+        InstallEvent::Extract,
+        InstallEvent::Installed {
+            path: PathBuf::from("/Applications/Android Studio 2024.2.app"),
+        },
+        InstallEvent::Failed {
+            error: "Version '1999.1' not found".to_string(),
+        },
+    ]
+    .iter()
+    .map(InstallEvent::to_json_line)
+    .collect();
+
+    assert_eq!(
+        lines,
+        vec![
+            r#"{"event":"download","pct":42}"#,
+            r#"{"event":"extract"}"#,
+            r#"{"event":"installed","path":"/Applications/Android Studio 2024.2.app"}"#,
+            r#"{"event":"failed","error":"Version '1999.1' not found"}"#,
+        ]
+    );
+}
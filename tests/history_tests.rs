@@ -0,0 +1,61 @@
+use astudios::error::AstudiosError;
+use astudios::history::{History, HistoryAction, HistoryEntry, HistoryResult, format_utc};
+use std::fs;
+
+/// Test that entries are appended in order and read back, skipping damaged lines
+#[test]
+fn test_history_append_and_recent() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("history.jsonl");
+    let history = History::at(path.clone());
+    assert!(history.recent(10).unwrap().is_empty());
+
+    let installed = HistoryEntry::new(HistoryAction::Install, "2024.2.1", &Ok::<(), _>(()));
+    let failed = HistoryEntry::new(
+        HistoryAction::Uninstall,
+        "2023.3.1",
+        &Err::<(), _>(AstudiosError::VersionNotFound("2023.3.1".to_string())),
+    );
+    history.append(&installed).unwrap();
+    // A line cut short by a crash
+    let mut log = fs::read_to_string(&path).unwrap();
+    log.push_str("{\"timestamp\":\n");
+    fs::write(&path, log).unwrap();
+    history.append(&failed).unwrap();
+
+    let entries = history.recent(10).unwrap();
+    assert_eq!(entries, vec![installed.clone(), failed.clone()]);
+    assert_eq!(entries[1].result, HistoryResult::Failure);
+    assert_eq!(
+        entries[1].error.as_deref(),
+        Some("Version '2023.3.1' not found")
+    );
+    assert_eq!(history.recent(1).unwrap(), vec![failed]);
+}
+
+/// Test that a full log is rotated and the rotated entries are still listed
+#[test]
+fn test_history_rotation() {
+    let temp = tempfile::tempdir().unwrap();
+    let history = History::at(temp.path().join("history.jsonl")).with_max_bytes(1);
+
+    let entries: Vec<HistoryEntry> = ["2024.1.1", "2024.2.1", "2024.3.1"]
+        .into_iter()
+        .map(|version| HistoryEntry::new(HistoryAction::Use, version, &Ok::<(), _>(())))
+        .collect();
+    for entry in &entries {
+        history.append(entry).unwrap();
+    }
+
+    // Only the current and one rotated log are kept
+    assert!(history.rotated_path().exists());
+    assert_eq!(history.recent(10).unwrap(), entries[1..].to_vec());
+}
+
+/// Test UTC formatting of history timestamps
+#[test]
+fn test_format_utc() {
+    assert_eq!(format_utc(0), "1970-01-01 00:00:00");
+    assert_eq!(format_utc(951_782_400), "2000-02-29 00:00:00");
+    assert_eq!(format_utc(1_760_620_245), "2025-10-16 13:10:45");
+}
@@ -0,0 +1,728 @@
+mod common;
+
+use astudios::blobs::BlobStore;
+use astudios::error::AstudiosError;
+use astudios::installer::{ExtractionSemaphore, Installer, wait_with_timeout};
+use astudios::model::{AndroidStudio, InstalledAndroidStudio, ReleaseChannel};
+use std::{
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use common::create_fake_bundle;
+
+/// Create an installer whose directories live under a temporary directory
+fn temp_installer(root: &Path) -> Installer {
+    Installer::with_directories(root.join("versions"), root.join("Applications")).unwrap()
+}
+
+/// Test copying an app bundle whose path contains spaces and non-ASCII characters
+#[test]
+fn test_copy_app_bundle_with_special_characters() {
+    let temp = tempfile::tempdir().unwrap();
+    let source_dir = temp.path().join("Mounted Volume ☕");
+    let app_path = source_dir.join("Android Studio Ünïcode Preview.app");
+    fs::create_dir_all(app_path.join("Contents").join("MacOS")).unwrap();
+    fs::write(app_path.join("Contents").join("Info.plist"), "<plist/>").unwrap();
+
+    let destination = temp.path().join("extracted dir (copy)");
+    fs::create_dir_all(&destination).unwrap();
+
+    let copied = Installer::copy_app_bundle(&app_path, &destination).unwrap();
+
+    assert_eq!(
+        copied,
+        destination.join("Android Studio Ünïcode Preview.app")
+    );
+    assert!(copied.join("Contents").join("MacOS").is_dir());
+    assert!(copied.join("Contents").join("Info.plist").is_file());
+}
+
+/// Test spotting an existing bundle whose name differs from the new one only in case
+#[test]
+fn test_find_case_variant() {
+    let temp = tempfile::tempdir().unwrap();
+    let apps = temp.path();
+    fs::create_dir_all(apps.join("android studio 2024.2.app")).unwrap();
+    fs::create_dir_all(apps.join("Android Studio 2024.3.app")).unwrap();
+
+    assert_eq!(
+        Installer::find_case_variant(apps, "Android Studio 2024.2.app"),
+        Some(apps.join("android studio 2024.2.app"))
+    );
+    // An exact match is an ordinary reinstall, not a case collision
+    assert_eq!(
+        Installer::find_case_variant(apps, "Android Studio 2024.3.app"),
+        None
+    );
+    assert_eq!(
+        Installer::find_case_variant(apps, "Android Studio 2025.1.app"),
+        None
+    );
+
+    // The probe reflects the volume, and leaves nothing behind
+    let case_sensitive = !apps.join("ANDROID STUDIO 2024.3.APP").exists();
+    assert_eq!(Installer::is_case_insensitive(apps), !case_sensitive);
+    assert!(!apps.join(".astudios-case-probe").exists());
+}
+
+/// Test that verification checks the launcher named by CFBundleExecutable
+#[test]
+fn test_verify_installation_requires_executable_launcher() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path());
+    let app_path = create_fake_bundle(
+        temp.path(),
+        "Android Studio 2024.2.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+    let launcher = app_path.join("Contents").join("MacOS").join("studio");
+
+    installer.verify_installation(&app_path).unwrap();
+
+    fs::set_permissions(&launcher, fs::Permissions::from_mode(0o644)).unwrap();
+    let error = installer.verify_installation(&app_path).unwrap_err();
+    assert!(error.to_string().contains("Launcher is not executable"));
+
+    fs::remove_file(&launcher).unwrap();
+    let error = installer.verify_installation(&app_path).unwrap_err();
+    assert!(error.to_string().contains("Launcher executable missing"));
+}
+
+/// Test that waiting on a hung process gives up at the timeout, and not on one that exits
+#[test]
+fn test_wait_with_timeout() {
+    let mut hung = Command::new("sleep").arg("5").spawn().unwrap();
+    let started = std::time::Instant::now();
+    let status = wait_with_timeout(&mut hung, Duration::from_millis(100)).unwrap();
+    assert!(status.is_none());
+    assert!(started.elapsed() < Duration::from_secs(2));
+    hung.kill().unwrap();
+    hung.wait().unwrap();
+
+    let mut quick = Command::new("true").spawn().unwrap();
+    let status = wait_with_timeout(&mut quick, Duration::from_secs(5)).unwrap();
+    assert!(status.is_some_and(|status| status.success()));
+}
+
+/// Test that concurrent extractions never hold more mounts than the limit allows
+#[test]
+fn test_extraction_semaphore_serializes_mounts() {
+    let semaphore = Arc::new(ExtractionSemaphore::new(1));
+    let active = Arc::new(AtomicUsize::new(0));
+    let max_active = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..2)
+        .map(|_| {
+            let semaphore = Arc::clone(&semaphore);
+            let active = Arc::clone(&active);
+            let max_active = Arc::clone(&max_active);
+            thread::spawn(move || {
+                let _permit = semaphore.acquire();
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                max_active.fetch_max(now, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(50));
+                active.fetch_sub(1, Ordering::SeqCst);
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(max_active.load(Ordering::SeqCst), 1);
+}
+
+/// Test that a product-info.json with a BOM and trailing data is still recognized
+#[test]
+fn test_installed_bundle_with_bom_product_info() {
+    let temp = tempfile::tempdir().unwrap();
+    let app_path = create_fake_bundle(
+        temp.path(),
+        "Android Studio.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+    fs::write(
+        app_path
+            .join("Contents")
+            .join("Resources")
+            .join("product-info.json"),
+        "\u{feff}{\"name\": \"Android Studio\", \"version\": \"2024.2\", \"buildNumber\": \"242.23339.11.2421.12483815\"}\n\0\0",
+    )
+    .unwrap();
+
+    let installed = InstalledAndroidStudio::new(app_path)
+        .unwrap()
+        .expect("bundle with BOM-prefixed product-info.json should be recognized");
+    assert_eq!(installed.version.short_version, "2024.2");
+}
+
+/// Test that a bundle with only an Info.plist is recognized from its build version
+#[test]
+fn test_installed_bundle_without_product_info() {
+    let temp = tempfile::tempdir().unwrap();
+    let app_path = create_fake_bundle(
+        temp.path(),
+        "Android Studio.app",
+        "2023.1",
+        "AI-231.9392.1.2311.11076708",
+    );
+    fs::remove_file(
+        app_path
+            .join("Contents")
+            .join("Resources")
+            .join("product-info.json"),
+    )
+    .unwrap();
+
+    let installed = InstalledAndroidStudio::new(app_path)
+        .unwrap()
+        .expect("bundle without product-info.json should be recognized");
+    assert_eq!(installed.version.short_version, "2023.1");
+    assert_eq!(installed.version.product_code, "AI");
+    assert_eq!(installed.version.build_number, "231.9392.1.2311.11076708");
+    assert_eq!(installed.version.product_name, "Android Studio");
+}
+
+/// Test that bundles with blank version keys are not treated as installations
+#[test]
+fn test_installed_bundle_with_empty_versions() {
+    let temp = tempfile::tempdir().unwrap();
+    let no_short_version = create_fake_bundle(
+        temp.path(),
+        "Android Studio Blank.app",
+        "",
+        "AI-242.23339.11.2421.12483815",
+    );
+    let no_build = create_fake_bundle(temp.path(), "Android Studio Spaces.app", "2024.2", "  ");
+
+    assert!(
+        InstalledAndroidStudio::new(no_short_version)
+            .unwrap()
+            .is_none()
+    );
+    assert!(InstalledAndroidStudio::new(no_build).unwrap().is_none());
+}
+
+/// Test that installing into the managed versions directory is refused
+#[test]
+fn test_refuses_install_into_versions_dir() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path());
+
+    let nested = temp
+        .path()
+        .join("versions")
+        .join("..")
+        .join("versions")
+        .join("apps");
+    let result = installer.install_version_with_checks(
+        "2024.2.1.11",
+        "Android Studio Ladybug",
+        Some(nested.to_str().unwrap()),
+        false,
+    );
+    assert!(matches!(result, Err(AstudiosError::Path(_))));
+
+    assert!(
+        installer
+            .ensure_unmanaged_target(&temp.path().join("Applications"))
+            .is_ok()
+    );
+}
+
+/// Test the active-version fallback used when the symlink is absent
+#[test]
+fn test_default_studio_without_symlink() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path());
+    let applications = temp.path().join("Applications");
+
+    assert!(installer.default_studio(None).unwrap().is_none());
+
+    let older = create_fake_bundle(
+        &applications,
+        "Android Studio 2024.1.app",
+        "2024.1",
+        "AI-241.18034.62.2411.12071903",
+    );
+    let newer = create_fake_bundle(
+        &applications,
+        "Android Studio 2024.2.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+    let hour_ago = std::time::SystemTime::now() - Duration::from_secs(3600);
+    fs::File::open(&older)
+        .unwrap()
+        .set_modified(hour_ago)
+        .unwrap();
+
+    assert!(installer.get_active_studio().unwrap().is_none());
+    assert_eq!(installer.default_studio(None).unwrap().unwrap().path, newer);
+    assert_eq!(
+        installer
+            .default_studio(Some(&older))
+            .unwrap()
+            .unwrap()
+            .path,
+        older
+    );
+}
+
+/// Test switching the symlink to a bundle outside the applications directory
+#[test]
+fn test_switch_to_external_bundle_by_path() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path());
+    let applications = temp.path().join("Applications");
+    fs::create_dir_all(&applications).unwrap();
+
+    let not_a_bundle = temp.path().join("Toolbox").join("Empty.app");
+    fs::create_dir_all(&not_a_bundle).unwrap();
+    assert!(installer.switch_to_path(&not_a_bundle).is_err());
+
+    let external = create_fake_bundle(
+        &temp.path().join("Toolbox"),
+        "Android Studio.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+    let installed = installer.switch_to_path(&external).unwrap();
+    assert_eq!(installed.path, external.canonicalize().unwrap());
+
+    let active = installer.get_active_studio().unwrap().unwrap();
+    assert_eq!(active.path, installed.path);
+}
+
+/// Test that per-channel mode keeps a separate launcher symlink for each channel
+#[test]
+fn test_per_channel_symlinks() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path()).with_per_channel_symlinks(true);
+    let applications = temp.path().join("Applications");
+    fs::create_dir_all(&applications).unwrap();
+
+    let versions = temp.path().join("versions");
+    let stable = create_fake_bundle(
+        &versions,
+        "Android Studio 2024.2.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+    let canary = create_fake_bundle(
+        &versions,
+        "Android Studio Canary 2024.3.app",
+        "2024.3",
+        "AI-243.12818.47.2431.12569813",
+    );
+    let stable = installer.switch_to_path(&stable).unwrap().path;
+    let canary = installer.switch_to_path(&canary).unwrap().path;
+
+    assert_eq!(
+        fs::read_link(applications.join("Android Studio.app")).unwrap(),
+        stable
+    );
+    assert_eq!(
+        fs::read_link(applications.join("Android Studio Canary.app")).unwrap(),
+        canary
+    );
+    assert_eq!(installer.get_active_studio().unwrap().unwrap().path, stable);
+    let active: Vec<(ReleaseChannel, PathBuf)> = installer
+        .active_studios()
+        .unwrap()
+        .into_iter()
+        .map(|(channel, installed)| (channel, installed.path))
+        .collect();
+    assert_eq!(
+        active,
+        vec![
+            (ReleaseChannel::Release, stable.clone()),
+            (ReleaseChannel::Canary, canary.clone())
+        ]
+    );
+
+    // An explicit channel picks the launcher regardless of the bundle's channel
+    let beta = temp_installer(temp.path()).with_symlink_channel(ReleaseChannel::Beta);
+    beta.switch_to_path(&canary).unwrap();
+    assert_eq!(beta.get_active_studio().unwrap().unwrap().path, canary);
+    assert_eq!(installer.get_active_studio().unwrap().unwrap().path, stable);
+
+    // A real bundle in place of a preview launcher is never removed
+    let launcher = applications.join("Android Studio Canary.app");
+    fs::remove_file(&launcher).unwrap();
+    fs::create_dir_all(launcher.join("Contents")).unwrap();
+    assert!(installer.switch_to_path(&canary).is_err());
+    assert!(launcher.join("Contents").is_dir());
+}
+
+/// Test that planning an uninstall lists what would go without removing it
+#[test]
+fn test_plan_uninstall() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path());
+    let applications = temp.path().join("Applications");
+    let bundle = create_fake_bundle(
+        &applications,
+        "Android Studio 2024.2.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+    let symlink = applications.join("Android Studio.app");
+    std::os::unix::fs::symlink(&bundle, &symlink).unwrap();
+
+    let version_dir = temp.path().join("versions").join("2024.2");
+    fs::create_dir_all(&version_dir).unwrap();
+    fs::write(version_dir.join("studio.dmg"), "archive").unwrap();
+    fs::write(version_dir.join("install.log"), "log").unwrap();
+    let store = BlobStore::with_dir(temp.path().join("blobs")).unwrap();
+    let digest = store.adopt(&version_dir.join("studio.dmg")).unwrap();
+
+    let plan = installer.plan_uninstall("2024.2", false, &store).unwrap();
+    assert_eq!(plan.installation.path, bundle);
+    assert_eq!(plan.symlinks, vec![symlink.clone()]);
+    assert_eq!(plan.version_dir, Some(version_dir.clone()));
+    // The archive is counted once, as the blob it shares with the version directory
+    assert_eq!(plan.version_dir_bytes, 3);
+    assert_eq!(plan.blobs, vec![(store.blob_path(&digest), 7)]);
+    assert_eq!(
+        plan.reclaimable_bytes(),
+        plan.bundle_bytes + plan.version_dir_bytes + 7
+    );
+    assert!(bundle.exists() && symlink.is_symlink() && version_dir.exists());
+    assert!(store.blob_path(&digest).exists());
+
+    let kept = installer.plan_uninstall("2024.2", true, &store).unwrap();
+    assert!(kept.keep_files && kept.blobs.is_empty());
+    assert_eq!(kept.reclaimable_bytes(), kept.bundle_bytes);
+}
+
+/// Test that a launcher symlink with a relative target is recognized as active
+/// and removed along with the bundle it points at
+#[test]
+fn test_relative_launcher_symlink() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path());
+    let applications = temp.path().join("Applications");
+    let bundle = create_fake_bundle(
+        &applications,
+        "Android Studio 2024.2.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+    let symlink = applications.join("Android Studio.app");
+    std::os::unix::fs::symlink("../Applications/./Android Studio 2024.2.app/", &symlink).unwrap();
+
+    let active = installer.get_active_studio().unwrap().unwrap();
+    assert_eq!(active.identifier(), "AI-242.23339.11.2421.12483815");
+
+    let store = BlobStore::with_dir(temp.path().join("blobs")).unwrap();
+    let plan = installer.plan_uninstall("2024.2", false, &store).unwrap();
+    assert_eq!(plan.installation.path, bundle);
+    assert_eq!(plan.symlinks, vec![symlink]);
+}
+
+/// Test that only dead staging artifacts are cleaned, and never next to a partial download
+#[test]
+fn test_clean_stale_staging() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path());
+    let versions = temp.path().join("versions");
+
+    // Nothing but a failed run's leftovers: the whole directory goes
+    let failed = versions.join("2024.1");
+    fs::create_dir_all(failed.join("extracted")).unwrap();
+    fs::write(failed.join("android-studio-2024.1-mac.dmg"), "").unwrap();
+
+    // A finished archive and its extraction stay
+    let kept = versions.join("2024.2");
+    fs::create_dir_all(kept.join("extracted")).unwrap();
+    fs::write(kept.join("extracted").join(".extraction-complete"), "").unwrap();
+    fs::write(kept.join("android-studio-2024.2-mac.dmg"), "archive").unwrap();
+    fs::write(kept.join("install.log"), "").unwrap();
+
+    // A download in progress is never touched
+    let downloading = versions.join("2024.3");
+    fs::create_dir_all(downloading.join("extracted")).unwrap();
+    fs::write(downloading.join("android-studio-2024.3-mac.dmg"), "").unwrap();
+    fs::write(
+        downloading.join("android-studio-2024.3-mac.dmg.4242.part"),
+        "",
+    )
+    .unwrap();
+
+    // Nor is one aria2 is writing straight to the archive, tracked by its control file
+    let aria2 = versions.join("2024.4");
+    fs::create_dir_all(&aria2).unwrap();
+    fs::write(aria2.join("android-studio-2024.4-mac.dmg"), "").unwrap();
+    fs::write(aria2.join("android-studio-2024.4-mac.dmg.aria2"), "").unwrap();
+
+    let mut removed = installer.clean_stale_staging();
+    removed.sort();
+    assert_eq!(
+        removed,
+        vec![
+            failed.clone(),
+            failed.join("android-studio-2024.1-mac.dmg"),
+            failed.join("extracted"),
+        ]
+    );
+    assert!(!failed.exists());
+    assert!(kept.join("extracted").join(".extraction-complete").exists());
+    assert!(kept.join("install.log").exists());
+    assert!(downloading.join("extracted").exists());
+    assert!(downloading.join("android-studio-2024.3-mac.dmg").exists());
+    assert!(aria2.join("android-studio-2024.4-mac.dmg").exists());
+
+    assert!(installer.clean_stale_staging().is_empty());
+}
+
+/// Test that archives beyond the newest per track are evicted, except an installed version's
+#[test]
+fn test_evict_archives() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path());
+    create_fake_bundle(
+        &temp.path().join("Applications"),
+        "Android Studio 2024.2.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+    let versions = temp.path().join("versions");
+    let archive = |version: &str, contents: &str| {
+        let dir = versions.join(version);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("android-studio-{version}-mac.dmg"));
+        fs::write(&path, contents).unwrap();
+        path
+    };
+    let last_used = |path: &Path, age_days: u64| {
+        let used = SystemTime::now() - Duration::from_secs(age_days * 86_400);
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_times(fs::FileTimes::new().set_accessed(used).set_modified(used))
+            .unwrap();
+    };
+    let installed = archive("2024.2", "installed");
+    let older = archive("2024.2.1.11", "older");
+    let newest = archive("2024.2.2.13", "newest");
+    let other_track = archive("2023.1.1", "other");
+    fs::write(versions.join("2024.2.2.13").join("next.dmg.4242.part"), "").unwrap();
+    let store = BlobStore::with_dir(temp.path().join("blobs")).unwrap();
+    // Hashing reads the archive, so its last use is set afterwards
+    let digest = store.adopt(&older).unwrap();
+    for (path, age_days) in [
+        (&installed, 30),
+        (&older, 20),
+        (&newest, 1),
+        (&other_track, 40),
+    ] {
+        last_used(path, age_days);
+    }
+
+    let eviction = installer.evict_archives(1, &store).unwrap();
+    assert_eq!(eviction.evicted, vec![older.clone()]);
+    // The archive's only other link was in the blob store, which is pruned
+    assert_eq!(eviction.reclaimed_bytes, 5);
+    assert!(!older.exists() && !store.blob_path(&digest).exists());
+    assert!(installed.exists() && newest.exists() && other_track.exists());
+
+    assert!(
+        installer
+            .evict_archives(1, &store)
+            .unwrap()
+            .evicted
+            .is_empty()
+    );
+}
+
+/// Test that a partially copied bundle at the target is replaced by a complete copy
+#[test]
+fn test_install_app_bundle_replaces_partial_copy() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = create_fake_bundle(
+        &temp.path().join("extracted"),
+        "Android Studio.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+
+    // An interrupted copy: some leftovers, no Info.plist
+    let target = temp
+        .path()
+        .join("Applications")
+        .join("Android Studio 2024.2.app");
+    fs::create_dir_all(target.join("Contents").join("Resources")).unwrap();
+    fs::write(target.join("Contents").join("stale.tmp"), "partial").unwrap();
+
+    Installer::install_app_bundle(&source, &target).unwrap();
+
+    assert!(target.join("Contents").join("Info.plist").is_file());
+    assert!(!target.join("Contents").join("stale.tmp").exists());
+    let installed = InstalledAndroidStudio::new(target).unwrap().unwrap();
+    assert_eq!(installed.version.short_version, "2024.2");
+}
+
+/// Test finding the volumes a disk image is still mounted at from `hdiutil info`
+#[test]
+fn test_parse_hdiutil_info() {
+    let temp = tempfile::tempdir().unwrap();
+    let image = temp.path().join("android-studio-mac_arm.dmg");
+    let other = temp.path().join("other.dmg");
+    fs::write(&image, b"").unwrap();
+
+    let info = format!(
+        "framework       : 671.100.2\n\
+         driver          : 671.100.2\n\
+         ================================================\n\
+         image-path      : {}\n\
+         writeable       : FALSE\n\
+         /dev/disk4\tGUID_partition_scheme\t\n\
+         /dev/disk4s1\tApple_HFS\t/private/var/folders/xy/astudios-mount-AbC123\n\
+         ================================================\n\
+         image-path      : {}\n\
+         /dev/disk5\tGUID_partition_scheme\t\n\
+         /dev/disk5s1\tApple_HFS\t/Volumes/Other\n",
+        image.display(),
+        other.display()
+    );
+
+    assert_eq!(
+        Installer::parse_hdiutil_info(&info, &image),
+        vec![PathBuf::from(
+            "/private/var/folders/xy/astudios-mount-AbC123"
+        )]
+    );
+    assert!(Installer::parse_hdiutil_info("framework : 1\n", &image).is_empty());
+}
+
+/// Test that bundles are recognized by their metadata whatever their name
+#[test]
+fn test_lists_bundles_with_custom_names() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path());
+    let applications = temp.path().join("Applications");
+    let custom = create_fake_bundle(
+        &applications,
+        "AS-2024.2-canary.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+    fs::create_dir_all(applications.join("Safari.app").join("Contents")).unwrap();
+
+    let paths: Vec<PathBuf> = installer
+        .list_installed_studios()
+        .unwrap()
+        .into_iter()
+        .map(|installed| installed.path)
+        .collect();
+    assert_eq!(paths, vec![custom]);
+}
+
+/// Test that bundles symlinked into the applications directory are listed once
+#[test]
+fn test_lists_symlinked_bundles() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path());
+    let applications = temp.path().join("Applications");
+
+    let local = create_fake_bundle(
+        &applications,
+        "Android Studio 2024.1.app",
+        "2024.1",
+        "AI-241.18034.62.2411.12071903",
+    );
+    let external = create_fake_bundle(
+        &temp.path().join("External"),
+        "Android Studio 2024.2.app",
+        "2024.2",
+        "AI-242.23339.11.2421.12483815",
+    );
+    std::os::unix::fs::symlink(&external, applications.join("Android Studio 2024.2.app")).unwrap();
+    // A second alias of the local bundle and the active symlink add no entries
+    std::os::unix::fs::symlink(&local, applications.join("Android Studio Stable.app")).unwrap();
+    std::os::unix::fs::symlink(&external, applications.join("Android Studio.app")).unwrap();
+
+    let paths: Vec<PathBuf> = installer
+        .list_installed_studios()
+        .unwrap()
+        .into_iter()
+        .map(|installed| installed.path)
+        .collect();
+    assert_eq!(paths, vec![external.canonicalize().unwrap(), local]);
+}
+
+/// Test that codenames resolve to installed bundles through their feed release names
+#[test]
+fn test_match_codename() {
+    let temp = tempfile::tempdir().unwrap();
+    let installer = temp_installer(temp.path());
+    let applications = temp.path().join("Applications");
+    create_fake_bundle(
+        &applications,
+        "Android Studio 2024.1.app",
+        "2024.1",
+        "AI-241.15989.150.2411.11948838",
+    );
+    create_fake_bundle(
+        &applications,
+        "Android Studio 2024.1.2.app",
+        "2024.1",
+        "AI-241.18034.62.2412.12266719",
+    );
+    let installations = installer.list_installed_studios().unwrap();
+
+    let release = |name: &str, build: &str| AndroidStudio {
+        name: name.to_string(),
+        build: build.to_string(),
+        version: String::new(),
+        channel: "Release".to_string(),
+        platform_build: String::new(),
+        platform_version: String::new(),
+        date: String::new(),
+        downloads: Vec::new(),
+    };
+    let releases = vec![
+        release(
+            "Android Studio Koala | 2024.1.1",
+            "AI-241.15989.150.2411.11948838",
+        ),
+        release(
+            "Android Studio Koala Feature Drop | 2024.1.2",
+            "AI-241.18034.62.2412.12266719",
+        ),
+        release(
+            "Android Studio Ladybug | 2024.2.1",
+            "AI-242.21829.142.2421.12409432",
+        ),
+    ];
+
+    let builds = |query: &str| -> Vec<String> {
+        Installer::match_codename(&installations, &releases, query)
+            .into_iter()
+            .map(|install| install.version.build_version.clone())
+            .collect()
+    };
+    assert_eq!(builds("koala").len(), 2);
+    assert_eq!(
+        builds("Koala Feature Drop"),
+        vec!["AI-241.18034.62.2412.12266719"]
+    );
+    // Not installed, a partial word, or just a number
+    assert!(builds("ladybug").is_empty());
+    assert!(builds("koal").is_empty());
+    assert!(builds("2").is_empty());
+}
@@ -0,0 +1,32 @@
+use astudios::json_file::{self, JsonFile};
+use astudios::state::{InstallRecord, State};
+use std::fs;
+
+/// Test that saving replaces the file whole and leaves no temporary files behind
+#[test]
+fn test_save_replaces_file_atomically() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("nested").join("state.json");
+    assert_eq!(State::load_from(&path).unwrap(), State::default());
+
+    let mut state = State::default();
+    state.record_install(
+        temp.path().join("Android Studio.app"),
+        InstallRecord {
+            version: "2024.2.1.11".to_string(),
+            verified: true,
+        },
+    );
+    state.save_to(&path).unwrap();
+    assert_eq!(State::load_from(&path).unwrap(), state);
+
+    // A longer file is replaced, not overwritten in place
+    fs::write(&path, " ".repeat(64 * 1024)).unwrap();
+    State::default().save_to(&path).unwrap();
+    assert_eq!(State::load_from(&path).unwrap(), State::default());
+
+    json_file::write_atomic(&path, b"{}").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+    let entries: Vec<_> = fs::read_dir(path.parent().unwrap()).unwrap().collect();
+    assert_eq!(entries.len(), 1);
+}
@@ -0,0 +1,399 @@
+use astudios::list::{AndroidStudioLister, CACHE_SCHEMA, CacheMaxAge, RefreshSummary};
+use astudios::model::{AndroidStudio, AndroidStudioReleasesList};
+use std::{fs, str::FromStr, time::Duration};
+
+const CACHED_RELEASES: &str = r#"{
+  "schema": 1,
+  "fetched_at": 1690329600,
+  "data": {
+    "@version": "1.0",
+    "item": [
+      {
+        "name": "Android Studio Hedgehog | 2023.1.1",
+        "build": "AI-231.9392.1.2311.11076708",
+        "version": "2023.1.1",
+        "channel": "Release",
+        "platformBuild": "231.9392.1",
+        "platformVersion": "2023.1.1",
+        "date": "2023-07-26",
+        "download": [
+          {
+            "link": "https://example.com/android-studio-2023.1.1-mac.dmg",
+            "size": "1024000000",
+            "checksum": "abc123"
+          }
+        ]
+      }
+    ]
+  }
+}"#;
+
+/// Test parsing of human-readable cache durations
+#[test]
+fn test_cache_max_age_parsing() {
+    let cases = [
+        ("0", CacheMaxAge::Within(Duration::ZERO)),
+        ("45", CacheMaxAge::Within(Duration::from_secs(45))),
+        ("45s", CacheMaxAge::Within(Duration::from_secs(45))),
+        ("30m", CacheMaxAge::Within(Duration::from_secs(30 * 60))),
+        ("2h", CacheMaxAge::Within(Duration::from_secs(2 * 60 * 60))),
+        ("1d", CacheMaxAge::Within(Duration::from_secs(24 * 60 * 60))),
+        ("never", CacheMaxAge::Never),
+        ("NEVER", CacheMaxAge::Never),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(CacheMaxAge::from_str(input).unwrap(), expected, "{input}");
+    }
+
+    for invalid in ["", "h", "1w", "-1h", "1.5h", "soon"] {
+        assert!(CacheMaxAge::from_str(invalid).is_err(), "{invalid}");
+    }
+}
+
+/// Test cache freshness decisions
+#[test]
+fn test_cache_max_age_freshness() {
+    let hour = Duration::from_secs(60 * 60);
+
+    assert!(!CacheMaxAge::Within(Duration::ZERO).is_fresh(Duration::ZERO));
+    assert!(CacheMaxAge::Within(hour).is_fresh(Duration::from_secs(59 * 60)));
+    assert!(!CacheMaxAge::Within(hour).is_fresh(hour));
+    assert!(CacheMaxAge::Never.is_fresh(Duration::from_secs(365 * 24 * 60 * 60)));
+}
+
+/// Test that a "never" max age serves an existing cache without fetching
+#[test]
+fn test_never_max_age_uses_existing_cache() {
+    let temp = tempfile::tempdir().unwrap();
+    fs::write(temp.path().join("releases.json"), CACHED_RELEASES).unwrap();
+
+    let lister = AndroidStudioLister::with_cache_dir(temp.path().to_path_buf())
+        .unwrap()
+        .with_max_age(CacheMaxAge::Never);
+    let releases = lister.get_releases().unwrap();
+
+    assert_eq!(releases.items.len(), 1);
+    assert_eq!(releases.items[0].version, "2023.1.1");
+}
+
+/// Test that caches written with another schema are treated as missing
+#[test]
+fn test_cache_schema_mismatch_is_a_miss() {
+    let temp = tempfile::tempdir().unwrap();
+    let cache_path = temp.path().join("releases.json");
+    let lister = AndroidStudioLister::with_cache_dir(temp.path().to_path_buf())
+        .unwrap()
+        .with_max_age(CacheMaxAge::Never);
+
+    fs::write(&cache_path, CACHED_RELEASES).unwrap();
+    assert!(lister.cache_age().unwrap().is_some());
+
+    let outdated = CACHED_RELEASES.replacen(
+        &format!("\"schema\": {CACHE_SCHEMA}"),
+        &format!("\"schema\": {}", CACHE_SCHEMA + 1),
+        1,
+    );
+    fs::write(&cache_path, outdated).unwrap();
+    assert_eq!(lister.cache_age().unwrap(), None);
+
+    // Caches from before the envelope was introduced hold the bare list
+    let legacy: serde_json::Value = serde_json::from_str(CACHED_RELEASES).unwrap();
+    fs::write(&cache_path, legacy["data"].to_string()).unwrap();
+    assert_eq!(lister.cache_age().unwrap(), None);
+}
+
+fn release(version: &str, build: &str) -> AndroidStudio {
+    AndroidStudio {
+        name: format!("Android Studio {version}"),
+        build: build.to_string(),
+        version: version.to_string(),
+        channel: "Release".to_string(),
+        platform_build: String::new(),
+        platform_version: String::new(),
+        date: String::new(),
+        downloads: Vec::new(),
+    }
+}
+
+/// Test that the limit applies after sorting newest first
+#[test]
+fn test_newest_first_limits_to_newest_versions() {
+    let temp = tempfile::tempdir().unwrap();
+    let lister = AndroidStudioLister::with_cache_dir(temp.path().to_path_buf()).unwrap();
+
+    let shuffled = vec![
+        release("2023.1.1", "AI-231.9392.1.2311.11076708"),
+        release("2024.10.1", "AI-2410.1.1.1.1"),
+        release("2022.3.1", "AI-223.8836.35.2231.10406996"),
+        release("2024.2.1", "AI-242.21829.142.2421.12409432"),
+        release("2024.2.1", "AI-242.23339.11.2421.12483815"),
+        release("2024.9.1", "AI-249.1.1.1.1"),
+    ];
+
+    let newest: Vec<_> = lister
+        .newest_first(shuffled, Some(3))
+        .into_iter()
+        .map(|item| (item.version, item.build))
+        .collect();
+
+    assert_eq!(
+        newest,
+        vec![
+            ("2024.10.1".to_string(), "AI-2410.1.1.1.1".to_string()),
+            ("2024.9.1".to_string(), "AI-249.1.1.1.1".to_string()),
+            (
+                "2024.2.1".to_string(),
+                "AI-242.23339.11.2421.12483815".to_string()
+            ),
+        ]
+    );
+}
+
+/// Test filtering releases to a major track given as a track or a full version
+#[test]
+fn test_filter_by_major_track() {
+    let temp = tempfile::tempdir().unwrap();
+    let lister = AndroidStudioLister::with_cache_dir(temp.path().to_path_buf()).unwrap();
+    let releases = || {
+        vec![
+            release("2024.2.1.11", "AI-242.23339.11.2421.12483815"),
+            release("2024.2.2.13", "AI-242.23726.103.2422.12816248"),
+            release("2024.1.2.12", "AI-241.18034.62.2412.12266719"),
+            release("2024.20.1", "AI-2420.1.1.1.1"),
+        ]
+    };
+
+    for track in ["2024.2", "2024.2.1"] {
+        let versions: Vec<_> = lister
+            .filter_by_major_track(releases(), track)
+            .into_iter()
+            .map(|item| item.version)
+            .collect();
+        assert_eq!(versions, vec!["2024.2.1.11", "2024.2.2.13"]);
+    }
+}
+
+/// Test filtering releases to an exact IntelliJ platform build
+#[test]
+fn test_filter_by_platform_build() {
+    let temp = tempfile::tempdir().unwrap();
+    let lister = AndroidStudioLister::with_cache_dir(temp.path().to_path_buf()).unwrap();
+    let on_platform = |version: &str, platform_build: &str| AndroidStudio {
+        platform_build: platform_build.to_string(),
+        ..release(version, &format!("AI-{version}"))
+    };
+    let releases = vec![
+        on_platform("2024.3.1.13", "243.22562.218"),
+        on_platform("2024.3.1.14", "243.22562.218"),
+        on_platform("2024.3.2.11", "243.22562.2180"),
+        on_platform("2024.2.1.11", "242.23339.11"),
+    ];
+
+    let versions: Vec<_> = lister
+        .filter_by_platform_build(releases, " 243.22562.218")
+        .into_iter()
+        .map(|item| item.version)
+        .collect();
+    assert_eq!(versions, vec!["2024.3.1.13", "2024.3.1.14"]);
+}
+
+/// Test that a refresh reports builds missing from the previous cache
+#[test]
+fn test_refresh_summary_new_releases() {
+    let previous = AndroidStudioReleasesList {
+        version: "1.0".to_string(),
+        items: vec![release("2024.2.1", "AI-242.23339.11.2421.12483815")],
+    };
+    let current = || AndroidStudioReleasesList {
+        version: "1.0".to_string(),
+        items: vec![
+            release("2024.3.1", "AI-243.22562.145.2431.12856000"),
+            release("2024.2.1", "AI-242.23339.11.2421.12483815"),
+        ],
+    };
+
+    let summary = RefreshSummary {
+        previous: Some(previous),
+        current: current(),
+    };
+    let new_builds: Vec<_> = summary
+        .new_releases()
+        .iter()
+        .map(|item| item.build.as_str())
+        .collect();
+    assert_eq!(new_builds, vec!["AI-243.22562.145.2431.12856000"]);
+
+    let first_fetch = RefreshSummary {
+        previous: None,
+        current: current(),
+    };
+    assert!(first_fetch.new_releases().is_empty());
+}
+
+/// Test query resolution for exact, unique, ambiguous and unknown queries
+#[test]
+fn test_find_matching_releases() {
+    let temp = tempfile::tempdir().unwrap();
+    let releases = AndroidStudioReleasesList {
+        version: "1.0".to_string(),
+        items: vec![
+            release("2024.2.1", "AI-242.21829.142.2421.12409432"),
+            release("2024.2.12", "AI-242.23339.11.2421.12483815"),
+            release("2024.3.1", "AI-243.22562.145.2431.12856000"),
+        ],
+    };
+    let envelope = serde_json::json!({
+        "schema": CACHE_SCHEMA,
+        "fetched_at": 0,
+        "data": releases,
+    });
+    fs::write(temp.path().join("releases.json"), envelope.to_string()).unwrap();
+    let lister = AndroidStudioLister::with_cache_dir(temp.path().to_path_buf())
+        .unwrap()
+        .with_max_age(CacheMaxAge::Never);
+
+    let versions: Vec<_> = lister
+        .find_all_matching("2024.2.1")
+        .unwrap()
+        .into_iter()
+        .map(|item| item.version)
+        .collect();
+    assert_eq!(versions, vec!["2024.2.1", "2024.2.12"]);
+
+    // An exact match wins over partial ones
+    assert_eq!(
+        lister.find_version_by_query("2024.2.1").unwrap().version,
+        "2024.2.1"
+    );
+    assert_eq!(
+        lister.find_version_by_query("2024.3").unwrap().version,
+        "2024.3.1"
+    );
+
+    // Ambiguous queries resolve to the newest match
+    assert_eq!(
+        lister.find_version_by_query("2024.2").unwrap().version,
+        "2024.2.12"
+    );
+    assert!(lister.find_version_by_query("1999.1").is_err());
+}
+
+/// Test that the queries used as examples in the CLI help resolve
+#[test]
+fn test_help_example_queries() {
+    let temp = tempfile::tempdir().unwrap();
+    let named = |name: &str, version: &str, build: &str| AndroidStudio {
+        name: format!("Android Studio {name}"),
+        ..release(version, build)
+    };
+    let releases = AndroidStudioReleasesList {
+        version: "1.0".to_string(),
+        items: vec![
+            named(
+                "Meerkat Feature Drop | 2024.3.2 Patch 1",
+                "2024.3.2.15",
+                "AI-243.26053.27.2432.13536105",
+            ),
+            named(
+                "Meerkat Feature Drop | 2024.3.2",
+                "2024.3.2.14",
+                "AI-243.26053.27.2432.13471170",
+            ),
+            named(
+                "Meerkat | 2024.3.1 Patch 1",
+                "2024.3.1.14",
+                "AI-243.24978.46.2431.13363775",
+            ),
+            named(
+                "Ladybug | 2024.2.1 Patch 2",
+                "2024.2.1.12",
+                "AI-242.23339.11.2421.12700392",
+            ),
+            named(
+                "Ladybug | 2024.2.1 Patch 1",
+                "2024.2.1.11",
+                "AI-242.23339.11.2421.12550806",
+            ),
+            named(
+                "Hedgehog | 2023.1.1 Patch 2",
+                "2023.1.1.28",
+                "AI-231.9392.1.2311.11330709",
+            ),
+            named(
+                "Hedgehog | 2023.1.1 Patch 1",
+                "2023.1.1.26",
+                "AI-231.9392.1.2311.11255304",
+            ),
+            named(
+                "Giraffe | 2022.3.1 Patch 4",
+                "2022.3.1.22",
+                "AI-223.8836.35.2231.11090377",
+            ),
+            named(
+                "Giraffe | 2022.3.1 Patch 3",
+                "2022.3.1.21",
+                "AI-223.8836.35.2231.11005911",
+            ),
+        ],
+    };
+    let envelope = serde_json::json!({
+        "schema": CACHE_SCHEMA,
+        "fetched_at": 0,
+        "data": releases,
+    });
+    fs::write(temp.path().join("releases.json"), envelope.to_string()).unwrap();
+    let lister = AndroidStudioLister::with_cache_dir(temp.path().to_path_buf())
+        .unwrap()
+        .with_max_age(CacheMaxAge::Never);
+
+    for (query, expected) in [
+        ("Hedgehog", "2023.1.1.28"),
+        ("2022.3.1", "2022.3.1.22"),
+        ("Ladybug", "2024.2.1.12"),
+        ("Android Studio Meerkat Feature Drop", "2024.3.2.15"),
+        ("2024.3", "2024.3.2.15"),
+        ("2024.2.1.11", "2024.2.1.11"),
+    ] {
+        assert_eq!(
+            lister.find_version_by_query(query).unwrap().version,
+            expected,
+            "query {query}"
+        );
+    }
+
+    // Install and download offer every match, newest first
+    let versions: Vec<_> = lister
+        .find_all_matching("hedgehog")
+        .unwrap()
+        .into_iter()
+        .map(|item| item.version)
+        .collect();
+    assert_eq!(versions, vec!["2023.1.1.28", "2023.1.1.26"]);
+}
+
+/// Test that the newest release is picked per channel by version, not by feed order
+#[test]
+fn test_latest_in_each_channel() {
+    let temp = tempfile::tempdir().unwrap();
+    let lister = AndroidStudioLister::with_cache_dir(temp.path().to_path_buf()).unwrap();
+
+    let canary = |version: &str, build: &str| AndroidStudio {
+        channel: "Canary".to_string(),
+        ..release(version, build)
+    };
+    let releases = vec![
+        release("2024.2.9", "AI-242.9"),
+        release("2024.2.10", "AI-242.10"),
+        canary("2024.3.1.5", "AI-243.5"),
+        release("2024.1.3", "AI-241.3"),
+        canary("2024.3.1.2", "AI-243.2"),
+    ];
+
+    let mut latest: Vec<_> = lister
+        .latest_in_each_channel(&releases)
+        .into_iter()
+        .collect();
+    latest.sort();
+    assert_eq!(latest, vec!["AI-242.10", "AI-243.5"]);
+}
@@ -0,0 +1,18 @@
+use astudios::lock::OperationLock;
+
+/// Test that a second operation fails fast while the lock is held
+#[test]
+fn test_operation_lock_is_exclusive() {
+    let temp = tempfile::tempdir().unwrap();
+    let lock_path = temp.path().join("astudios.lock");
+
+    let held = OperationLock::acquire_at(&lock_path, false).unwrap();
+    let err = OperationLock::acquire_at(&lock_path, false).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Another astudios operation is in progress")
+    );
+
+    drop(held);
+    assert!(OperationLock::acquire_at(&lock_path, false).is_ok());
+}
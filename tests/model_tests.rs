@@ -31,6 +31,7 @@ fn test_release_channels() {
         ReleaseChannel::Canary,
         ReleaseChannel::ReleaseCandidate,
         ReleaseChannel::Patch,
+        ReleaseChannel::FeatureDrop,
     ];
 
     // Since ReleaseChannel doesn't implement Serialize, we'll test the string representations
@@ -182,6 +183,113 @@ fn test_android_studio_version() {
     assert_yaml_snapshot!("android_studio_version_methods", version_info);
 }
 
+/// Test parsing a version from a bare build string
+#[test]
+fn test_version_from_build_string() {
+    let version =
+        AndroidStudioVersion::from_build_string(" AI-251.26094.121.2513.14007798\n").unwrap();
+    assert_eq!(
+        version,
+        AndroidStudioVersion::new(
+            "2025.1".to_string(),
+            "AI-251.26094.121.2513.14007798".to_string(),
+            "AI".to_string(),
+            "251.26094.121.2513.14007798".to_string(),
+            "Android Studio".to_string(),
+        )
+    );
+
+    // A bare build number is Android Studio's
+    let bare = AndroidStudioVersion::from_build_string("242.23339.11.2421.12483815").unwrap();
+    assert_eq!(bare.build_version, "AI-242.23339.11.2421.12483815");
+    assert_eq!(bare.short_version, "2024.2");
+
+    // Other products and branches keep what can be known
+    let other = AndroidStudioVersion::from_build_string("IC-2410.1.1").unwrap();
+    assert_eq!(other.product_code, "IC");
+    assert_eq!(other.short_version, "");
+    assert_eq!(other.product_name, "");
+
+    for malformed in [
+        "",
+        "AI-",
+        "AI-251",
+        "AI-251..121",
+        "AI-251.26094.x",
+        "ai-251.26094.121",
+        "-251.26094.121",
+        "Android Studio 2025.1",
+    ] {
+        assert!(
+            matches!(
+                AndroidStudioVersion::from_build_string(malformed),
+                Err(astudios::error::AstudiosError::Parse(_))
+            ),
+            "{malformed}"
+        );
+    }
+}
+
+/// Test reading architectures from `lipo -archs` and `file` output
+#[test]
+fn test_parse_architectures() {
+    assert_eq!(
+        parse_architectures("x86_64 arm64\n"),
+        vec!["x86_64", "arm64"]
+    );
+    assert_eq!(parse_architectures("arm64\n"), vec!["arm64"]);
+    assert_eq!(
+        parse_architectures(
+            "Mach-O universal binary with 2 architectures: [x86_64:Mach-O 64-bit executable x86_64] [arm64]\n"
+        ),
+        vec!["x86_64", "arm64"]
+    );
+    assert_eq!(
+        parse_architectures("Mach-O 64-bit executable x86_64\n"),
+        vec!["x86_64"]
+    );
+    assert!(parse_architectures("POSIX shell script, ASCII text executable\n").is_empty());
+
+    let intel_only = vec!["x86_64".to_string()];
+    assert_eq!(
+        needs_rosetta(&intel_only),
+        MacArch::host() == MacArch::Arm64
+    );
+    assert!(!needs_rosetta(&["x86_64".to_string(), "arm64".to_string()]));
+    assert!(!needs_rosetta(&[]));
+}
+
+/// Test extracting the major track from multi-segment version strings
+#[test]
+fn test_major_track() {
+    for (version, track) in [
+        ("2024.2", "2024.2"),
+        ("2024.2.1", "2024.2"),
+        ("2024.2.1.12", "2024.2"),
+        ("2024.10.1.3", "2024.10"),
+        ("2023.3.1 Canary 8", "2023.3"),
+        ("  2025.1.2.11 ", "2025.1"),
+        ("Ladybug", "Ladybug"),
+        ("2024", "2024"),
+        ("", ""),
+    ] {
+        assert_eq!(major_track(version), track, "{version:?}");
+    }
+
+    let version = AndroidStudioVersion::new(
+        "2024.2.1.12".to_string(),
+        "AI-242.23339.11.2421.12483815".to_string(),
+        "AI".to_string(),
+        "242.23339.11.2421.12483815".to_string(),
+        "Android Studio".to_string(),
+    );
+    assert_eq!(version.major_track(), "2024.2");
+
+    let mut tracks = vec!["2024.9", "2023.3", "2024.10"];
+    tracks.sort_by_key(|track| major_track_key(track));
+    assert_eq!(tracks, vec!["2023.3", "2024.9", "2024.10"]);
+}
+
 /// Test InstalledAndroidStudio display methods
 #[test]
 fn test_installed_android_studio_display() {
@@ -198,6 +306,7 @@ fn test_installed_android_studio_display() {
     let installed = InstalledAndroidStudio {
         path: PathBuf::from("/Applications/Android Studio Hedgehog.app"),
         version,
+        channel_override: None,
     };
 
     let display_info = vec![
@@ -218,6 +327,11 @@ fn test_channel_type_conversion() {
         ("Canary", "Canary"),
         ("RC", "RC"),
         ("Patch", "Patch"),
+        (" beta ", "Beta"),
+        ("CANARY", "Canary"),
+        ("Release Candidate", "RC"),
+        ("Feature Drop", "FeatureDrop"),
+        ("feature-drop", "FeatureDrop"),
         ("Unknown", "Release"), // Default fallback
     ];
 
@@ -240,3 +354,294 @@ fn test_channel_type_conversion() {
 
     assert_yaml_snapshot!("channel_type_conversion", results);
 }
+
+/// Test patch artifact detection and base build matching
+#[test]
+fn test_patch_release_detection() {
+    let patch = AndroidStudio {
+        name: "Android Studio Hedgehog | 2023.1.1 Patch 2".to_string(),
+        version: "2023.1.1.28".to_string(),
+        build: "AI-231.9392.1.2311.11330709".to_string(),
+        date: "2024-01-30".to_string(),
+        channel: "Patch".to_string(),
+        platform_build: "231.9392.1".to_string(),
+        platform_version: "2023.1.1".to_string(),
+        downloads: vec![
+            Download {
+                link: "https://example.com/AI-231.9392.1.2311.11076708-231.9392.1.2311.11330709-patch-mac.jar".to_string(),
+                size: "12 MB".to_string(),
+                checksum: "".to_string(),
+            },
+            Download {
+                link: "https://example.com/android-studio-2023.1.1.28-mac.dmg".to_string(),
+                size: "1.1 GB".to_string(),
+                checksum: "".to_string(),
+            },
+        ],
+    };
+
+    let version = |build: &str| {
+        AndroidStudioVersion::new(
+            "2023.1".to_string(),
+            build.to_string(),
+            "AI".to_string(),
+            build.trim_start_matches("AI-").to_string(),
+            "Android Studio".to_string(),
+        )
+    };
+
+    assert_eq!(patch.build_train(), "AI-231.9392.1.2311");
+    assert!(
+        patch
+            .get_macos_download()
+            .unwrap()
+            .link
+            .ends_with("mac.dmg")
+    );
+    assert!(patch.get_patch_download().unwrap().is_patch_artifact());
+    assert_eq!(
+        patch
+            .get_patch_download()
+            .unwrap()
+            .default_filename(&patch.version),
+        "android-studio-2023.1.1.28.jar"
+    );
+
+    assert!(patch.is_patch_of(&version("AI-231.9392.1.2311.11076708")));
+    assert!(!patch.is_patch_of(&version("AI-231.9392.1.2311.11330709")));
+    assert!(!patch.is_patch_of(&version("AI-231.9392.1.23111.1")));
+    assert!(!patch.is_patch_of(&version("AI-232.1234.5.2321.11234567")));
+}
+
+/// Test the error raised when a release has no download for the current platform
+#[test]
+fn test_platform_unavailable_errors() {
+    let download = |link: &str| Download {
+        link: link.to_string(),
+        size: "1 GB".to_string(),
+        checksum: "".to_string(),
+    };
+
+    let mut studio = AndroidStudio {
+        name: "Android Studio Preview".to_string(),
+        version: "2024.3.1.1".to_string(),
+        build: "AI-243.1.1.1".to_string(),
+        date: "2024-10-01".to_string(),
+        channel: "Canary".to_string(),
+        platform_build: "243.1.1".to_string(),
+        platform_version: "2024.3.1".to_string(),
+        downloads: vec![
+            download("https://example.com/android-studio-2024.3.1.1-linux.tar.gz"),
+            download("https://example.com/android-studio-2024.3.1.1-windows.zip"),
+            download("https://example.com/android-studio-2024.3.1.1-windows.exe"),
+        ],
+    };
+
+    assert_eq!(studio.available_platforms(), vec!["Linux", "Windows"]);
+    let linux_only = studio.require_platform_download().unwrap_err().to_string();
+
+    studio.downloads.clear();
+    let no_downloads = studio.require_platform_download().unwrap_err().to_string();
+
+    assert_yaml_snapshot!(
+        "platform_unavailable_errors",
+        vec![("linux_and_windows", linux_only), ("empty", no_downloads)]
+    );
+}
+
+/// Test macOS architecture detection and arch-preferred download selection
+#[test]
+fn test_macos_arch_selection() {
+    let studio = AndroidStudio {
+        name: "Android Studio Ladybug | 2024.2.1".to_string(),
+        version: "2024.2.1.11".to_string(),
+        build: "AI-242.23339.11.2421.12483815".to_string(),
+        date: "2024-10-01".to_string(),
+        channel: "Release".to_string(),
+        platform_build: "242.23339.11".to_string(),
+        platform_version: "2024.2.1".to_string(),
+        downloads: vec![
+            Download {
+                link: "https://example.com/android-studio-2024.2.1.11-mac.dmg".to_string(),
+                size: "1".to_string(),
+                checksum: "a".to_string(),
+            },
+            Download {
+                link: "https://example.com/android-studio-2024.2.1.11-mac_arm.dmg".to_string(),
+                size: "1".to_string(),
+                checksum: "b".to_string(),
+            },
+        ],
+    };
+
+    assert_eq!(studio.macos_arches(), vec![MacArch::X86_64, MacArch::Arm64]);
+    assert!(
+        studio
+            .get_macos_download_for(MacArch::Arm64)
+            .unwrap()
+            .link
+            .ends_with("mac_arm.dmg")
+    );
+    assert!(
+        studio
+            .get_macos_download_for(MacArch::X86_64)
+            .unwrap()
+            .link
+            .ends_with("-mac.dmg")
+    );
+}
+
+/// Test that release notes point at the page for the release's channel
+#[test]
+fn test_release_notes_url() {
+    let studio = |channel: &str| AndroidStudio {
+        name: "Android Studio Ladybug".to_string(),
+        version: "2024.2.1.11".to_string(),
+        build: "AI-242.23339.11.2421.12483815".to_string(),
+        date: "2024-10-01".to_string(),
+        channel: channel.to_string(),
+        platform_build: "242.23339.11".to_string(),
+        platform_version: "2024.2.1".to_string(),
+        downloads: vec![],
+    };
+
+    assert_eq!(
+        studio("Release").release_notes_url(),
+        "https://developer.android.com/studio/releases"
+    );
+    assert_eq!(
+        studio("Canary").release_notes_url(),
+        "https://developer.android.com/studio/preview/features"
+    );
+}
+
+/// Test parsing of the IntelliJ platform build into a comparable tuple
+#[test]
+fn test_platform_version_parsed() {
+    let with_platform_build = |platform_build: &str| AndroidStudio {
+        name: "Android Studio Ladybug".to_string(),
+        version: "2024.2.1.1".to_string(),
+        build: "AI-242.21829.142.2421.12409432".to_string(),
+        date: "2024-10-01".to_string(),
+        channel: "Release".to_string(),
+        platform_build: platform_build.to_string(),
+        platform_version: "2024.2.1".to_string(),
+        downloads: vec![],
+    };
+
+    let parsed =
+        |platform_build: &str| with_platform_build(platform_build).platform_version_parsed();
+    assert_eq!(parsed("242.21829.142"), Some((242, 21829, 142)));
+    assert_eq!(parsed("243"), Some((243, 0, 0)));
+    assert_eq!(parsed(""), None);
+    assert_eq!(parsed("EAP-SNAPSHOT"), None);
+    // Newer builds of the same branch compare greater, numerically
+    assert!(parsed("242.23339.11") > parsed("242.9999.1"));
+}
+
+/// Test validating and rendering bundle name templates
+#[test]
+fn test_bundle_name_template() {
+    let studio = |name: &str, channel: &str| AndroidStudio {
+        name: name.to_string(),
+        version: "2024.3.2.11".to_string(),
+        build: "AI-243.24978.46.2432.13026456".to_string(),
+        date: "2025-03-10".to_string(),
+        channel: channel.to_string(),
+        platform_build: "243.24978.46".to_string(),
+        platform_version: "2024.3".to_string(),
+        downloads: vec![],
+    };
+    let meerkat = studio(
+        "Android Studio Meerkat Feature Drop | 2024.3.2 Canary 1",
+        "Canary",
+    );
+    assert_eq!(meerkat.codename(), Some("Meerkat"));
+    assert_eq!(
+        studio("Android Studio 2024.3.2", "Release").codename(),
+        None
+    );
+
+    let render = |template: &str, release: &AndroidStudio| {
+        template
+            .parse::<BundleNameTemplate>()
+            .unwrap()
+            .render(release)
+    };
+    assert_eq!(
+        BundleNameTemplate::default().render(&meerkat),
+        "Android Studio 2024.3.2.11.app"
+    );
+    assert_eq!(
+        render(
+            "Android Studio {codename} {channel} {version}.app",
+            &meerkat
+        ),
+        "Android Studio Meerkat Canary 2024.3.2.11.app"
+    );
+    assert_eq!(
+        render("Android Studio-{build}.app", &meerkat),
+        "Android Studio-AI-243.24978.46.2432.13026456.app"
+    );
+    // A missing codename does not leave a double space behind
+    assert_eq!(
+        render(
+            "Android Studio {codename} {version}.app",
+            &studio("Android Studio 2024.3.2", "Release")
+        ),
+        "Android Studio 2024.3.2.11.app"
+    );
+
+    for invalid in [
+        "Android Studio {channel}.app",
+        "Android Studio {version}",
+        "Studios/{version}.app",
+        "Android Studio {release}.app",
+        "Android Studio {version.app",
+    ] {
+        assert!(
+            invalid.parse::<BundleNameTemplate>().is_err(),
+            "{invalid} should be rejected"
+        );
+    }
+}
+
+/// Test flagging releases on either side of the outdated threshold
+#[test]
+fn test_is_outdated() {
+    let released_on = |date: &str| AndroidStudio {
+        name: "Android Studio Ladybug".to_string(),
+        version: "2024.2.1.11".to_string(),
+        build: "AI-242.23339.11.2421.12483815".to_string(),
+        date: date.to_string(),
+        channel: "Release".to_string(),
+        platform_build: "242.23339.11".to_string(),
+        platform_version: "2024.2.1".to_string(),
+        downloads: vec![],
+    };
+
+    // 18 months before 2026-04-01 is 2024-10-01
+    assert!(released_on("2024-10-01").is_outdated(18, "2026-04-01"));
+    assert!(released_on("2024-09-30").is_outdated(18, "2026-04-01"));
+    assert!(!released_on("2024-10-02").is_outdated(18, "2026-04-01"));
+    assert!(!released_on("2025-06-15").is_outdated(18, "2026-04-01"));
+    // The feed's long date format counts too
+    assert!(released_on("October 1, 2024").is_outdated(18, "2026-04-01"));
+    assert!(!released_on("October 2, 2024").is_outdated(18, "2026-04-01"));
+    // A threshold of 0 disables the check, and unknown dates are never outdated
+    assert!(!released_on("2010-01-01").is_outdated(0, "2026-04-01"));
+    assert!(!released_on("unknown").is_outdated(18, "2026-04-01"));
+}
+
+/// Test counting whole months between dates
+#[test]
+fn test_months_between() {
+    assert_eq!(months_between("2024-01-31", "2024-02-29"), Some(0));
+    assert_eq!(months_between("2024-01-31", "2024-03-01"), Some(1));
+    assert_eq!(months_between("2023-12-15", "2025-06-15"), Some(18));
+    assert_eq!(months_between("2024-13-01", "2025-01-01"), None);
+
+    let today = today();
+    assert_eq!(today.len(), 10);
+    assert_eq!(months_between(&today, &today), Some(0));
+}
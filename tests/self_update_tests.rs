@@ -0,0 +1,51 @@
+use astudios::self_update::{SelfUpdateCheck, is_newer, parse_latest_version};
+use std::{fs, time::Duration};
+
+/// Test version comparison against the running version
+#[test]
+fn test_is_newer() {
+    assert!(is_newer("0.1.4", "0.1.3"));
+    assert!(is_newer("0.10.0", "0.9.9"));
+    assert!(is_newer("1.0.0", "0.1.3"));
+    assert!(!is_newer("0.1.3", "0.1.3"));
+    assert!(!is_newer("0.1.2", "0.1.3"));
+    assert!(!is_newer("0.1.3-beta.1", "0.1.3"));
+}
+
+/// Test reading the latest version from crates.io and GitHub responses
+#[test]
+fn test_parse_latest_version() {
+    let crates_io = r#"{"crate": {"name": "astudios", "max_version": "0.3.0-rc.1", "max_stable_version": "0.2.0"}}"#;
+    assert_eq!(parse_latest_version(crates_io).as_deref(), Some("0.2.0"));
+
+    let github = r#"{"tag_name": "v0.2.1", "name": "astudios 0.2.1"}"#;
+    assert_eq!(parse_latest_version(github).as_deref(), Some("0.2.1"));
+
+    assert_eq!(parse_latest_version("<html></html>"), None);
+    assert_eq!(parse_latest_version(r#"{"message": "Not Found"}"#), None);
+}
+
+/// Test that a recent cached result is used without querying the network
+#[test]
+fn test_cached_result_is_used() {
+    let temp = tempfile::tempdir().unwrap();
+    let cache_path = temp.path().join("self-update.json");
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    fs::write(
+        &cache_path,
+        format!(r#"{{"checked_at": {now}, "latest": "9.0.0"}}"#),
+    )
+    .unwrap();
+
+    // The URL is unreachable, so only the cache can answer
+    let check = SelfUpdateCheck::new(
+        "http://127.0.0.1:9/unreachable".to_string(),
+        cache_path,
+        Duration::from_secs(60 * 60),
+    );
+    assert_eq!(check.newer_version("0.1.3").as_deref(), Some("9.0.0"));
+    assert_eq!(check.newer_version("9.0.0"), None);
+}
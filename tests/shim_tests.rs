@@ -0,0 +1,39 @@
+use astudios::shim::{SHIM_NAME, Shim};
+use std::{fs, os::unix::fs::PermissionsExt, path::Path};
+
+/// Test installing and removing the shim
+#[test]
+fn test_shim_install_and_uninstall() {
+    let temp = tempfile::tempdir().unwrap();
+    let bin_dir = temp.path().join("bin");
+    let shim = Shim::in_dir(&bin_dir);
+    assert_eq!(shim.path(), bin_dir.join(SHIM_NAME));
+    assert!(!shim.is_installed());
+
+    shim.install(Path::new("/opt/it's here/astudios")).unwrap();
+    assert!(shim.is_installed());
+    let script = fs::read_to_string(shim.path()).unwrap();
+    assert!(script.starts_with("#!/bin/sh\n"));
+    assert!(script.contains(r"exec '/opt/it'\''s here/astudios' shim --exec -- "));
+    let mode = fs::metadata(shim.path()).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o755);
+
+    // Reinstalling over our own shim is allowed
+    shim.install(Path::new("/usr/local/bin/astudios")).unwrap();
+
+    assert!(shim.uninstall().unwrap());
+    assert!(!shim.path().exists());
+    assert!(!shim.uninstall().unwrap());
+}
+
+/// Test that a foreign `studio` command is never overwritten or removed
+#[test]
+fn test_shim_leaves_foreign_files_alone() {
+    let temp = tempfile::tempdir().unwrap();
+    let shim = Shim::in_dir(temp.path());
+    fs::write(shim.path(), "#!/bin/sh\necho someone else\n").unwrap();
+
+    assert!(shim.install(Path::new("/usr/local/bin/astudios")).is_err());
+    assert!(!shim.uninstall().unwrap());
+    assert!(shim.path().exists());
+}
@@ -0,0 +1,31 @@
+use astudios::{json_file::JsonFile, sizes::SizeCache};
+use std::fs;
+
+/// Test that bundle sizes are reused until the bundle's modification time changes
+#[test]
+fn test_size_cache() {
+    let temp = tempfile::tempdir().unwrap();
+    let bundle = temp.path().join("Android Studio.app");
+    fs::create_dir_all(bundle.join("Contents")).unwrap();
+    fs::write(bundle.join("Contents/Info.plist"), vec![0u8; 100]).unwrap();
+
+    let mut cache = SizeCache::default();
+    assert_eq!(cache.size_of(&bundle), 100);
+
+    // A stale size is returned as long as the recorded mtime matches
+    cache.bundles.get_mut(&bundle).unwrap().size_bytes = 42;
+    assert_eq!(cache.size_of(&bundle), 42);
+
+    cache.bundles.get_mut(&bundle).unwrap().modified = 0;
+    assert_eq!(cache.size_of(&bundle), 100);
+
+    let path = temp.path().join("bundle-sizes.json");
+    cache.save_to(&path).unwrap();
+    assert_eq!(SizeCache::load_from(&path).unwrap(), cache);
+
+    fs::remove_dir_all(&bundle).unwrap();
+    cache.retain_existing();
+    assert!(cache.bundles.is_empty());
+    fs::write(&path, "not json").unwrap();
+    assert_eq!(SizeCache::load_from(&path).unwrap(), SizeCache::default());
+}
@@ -0,0 +1,97 @@
+use astudios::json_file::JsonFile;
+use astudios::state::{InstallCheckpoint, InstallRecord, State};
+use std::fs;
+
+/// Test that install records round-trip and unverified installs are reported
+#[test]
+fn test_unverified_installs() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("state.json");
+    assert_eq!(State::load_from(&path).unwrap(), State::default());
+
+    let verified = temp.path().join("Android Studio 2024.2.app");
+    let unverified = temp.path().join("Android Studio 2024.3.app");
+    let removed = temp.path().join("Android Studio 2024.1.app");
+    fs::create_dir_all(&verified).unwrap();
+    fs::create_dir_all(&unverified).unwrap();
+
+    let mut state = State::default();
+    for (app_path, version, is_verified) in [
+        (&verified, "2024.2.1", true),
+        (&unverified, "2024.3.1", false),
+        (&removed, "2024.1.1", false),
+    ] {
+        state.record_install(
+            app_path.clone(),
+            InstallRecord {
+                version: version.to_string(),
+                verified: is_verified,
+            },
+        );
+    }
+    state.save_to(&path).unwrap();
+
+    let mut loaded = State::load_from(&path).unwrap();
+    assert_eq!(loaded, state);
+    // Bundles deleted behind astudios' back are not reported
+    assert_eq!(loaded.unverified_installs(), vec![unverified.as_path()]);
+
+    assert!(loaded.remove_install(&unverified).is_some());
+    assert!(loaded.unverified_installs().is_empty());
+}
+
+/// Test that channel labels are stored, cleared, and dropped with the install
+#[test]
+fn test_channel_overrides() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("state.json");
+    let app_path = temp.path().join("Android Studio Preview.app");
+
+    let mut state = State::default();
+    state.set_channel_override(app_path.clone(), Some("Canary".to_string()));
+    state.save_to(&path).unwrap();
+
+    let mut loaded = State::load_from(&path).unwrap();
+    assert_eq!(loaded.channel_override(&app_path), Some("Canary"));
+
+    loaded.set_channel_override(app_path.clone(), None);
+    assert_eq!(loaded.channel_override(&app_path), None);
+
+    loaded.set_channel_override(app_path.clone(), Some("Beta".to_string()));
+    loaded.remove_install(&app_path);
+    assert_eq!(loaded.channel_override(&app_path), None);
+}
+
+/// Test that an install checkpoint resumes only for the same versions
+#[test]
+fn test_install_checkpoint_resume() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("install-checkpoint.json");
+    let versions: Vec<String> = ["2024.1.1", "2024.2.1", "2024.3.1"]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut checkpoint = InstallCheckpoint::resume(&path, &versions);
+    assert_eq!(checkpoint.remaining(), versions);
+    checkpoint.mark_completed("2024.1.1");
+    checkpoint.mark_completed("2024.1.1");
+    checkpoint.save_to(&path).unwrap();
+
+    let resumed = InstallCheckpoint::resume(&path, &versions);
+    assert_eq!(resumed.completed, vec!["2024.1.1".to_string()]);
+    assert_eq!(resumed.remaining(), versions[1..].to_vec());
+
+    // A version uninstalled since is installed again
+    let mut uninstalled = resumed.clone();
+    uninstalled.retain_installed(|version| version != "2024.1.1");
+    assert_eq!(uninstalled.remaining(), versions);
+
+    // A different request starts over
+    let other = InstallCheckpoint::resume(&path, &versions[1..]);
+    assert_eq!(other.remaining(), versions[1..].to_vec());
+
+    InstallCheckpoint::clear(&path).unwrap();
+    assert!(!path.exists());
+    InstallCheckpoint::clear(&path).unwrap();
+}